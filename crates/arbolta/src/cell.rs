@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: MIT
 
 use crate::bit::Bit;
-use crate::signal::{AccessSignal, SignalIndex, SignalList};
+use crate::module::model::ModuleModel;
+use crate::signal::{AccessSignal, SignalIndex, SignalIndexList, SignalList};
+use dyn_clone::DynClone;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::Debug;
 use thiserror::Error;
 
 pub const CONNECTION_SIZE: usize = 8;
@@ -31,6 +34,10 @@ pub struct CellInfo {
   pub function: Function,
   pub area: f64,
   pub num_inputs: usize,
+  /// Pin names in connection order, output last. Only needed to export modules that weren't
+  /// elaborated from a netlist, which records them, see `HardwareModule::cell_pins`.
+  #[serde(default)]
+  pub pins: Vec<String>,
 }
 
 /// Proxy for a standard-cell and basic unit of 'compute'.
@@ -50,6 +57,100 @@ pub struct Cell {
   pub output_connection: SignalIndex,
 }
 
+/// User-defined cell behavior, registered in a `CellLibrary`.
+/// Implementations must be tagged with `#[typetag::serde]` so designs can be saved and loaded.
+#[typetag::serde]
+pub trait CellBehavior: Debug + DynClone + Send + Sync {
+  /// Compute output bits from input bits. Pins are flattened (LSB first) in the order
+  /// given by `BehaviorInfo`.
+  fn eval(&mut self, inputs: &[Bit], outputs: &mut [Bit]);
+
+  /// Clear any state held by the behavior.
+  fn reset(&mut self) {}
+
+  /// Behavior-specific statistics (ex, lookups, hits).
+  fn get_statistics(&self) -> HashMap<String, usize> {
+    HashMap::new()
+  }
+}
+
+dyn_clone::clone_trait_object!(CellBehavior);
+
+/// Library entry for a user-defined cell.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BehaviorInfo {
+  pub name: String,
+  pub area: f64,
+  /// Input pin names, in the order bits are passed to `CellBehavior::eval`.
+  pub inputs: Vec<String>,
+  /// Output pin names, in the order bits are returned from `CellBehavior::eval`.
+  pub outputs: Vec<String>,
+  /// Behavior each instance is cloned from.
+  pub behavior: Box<dyn CellBehavior>,
+}
+
+/// Instance of a user-defined cell.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BehaviorCell {
+  /// Name of cell.
+  pub name: String,
+  /// Input signal indices, all pins flattened.
+  pub inputs: SignalIndexList,
+  /// Output signal indices, all pins flattened.
+  pub outputs: SignalIndexList,
+  /// Width of each input pin then each output pin, empty when not known.
+  #[serde(default)]
+  pub widths: Vec<usize>,
+  pub behavior: Box<dyn CellBehavior>,
+  #[serde(skip)]
+  input_bits: Vec<Bit>,
+  #[serde(skip)]
+  output_bits: Vec<Bit>,
+}
+
+/// Behaviors can't be compared, only the cell and its connections are.
+impl PartialEq for BehaviorCell {
+  fn eq(&self, other: &Self) -> bool {
+    self.name == other.name && self.inputs == other.inputs && self.outputs == other.outputs
+  }
+}
+
+impl BehaviorCell {
+  pub fn new(info: &BehaviorInfo, inputs: SignalIndexList, outputs: SignalIndexList) -> Self {
+    Self {
+      name: info.name.clone(),
+      input_bits: vec![Bit::Zero; inputs.len()],
+      output_bits: vec![Bit::Zero; outputs.len()],
+      inputs,
+      outputs,
+      widths: vec![],
+      behavior: info.behavior.clone(),
+    }
+  }
+
+  pub fn eval(&mut self, signals: &mut SignalList) {
+    // Scratch buffers are skipped when serializing
+    self.input_bits.resize(self.inputs.len(), Bit::Zero);
+    self.output_bits.resize(self.outputs.len(), Bit::Zero);
+
+    for (bit, idx) in self.input_bits.iter_mut().zip(&self.inputs) {
+      *bit = signals[*idx].get_value();
+    }
+    self.behavior.eval(&self.input_bits, &mut self.output_bits);
+    for (bit, idx) in self.output_bits.iter().zip(&self.outputs) {
+      signals[*idx].set_value(*bit);
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.behavior.reset();
+  }
+
+  pub fn get_statistics(&self) -> HashMap<String, usize> {
+    self.behavior.get_statistics()
+  }
+}
+
 #[derive(Debug, Error)]
 pub enum CellError {
   #[error("couldn't find cell `{0}`")]
@@ -80,6 +181,12 @@ impl From<CellInfo> for Cell {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CellLibrary {
   pub cells: HashMap<String, CellInfo>,
+  /// User-defined cells.
+  #[serde(default)]
+  pub behaviors: HashMap<String, BehaviorInfo>,
+  /// Behavioral models, keyed by the module name they replace.
+  #[serde(default)]
+  pub models: HashMap<String, Box<dyn ModuleModel>>,
 }
 
 impl CellLibrary {
@@ -93,10 +200,22 @@ impl CellLibrary {
     }
   }
 
+  /// Register a user-defined cell, replacing any cell with the same name.
+  pub fn register_behavior(&mut self, info: BehaviorInfo) {
+    self.cells.remove(&info.name);
+    self.behaviors.insert(info.name.clone(), info);
+  }
+
+  /// Register a behavioral model used in place of module `module_name`.
+  pub fn register_model(&mut self, module_name: &str, model: Box<dyn ModuleModel>) {
+    self.models.insert(module_name.to_string(), model);
+  }
+
   pub fn get_cell_area(&self, cell_name: &str) -> Result<f64, CellError> {
-    match self.cells.get(cell_name) {
-      Some(cell_info) => Ok(cell_info.area),
-      None => Err(CellError::NotFound(cell_name.to_string())),
+    match (self.cells.get(cell_name), self.behaviors.get(cell_name)) {
+      (Some(cell_info), _) => Ok(cell_info.area),
+      (None, Some(behavior_info)) => Ok(behavior_info.area),
+      (None, None) => Err(CellError::NotFound(cell_name.to_string())),
     }
   }
 
@@ -193,23 +312,70 @@ impl Cell {
         output_bit = !output_bit;
       }
       Function::DffPosEdge => {
-        let (clock, data) = (
-          signals[self.input_connections[0]].get_value(),
-          signals[self.input_connections[1]].get_value(),
-        );
-        let (last_data, last_clock) = (self.state[0], self.state[1]);
-        // Detect rising edge, clock new data
-        output_bit = if clock == Bit::One && last_clock == Bit::Zero {
-          data
-        } else {
-          last_data
-        };
-        self.state = [output_bit, clock];
+        self.sample(signals);
+        output_bit = self.state[0];
       }
     };
     signals[self.output_connection].set_value(output_bit);
   }
 
+  pub fn is_sequential(&self) -> bool {
+    self.function == Function::DffPosEdge
+  }
+
+  /// Evaluate a combinational cell on 64 independent lanes, bit `i` of each value is lane `i`.
+  pub fn eval_lanes(&self, lanes: &[u64]) -> u64 {
+    let first = lanes[self.input_connections[0]];
+    let rest = self.input_connections[1..self.num_inputs]
+      .iter()
+      .map(|idx| lanes[*idx]);
+    match &self.function {
+      Function::Buf => first,
+      Function::Inverter => !first,
+      Function::And => rest.fold(first, |acc, lane| acc & lane),
+      Function::Or => rest.fold(first, |acc, lane| acc | lane),
+      Function::Nor => !rest.fold(first, |acc, lane| acc | lane),
+      Function::Nand => !rest.fold(first, |acc, lane| acc & lane),
+      Function::Xor => rest.fold(first, |acc, lane| acc ^ lane),
+      Function::Xnor => !rest.fold(first, |acc, lane| acc ^ lane),
+      Function::DffPosEdge => unreachable!("flip-flops are sampled with `sample_lanes`"),
+    }
+  }
+
+  /// Clock new data into a flip-flop on 64 lanes, `state` holds the stored data and last clock.
+  pub fn sample_lanes(&self, lanes: &[u64], state: &mut [u64; STATE_SIZE]) {
+    let (clock, data) = (
+      lanes[self.input_connections[0]],
+      lanes[self.input_connections[1]],
+    );
+    // Rising edge lanes clock new data
+    let edge = clock & !state[1];
+    *state = [(data & edge) | (state[0] & !edge), clock];
+  }
+
+  /// Clock new data into a flip-flop without writing its output, see `commit`.
+  pub fn sample(&mut self, signals: &SignalList) {
+    let (clock, data) = (
+      signals[self.input_connections[0]].get_value(),
+      signals[self.input_connections[1]].get_value(),
+    );
+    let (last_data, last_clock) = (self.state[0], self.state[1]);
+    // Detect rising edge, clock new data
+    let output_bit = if clock == Bit::One && last_clock == Bit::Zero {
+      data
+    } else {
+      last_data
+    };
+    self.state = [output_bit, clock];
+  }
+
+  /// Write the stored value of a flip-flop to its output, returns if it changed.
+  pub fn commit(&self, signals: &mut SignalList) -> bool {
+    let changed = signals[self.output_connection].get_value() != self.state[0];
+    signals[self.output_connection].set_value(self.state[0]);
+    changed
+  }
+
   pub fn reset(&mut self) {
     if self.function == Function::DffPosEdge {
       self.state = [Bit::Zero; 2]
@@ -217,6 +383,10 @@ impl Cell {
   }
 }
 
+fn pin_names(pins: &[&str]) -> Vec<String> {
+  pins.iter().map(|pin| pin.to_string()).collect()
+}
+
 pub fn default_cell_library() -> CellLibrary {
   let cells = HashMap::from([
     (
@@ -226,6 +396,7 @@ pub fn default_cell_library() -> CellLibrary {
         function: Function::Buf,
         area: 4.0,
         num_inputs: 1,
+        pins: pin_names(&["A", "Y"]),
       },
     ),
     (
@@ -235,6 +406,7 @@ pub fn default_cell_library() -> CellLibrary {
         function: Function::Inverter,
         area: 2.0,
         num_inputs: 1,
+        pins: pin_names(&["A", "Y"]),
       },
     ),
     (
@@ -244,6 +416,7 @@ pub fn default_cell_library() -> CellLibrary {
         function: Function::Nand,
         area: 4.0,
         num_inputs: 2,
+        pins: pin_names(&["A", "B", "Y"]),
       },
     ),
     (
@@ -253,6 +426,7 @@ pub fn default_cell_library() -> CellLibrary {
         function: Function::Nor,
         area: 4.0,
         num_inputs: 2,
+        pins: pin_names(&["A", "B", "Y"]),
       },
     ),
     (
@@ -262,9 +436,14 @@ pub fn default_cell_library() -> CellLibrary {
         function: Function::DffPosEdge,
         area: 8.0,
         num_inputs: 2,
+        pins: pin_names(&["C", "D", "Q"]),
       },
     ),
   ]);
 
-  CellLibrary { cells }
+  CellLibrary {
+    cells,
+    behaviors: HashMap::new(),
+    models: HashMap::new(),
+  }
 }
@@ -4,5 +4,6 @@
 pub mod bit;
 pub mod cell;
 pub mod module;
+pub mod primitive;
 pub mod signal;
 pub mod synth;
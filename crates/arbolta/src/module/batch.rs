@@ -0,0 +1,202 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::report::HierarchyReport;
+use crate::signal::AccessSignal;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Independent stimulus sequence, simulated from a reset design.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stimulus {
+  /// Values written before each evaluation, by port name or hierarchical path.
+  pub steps: Vec<Vec<(String, u128)>>,
+}
+
+/// Simulates independent stimulus sequences on a pool of threads, each with its own copy of
+/// the design. Results don't depend on the number of threads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchRunner {
+  /// Worker threads, all available cores when 0.
+  pub threads: usize,
+  /// Pulse the clock after writing each step, instead of a single `eval`.
+  pub clocked: bool,
+  /// Run `reset_clocked` at the start of each sequence.
+  pub reset: bool,
+  /// Ports or hierarchical paths read after each step.
+  pub outputs: Vec<String>,
+}
+
+/// Outputs of every sequence and statistics summed over all of them.
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+  /// Values of `BatchRunner::outputs` after each step, by sequence then step.
+  pub outputs: Vec<Vec<Vec<u128>>>,
+  /// Reset copy of the design holding toggle counts summed over all sequences.
+  pub design: Design,
+}
+
+/// Results of one worker thread, by sequence index.
+#[derive(Default)]
+struct Worker {
+  outputs: Vec<(usize, Vec<Vec<u128>>)>,
+  /// Rising and falling toggles of every signal, see `HardwareModule::get_toggle_counts`.
+  toggles: Vec<(usize, usize)>,
+  error: Option<(usize, DesignError)>,
+}
+
+/// Add toggle counts from `HardwareModule::get_toggle_counts` to `sum`.
+fn add_toggle_counts(sum: &mut Vec<(usize, usize)>, toggles: &[(usize, usize)]) {
+  sum.resize(sum.len().max(toggles.len()), (0, 0));
+  for (sum, (rising, falling)) in sum.iter_mut().zip(toggles) {
+    sum.0 += rising;
+    sum.1 += falling;
+  }
+}
+
+impl BatchRunner {
+  pub fn new(threads: usize) -> Self {
+    Self {
+      threads,
+      ..Default::default()
+    }
+  }
+
+  /// Simulate every sequence on a copy of `design`. Fails with the error of the first failing
+  /// sequence.
+  pub fn run(&self, design: &Design, stimuli: &[Stimulus]) -> Result<BatchResult, DesignError> {
+    let threads = match self.threads {
+      0 => std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+      threads => threads,
+    }
+    .clamp(1, stimuli.len().max(1));
+
+    let next = AtomicUsize::new(0);
+    let workers: Vec<Worker> = std::thread::scope(|scope| {
+      let handles: Vec<_> = (0..threads)
+        .map(|_| scope.spawn(|| self.run_worker(design, stimuli, &next)))
+        .collect();
+      handles
+        .into_iter()
+        .map(|handle| handle.join().expect("batch worker panicked"))
+        .collect()
+    });
+
+    // Merge in sequence order
+    let mut outputs = vec![vec![]; stimuli.len()];
+    let mut toggles = vec![];
+    let mut error: Option<(usize, DesignError)> = None;
+    for worker in workers {
+      for (idx, sequence_outputs) in worker.outputs {
+        outputs[idx] = sequence_outputs;
+      }
+      add_toggle_counts(&mut toggles, &worker.toggles);
+      if let Some((idx, err)) = worker.error {
+        if error.as_ref().is_none_or(|(first, _)| idx < *first) {
+          error = Some((idx, err));
+        }
+      }
+    }
+    if let Some((_, err)) = error {
+      return Err(err);
+    }
+
+    let mut merged = design.clone();
+    merged.module.reset();
+    merged.module.set_toggle_counts(&toggles);
+    Ok(BatchResult {
+      outputs,
+      design: merged,
+    })
+  }
+
+  /// Take sequences until none are left, reusing one copy of the design.
+  fn run_worker(&self, design: &Design, stimuli: &[Stimulus], next: &AtomicUsize) -> Worker {
+    let mut design = design.clone();
+    let mut worker = Worker::default();
+    loop {
+      let idx = next.fetch_add(1, Ordering::Relaxed);
+      let Some(stimulus) = stimuli.get(idx) else {
+        break;
+      };
+      match self.run_sequence(&mut design, stimulus) {
+        Ok(outputs) => worker.outputs.push((idx, outputs)),
+        Err(err) => {
+          worker.error = Some((idx, err));
+          break;
+        }
+      }
+      add_toggle_counts(&mut worker.toggles, &design.module.get_toggle_counts());
+    }
+    worker
+  }
+
+  fn run_sequence(
+    &self,
+    design: &mut Design,
+    stimulus: &Stimulus,
+  ) -> Result<Vec<Vec<u128>>, DesignError> {
+    design.module.reset();
+    if self.reset {
+      design.reset_clocked()?;
+      design.module.take_eval_error()?;
+    }
+
+    let mut outputs = Vec::with_capacity(stimulus.steps.len());
+    for step in &stimulus.steps {
+      for (path, val) in step {
+        design.module.set_path_int(path, *val)?;
+      }
+      match self.clocked {
+        true => design.eval_clocked()?,
+        false => design.eval(),
+      }
+      design.module.take_eval_error()?;
+      outputs.push(
+        self
+          .outputs
+          .iter()
+          .map(|path| design.module.get_path_int(path))
+          .collect::<Result<_, _>>()?,
+      );
+    }
+    Ok(outputs)
+  }
+}
+
+impl BatchResult {
+  pub fn get_total_toggle_count(&self) -> usize {
+    self.design.module.get_total_toggle_count()
+  }
+
+  /// Total toggles per unit of top module area, zero without area.
+  pub fn get_toggles_per_area(&self) -> Result<f64, DesignError> {
+    let area = self.design.get_module_area(&self.design.module.name)?;
+    match area > 0.0 {
+      true => Ok(self.get_total_toggle_count() as f64 / area),
+      false => Ok(0.0),
+    }
+  }
+
+  /// Total toggles of each top-level port.
+  pub fn get_port_toggle_counts(&self) -> BTreeMap<String, usize> {
+    let module = &self.design.module;
+    module
+      .ports
+      .iter()
+      .map(|(name, port)| {
+        let toggles = port
+          .signal_idx_list
+          .iter()
+          .map(|idx| module.signals[*idx].get_total_toggle_count())
+          .sum();
+        (name.clone(), toggles)
+      })
+      .collect()
+  }
+
+  pub fn get_hierarchy_report(&self) -> HierarchyReport {
+    self.design.get_hierarchy_report()
+  }
+}
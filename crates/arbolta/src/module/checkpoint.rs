@@ -0,0 +1,339 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::hardware_module::{Component, HardwareModule};
+use super::model::ModuleModel;
+use crate::bit::Bit;
+use crate::cell::{CellBehavior, STATE_SIZE};
+use crate::signal::{AccessSignal, Signal};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Dynamic state of a design, without its structure. Signals, cells and tri-state nets are
+/// listed module by module, each module followed by its submodules in component order.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Checkpoint {
+  /// Clock cycles run, see `Design::cycles`.
+  pub cycles: usize,
+  /// Simulated time, see `Design::time`.
+  #[serde(default)]
+  pub time: u64,
+  /// Rising edges and toggles of each clock in `Design::clocks`.
+  #[serde(default)]
+  pub clocks: Vec<(usize, usize)>,
+  /// Value of every signal.
+  pub values: Vec<Bit>,
+  /// Rising and falling toggles of every signal.
+  pub toggles: Vec<(usize, usize)>,
+  /// State of every basic cell.
+  pub cells: Vec<[Bit; STATE_SIZE]>,
+  /// State of every other cell (memories, word cells, models, ...).
+  pub components: Vec<ComponentState>,
+  pub tristate_nets: Vec<TristateNetState>,
+}
+
+/// External, internal and resolved value and contention count of a tri-state net.
+pub type TristateNetState = (Option<Bit>, Option<Bit>, Option<Bit>, usize);
+
+/// State of a component that isn't a basic cell.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum ComponentState {
+  /// Behavior of a user-defined cell, holding whatever state it keeps.
+  Behavior(Box<dyn CellBehavior>),
+  /// Stored value and last clock of a word-level flip-flop.
+  Word(Vec<Bit>, Bit),
+  Memory(MemoryState),
+  /// Stored value and last clock of a Xilinx flip-flop.
+  Xilinx(Bit, Bit),
+  /// Value driven on each output of a tri-state buffer.
+  Tristate(Vec<Option<Bit>>),
+  /// Behavioral model. Models (ex, Python callables) may not be serializable, so they're only
+  /// kept in memory and a loaded checkpoint leaves models as they are.
+  Model(#[serde(skip)] Option<Box<dyn ModuleModel>>),
+}
+
+/// Contents and port registers of a memory.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct MemoryState {
+  pub contents: Vec<Bit>,
+  pub read_ports: Vec<ReadPortState>,
+  pub write_ports: Vec<WritePortState>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ReadPortState {
+  pub state: Vec<Bit>,
+  pub last_clock: Bit,
+  pub last_addr: Option<usize>,
+  pub reads: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WritePortState {
+  pub last_clock: Bit,
+  pub last_write: Option<(usize, Vec<Bit>)>,
+  pub writes: usize,
+}
+
+/// Behaviors and models can't be compared, only the kind of component is.
+impl PartialEq for ComponentState {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Self::Behavior(_), Self::Behavior(_)) | (Self::Model(_), Self::Model(_)) => true,
+      (Self::Word(state, last_clock), Self::Word(other_state, other_clock)) => {
+        (state, last_clock) == (other_state, other_clock)
+      }
+      (Self::Memory(state), Self::Memory(other)) => state == other,
+      (Self::Xilinx(state, last_clock), Self::Xilinx(other_state, other_clock)) => {
+        (state, last_clock) == (other_state, other_clock)
+      }
+      (Self::Tristate(drive), Self::Tristate(other)) => drive == other,
+      _ => false,
+    }
+  }
+}
+
+impl ComponentState {
+  /// State of a component, `None` for basic cells and submodules.
+  fn new(component: &Component) -> Option<Self> {
+    let state = match component {
+      Component::Cell(_) | Component::Module(_) => return None,
+      Component::Behavior(cell) => Self::Behavior(cell.behavior.clone()),
+      Component::Word(cell) => Self::Word(cell.state.clone(), cell.last_clock),
+      Component::Memory(memory) => Self::Memory(MemoryState {
+        contents: memory.contents.clone(),
+        read_ports: memory
+          .read_ports
+          .iter()
+          .map(|port| ReadPortState {
+            state: port.state.clone(),
+            last_clock: port.last_clock,
+            last_addr: port.last_addr,
+            reads: port.reads,
+          })
+          .collect(),
+        write_ports: memory
+          .write_ports
+          .iter()
+          .map(|port| WritePortState {
+            last_clock: port.last_clock,
+            last_write: port.last_write.clone(),
+            writes: port.writes,
+          })
+          .collect(),
+      }),
+      Component::Xilinx(cell) => Self::Xilinx(cell.state, cell.last_clock),
+      Component::Tristate(buffer) => Self::Tristate(buffer.drive.clone()),
+      Component::Model(model) => Self::Model(Some(model.model.clone())),
+    };
+    Some(state)
+  }
+
+  /// Check the state was taken from a component of the same kind and size.
+  fn fits(&self, component: &Component) -> bool {
+    match (self, component) {
+      (Self::Behavior(_), Component::Behavior(_)) | (Self::Model(_), Component::Model(_)) => true,
+      (Self::Word(state, _), Component::Word(cell)) => state.len() == cell.state.len(),
+      (Self::Memory(state), Component::Memory(memory)) => {
+        state.contents.len() == memory.contents.len()
+          && state.read_ports.len() == memory.read_ports.len()
+          && state.write_ports.len() == memory.write_ports.len()
+          && state
+            .read_ports
+            .iter()
+            .zip(&memory.read_ports)
+            .all(|(state, port)| state.state.len() == port.state.len())
+      }
+      (Self::Xilinx(..), Component::Xilinx(_)) => true,
+      (Self::Tristate(drive), Component::Tristate(buffer)) => drive.len() == buffer.drive.len(),
+      _ => false,
+    }
+  }
+
+  /// Restore a component this state `fits`.
+  fn restore(&self, component: &mut Component) {
+    match (self, component) {
+      (Self::Behavior(behavior), Component::Behavior(cell)) => cell.behavior = behavior.clone(),
+      (Self::Word(state, last_clock), Component::Word(cell)) => {
+        (cell.state, cell.last_clock) = (state.clone(), *last_clock);
+      }
+      (Self::Memory(state), Component::Memory(memory)) => {
+        memory.contents.clone_from(&state.contents);
+        for (port, state) in memory.read_ports.iter_mut().zip(&state.read_ports) {
+          port.state.clone_from(&state.state);
+          (port.last_clock, port.last_addr, port.reads) =
+            (state.last_clock, state.last_addr, state.reads);
+        }
+        for (port, state) in memory.write_ports.iter_mut().zip(&state.write_ports) {
+          port.last_write.clone_from(&state.last_write);
+          (port.last_clock, port.writes) = (state.last_clock, state.writes);
+        }
+      }
+      (Self::Xilinx(state, last_clock), Component::Xilinx(cell)) => {
+        (cell.state, cell.last_clock) = (*state, *last_clock);
+      }
+      (Self::Tristate(drive), Component::Tristate(buffer)) => buffer.drive.clone_from(drive),
+      (Self::Model(Some(model)), Component::Model(component)) => component.model = model.clone(),
+      // Models of a loaded checkpoint keep their current state
+      _ => (),
+    }
+  }
+}
+
+/// Number of signals, basic cells, other cells and tri-state nets a checkpoint holds.
+type Shape = [usize; 4];
+
+impl Checkpoint {
+  pub fn load(path: &str) -> Result<Self, DesignError> {
+    let serialized = std::fs::read(path)?;
+    let reader = flexbuffers::Reader::get_root(serialized.as_slice())?;
+    Ok(Self::deserialize(reader)?)
+  }
+
+  pub fn save(&self, path: &str) -> Result<(), DesignError> {
+    let mut serializer = flexbuffers::FlexbufferSerializer::new();
+    self.serialize(&mut serializer)?;
+    let mut file_output = std::fs::File::create(path)?;
+    _ = file_output.write(serializer.view())?;
+    Ok(())
+  }
+
+  fn shape(&self) -> Shape {
+    [
+      self.values.len(),
+      self.cells.len(),
+      self.components.len(),
+      self.tristate_nets.len(),
+    ]
+  }
+}
+
+impl HardwareModule {
+  fn checkpoint_shape(&self, shape: &mut Shape) {
+    shape[0] += self.signals.len();
+    shape[3] += self.tristate_nets.len();
+    for component in &self.components {
+      match component {
+        Component::Cell(_) => shape[1] += 1,
+        Component::Module(module) => module.checkpoint_shape(shape),
+        _ => shape[2] += 1,
+      }
+    }
+  }
+
+  /// Check each component state in `checkpoint` fits the component it would restore.
+  fn checkpoint_fits(&self, checkpoint: &Checkpoint, offset: &mut usize) -> bool {
+    for component in &self.components {
+      match component {
+        Component::Cell(_) => (),
+        Component::Module(module) => {
+          if !module.checkpoint_fits(checkpoint, offset) {
+            return false;
+          }
+        }
+        component => {
+          if !checkpoint.components[*offset].fits(component) {
+            return false;
+          }
+          *offset += 1;
+        }
+      }
+    }
+    true
+  }
+
+  fn save_state(&self, checkpoint: &mut Checkpoint) {
+    for signal in &self.signals {
+      checkpoint.values.push(signal.get_value());
+    }
+    checkpoint.tristate_nets.extend(
+      self
+        .tristate_nets
+        .iter()
+        .map(|net| (net.external, net.internal, net.value, net.contention_count)),
+    );
+    for component in &self.components {
+      match component {
+        Component::Cell(cell) => checkpoint.cells.push(cell.state),
+        Component::Module(module) => module.save_state(checkpoint),
+        component => checkpoint.components.extend(ComponentState::new(component)),
+      }
+    }
+  }
+
+  /// Restore state from `checkpoint`, starting at `offset` in each of its lists.
+  fn restore_state(&mut self, checkpoint: &Checkpoint, offset: &mut Shape) {
+    self.events.clear();
+    self.sampled.clear();
+    for signal in &mut self.signals {
+      if let Signal::Net(net) = signal {
+        net.value = checkpoint.values[offset[0]];
+      }
+      offset[0] += 1;
+    }
+    for net in &mut self.tristate_nets {
+      (net.external, net.internal, net.value, net.contention_count) =
+        checkpoint.tristate_nets[offset[3]];
+      offset[3] += 1;
+    }
+    for component in &mut self.components {
+      match component {
+        Component::Cell(cell) => {
+          cell.state = checkpoint.cells[offset[1]];
+          offset[1] += 1;
+        }
+        Component::Module(module) => module.restore_state(checkpoint, offset),
+        component => {
+          checkpoint.components[offset[2]].restore(component);
+          offset[2] += 1;
+        }
+      }
+    }
+  }
+}
+
+impl Design {
+  /// Copy the dynamic state of the design (net values, toggle counts, cell and memory state,
+  /// cycle count, time and clock statistics). Much smaller and faster than `save`, which
+  /// stores the whole netlist.
+  pub fn checkpoint(&self) -> Checkpoint {
+    let mut checkpoint = Checkpoint {
+      cycles: self.cycles,
+      time: self.time,
+      clocks: self
+        .clocks
+        .iter()
+        .map(|clock| (clock.cycles, clock.toggles))
+        .collect(),
+      toggles: self.module.get_toggle_counts(),
+      ..Default::default()
+    };
+    self.module.save_state(&mut checkpoint);
+    checkpoint
+  }
+
+  /// Return to a checkpoint taken from this design or a copy of it. Fails without changing the
+  /// design when the checkpoint doesn't fit its structure.
+  pub fn restore(&mut self, checkpoint: &Checkpoint) -> Result<(), DesignError> {
+    let mut shape = Shape::default();
+    self.module.checkpoint_shape(&mut shape);
+    if shape != checkpoint.shape()
+      || checkpoint.toggles.len() != checkpoint.values.len()
+      || checkpoint.clocks.len() != self.clocks.len()
+      || !self.module.checkpoint_fits(checkpoint, &mut 0)
+    {
+      return Err(DesignError::CheckpointMismatch(self.module.name.clone()));
+    }
+
+    self.module.restore_state(checkpoint, &mut Shape::default());
+    self.module.set_toggle_counts(&checkpoint.toggles);
+    self.clock_domains.clear();
+    self.cycles = checkpoint.cycles;
+    self.time = checkpoint.time;
+    for (clock, (cycles, toggles)) in self.clocks.iter_mut().zip(&checkpoint.clocks) {
+      (clock.cycles, clock.toggles) = (*cycles, *toggles);
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::hardware_module::ModuleError;
+use super::levelize::Graph;
+use crate::bit::Bit;
+use crate::signal::{AccessSignal, SignalIndex};
+use serde::{Deserialize, Serialize};
+
+/// Free-running clock with a 50% duty cycle. Clocks start low, rise at `phase + k * period`
+/// (including time 0 when `phase` is 0) and fall half a period later, in the design's time
+/// unit.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Clock {
+  pub name: String,
+  pub signal: SignalIndex,
+  /// Time between rising edges.
+  pub period: u64,
+  /// Time of the first rising edge.
+  pub phase: u64,
+  /// Rising edges so far.
+  pub cycles: usize,
+  /// Toggles of nets in this clock's domain, see `Design::step`.
+  pub toggles: usize,
+}
+
+impl Clock {
+  /// Next edge from `time` on, and whether it's a rising edge. An edge at `time` itself is
+  /// still to come while the clock's `level` doesn't show it.
+  fn next_edge(&self, time: u64, level: Bit) -> (u64, bool) {
+    if time < self.phase {
+      return (self.phase, true);
+    }
+    let offset = (time - self.phase) % self.period;
+    let start = time - offset;
+    match (offset, level) {
+      (0, Bit::Zero) => (time, true),
+      (offset, Bit::One) if offset == self.period / 2 => (time, false),
+      (offset, _) if offset < self.period / 2 => (start + self.period / 2, false),
+      _ => (start + self.period, true),
+    }
+  }
+}
+
+impl Design {
+  /// Register a clock driving signal `name`.
+  pub fn add_clock(&mut self, name: &str, period: u64, phase: u64) -> Result<(), DesignError> {
+    let signal = self.module.get_signal_idx(name)?;
+    if period < 2 {
+      return Err(DesignError::InvalidClock(
+        name.to_string(),
+        "period must be at least 2".to_string(),
+      ));
+    }
+    if self.clocks.iter().any(|clock| clock.signal == signal) {
+      return Err(DesignError::InvalidClock(
+        name.to_string(),
+        "signal already has a clock".to_string(),
+      ));
+    }
+
+    self.clocks.push(Clock {
+      name: name.to_string(),
+      signal,
+      period,
+      phase,
+      cycles: 0,
+      toggles: 0,
+    });
+    Ok(())
+  }
+
+  /// Register a clock running at `multiply / divide` times the frequency of clock `source`,
+  /// with rising edges aligned to its first one.
+  pub fn add_derived_clock(
+    &mut self,
+    name: &str,
+    source: &str,
+    multiply: u64,
+    divide: u64,
+  ) -> Result<(), DesignError> {
+    let Some(source) = self.clocks.iter().find(|clock| clock.name == source) else {
+      return Err(DesignError::ModuleError(ModuleError::MissingSignal(
+        source.to_string(),
+      )));
+    };
+    let scaled = source.period * divide;
+    if multiply == 0 || !scaled.is_multiple_of(multiply) {
+      return Err(DesignError::InvalidClock(
+        name.to_string(),
+        format!(
+          "period {} * {divide} / {multiply} isn't a whole number",
+          source.period
+        ),
+      ));
+    }
+    self.add_clock(name, scaled / multiply, source.phase)
+  }
+
+  /// Advance time to the next edge of any clock, drive every clock with an edge at that time
+  /// and evaluate, recording VCD and SAIF activity. Each net's toggles are charged to the
+  /// clocks with an edge whose fanout cone contains it, split evenly when several do, and nets
+  /// outside those cones are split between all of them. Returns the new time.
+  pub fn step(&mut self) -> Result<u64, DesignError> {
+    let next_edges = self.next_edges();
+    let Some(time) = next_edges.iter().map(|(edge, _)| *edge).min() else {
+      return Err(DesignError::ModuleError(ModuleError::MissingSignal(
+        "clock".to_string(),
+      )));
+    };
+
+    // Values since the last step were held until now
+    if let Some(saif) = &mut self.saif {
+      saif.sample(&self.module, time - self.time);
+    }
+    // Counted from before driving the clocks, so their own toggles are charged too
+    let toggles = self.module.get_toggle_counts();
+    let mut edges = vec![];
+    for (idx, (clock, (edge, rising))) in self.clocks.iter_mut().zip(next_edges).enumerate() {
+      if edge != time {
+        continue;
+      }
+      self.module.set_signal(clock.signal, Bit::from(rising))?;
+      clock.cycles += usize::from(rising);
+      edges.push(idx);
+    }
+
+    self.eval();
+    self.time = time;
+    self.sample_vcd_step(time);
+    self.charge_toggles(&toggles, &edges);
+    Ok(time)
+  }
+
+  /// Next edge of each clock, see `Clock::next_edge`.
+  fn next_edges(&self) -> Vec<(u64, bool)> {
+    self
+      .clocks
+      .iter()
+      .map(|clock| clock.next_edge(self.time, self.module.signals[clock.signal].get_value()))
+      .collect()
+  }
+
+  fn charge_toggles(&mut self, before: &[(usize, usize)], edges: &[usize]) {
+    // Cleared when the module changes, see `compile` and `restore`
+    if self.clock_domains.len() != self.clocks.len() {
+      let mut graph = Graph::new(&self.module);
+      self.clock_domains = self
+        .clocks
+        .iter()
+        .map(|clock| graph.fanout_cone(clock.signal))
+        .collect();
+    }
+
+    let after = self.module.get_toggle_counts();
+    for (signal, (before, after)) in before.iter().zip(&after).enumerate() {
+      let toggles = (after.0 + after.1) - (before.0 + before.1);
+      if toggles == 0 {
+        continue;
+      }
+      let mut owners: Vec<usize> = edges
+        .iter()
+        .copied()
+        .filter(|idx| self.clock_domains[*idx].get(signal) == Some(&true))
+        .collect();
+      if owners.is_empty() {
+        owners = edges.to_vec();
+      }
+      for (i, idx) in owners.iter().enumerate() {
+        self.clocks[*idx].toggles +=
+          toggles / owners.len() + usize::from(i < toggles % owners.len());
+      }
+    }
+  }
+
+  /// Step through every clock edge up to and including `time`.
+  pub fn advance_to(&mut self, time: u64) -> Result<(), DesignError> {
+    while self.next_edges().iter().any(|(edge, _)| *edge <= time) {
+      self.step()?;
+    }
+    if let Some(saif) = &mut self.saif {
+      saif.sample(&self.module, time.saturating_sub(self.time));
+    }
+    self.time = self.time.max(time);
+    Ok(())
+  }
+}
@@ -3,7 +3,15 @@
 
 use crate::bit::Bit;
 use crate::cell::{CellError, CellLibrary};
+use crate::module::clock::Clock;
+use crate::module::event::Scheduler;
 use crate::module::hardware_module::{HardwareModule, ModuleError};
+use crate::module::replay::ReplayError;
+use crate::module::report::HierarchyReport;
+use crate::module::saif::Saif;
+use crate::module::tape::Tape;
+use crate::module::vcd::Vcd;
+use crate::primitive::xilinx::FpgaResources;
 use crate::signal::SignalIndex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,12 +19,74 @@ use std::io;
 use std::io::Write;
 use thiserror::Error;
 
+/// Extra reset signal asserted by `Design::reset_clocked`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Reset {
+  pub signal: SignalIndex,
+  /// Asserted low (ex, `rst_ni`).
+  pub active_low: bool,
+}
+
+/// Sequence run by `Design::reset_clocked`. The default holds `Design::reset` high for one
+/// clock cycle.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ResetConfig {
+  /// Asserted together with `Design::reset`.
+  pub resets: Vec<Reset>,
+  /// Clock cycles resets are held for.
+  pub cycles: usize,
+  /// Clock the design while resets are held, for synchronous resets. Otherwise the design is
+  /// only evaluated, which is enough for asynchronous resets.
+  pub clocked: bool,
+  /// Leave the sequence out of statistics: toggle, cycle, clock and memory access counts are put
+  /// back afterwards, and VCD and SAIF recording pause while it runs. Counters kept by
+  /// user-defined cells aren't.
+  pub exclude_statistics: bool,
+}
+
+impl Default for ResetConfig {
+  fn default() -> Self {
+    Self {
+      resets: vec![],
+      cycles: 1,
+      clocked: true,
+      exclude_statistics: false,
+    }
+  }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Design {
   pub module: HardwareModule,
   pub clock: Option<SignalIndex>,
   pub reset: Option<SignalIndex>,
   pub cell_library: CellLibrary,
+  #[serde(default)]
+  pub scheduler: Scheduler,
+  /// Compiled module for `Scheduler::Compiled`, rebuilt with `compile` after loading.
+  #[serde(skip)]
+  pub tape: Option<Tape>,
+  /// Clock cycles run by `eval_clocked`.
+  #[serde(default)]
+  pub cycles: usize,
+  /// Clocks advanced by `step`, independent of `clock`.
+  #[serde(default)]
+  pub clocks: Vec<Clock>,
+  /// Simulated time of the last `step`.
+  #[serde(default)]
+  pub time: u64,
+  /// Signals in the fanout cone of each clock, found again on the `step` after `compile` or
+  /// `restore`.
+  #[serde(skip)]
+  pub(crate) clock_domains: Vec<Vec<bool>>,
+  #[serde(default)]
+  pub reset_config: ResetConfig,
+  /// Waveform being recorded, see `start_vcd`.
+  #[serde(skip)]
+  pub vcd: Option<Vcd>,
+  /// Switching activity being recorded, see `start_saif`.
+  #[serde(skip)]
+  pub saif: Option<Saif>,
 }
 
 #[derive(Debug, Error)]
@@ -33,6 +103,14 @@ pub enum DesignError {
   DeserializeError(#[from] flexbuffers::DeserializationError),
   #[error("{0}")]
   SerializeError(#[from] flexbuffers::SerializationError),
+  #[error("checkpoint doesn't match the structure of design `{0}`")]
+  CheckpointMismatch(String),
+  #[error("invalid clock `{0}`: {1}")]
+  InvalidClock(String, String),
+  #[error("{0}")]
+  ReplayError(#[from] ReplayError),
+  #[error("no SAIF recording was started")]
+  MissingSaif,
 }
 
 impl Design {
@@ -56,6 +134,15 @@ impl Design {
       clock: None,
       reset: None,
       cell_library,
+      scheduler: Scheduler::default(),
+      tape: None,
+      cycles: 0,
+      clocks: vec![],
+      time: 0,
+      clock_domains: vec![],
+      reset_config: ResetConfig::default(),
+      vcd: None,
+      saif: None,
     }
   }
 
@@ -69,8 +156,64 @@ impl Design {
     Ok(())
   }
 
+  /// Assert another reset in `reset_clocked`, see `ResetConfig`.
+  pub fn add_reset(&mut self, name: &str, active_low: bool) -> Result<(), DesignError> {
+    let signal = self.module.get_signal_idx(name)?;
+    self.reset_config.resets.push(Reset { signal, active_low });
+    Ok(())
+  }
+
+  /// Every reset asserted by `reset_clocked`, starting with `reset`.
+  pub fn get_resets(&self) -> Vec<Reset> {
+    self
+      .reset
+      .map(|signal| Reset {
+        signal,
+        active_low: false,
+      })
+      .into_iter()
+      .chain(self.reset_config.resets.iter().cloned())
+      .collect()
+  }
+
+  pub fn set_scheduler(&mut self, scheduler: Scheduler) {
+    self.scheduler = scheduler;
+  }
+
+  /// Flatten the module and lower it into an instruction tape, then evaluate with it.
+  /// Only designs made of basic cells can be compiled.
+  pub fn compile(&mut self) -> Result<(), DesignError> {
+    let mut module = self.module.clone();
+    module.flatten()?;
+    self.tape = Some(Tape::compile(&module)?);
+    self.module = module;
+    self.clock_domains.clear();
+    self.scheduler = Scheduler::Compiled;
+    Ok(())
+  }
+
   pub fn eval(&mut self) {
-    self.module.eval();
+    match (self.scheduler, &mut self.tape) {
+      (Scheduler::Compiled, Some(tape)) => tape.eval(&mut self.module),
+      (Scheduler::Levelized | Scheduler::Compiled, _) => self.module.eval(),
+      (Scheduler::EventDriven, _) => self.module.eval_events(),
+    }
+  }
+
+  /// Evaluate until no signal changes, see `HardwareModule::eval_until_stable`.
+  pub fn eval_until_stable(&mut self, max_iterations: usize) -> Result<usize, DesignError> {
+    let iterations = match (self.scheduler, &mut self.tape) {
+      (Scheduler::Compiled, Some(tape)) => self
+        .module
+        .eval_until_stable_with(max_iterations, |module| tape.eval(module)),
+      (Scheduler::Levelized | Scheduler::Compiled, _) => self
+        .module
+        .eval_until_stable_with(max_iterations, HardwareModule::eval),
+      (Scheduler::EventDriven, _) => self
+        .module
+        .eval_until_stable_with(max_iterations, HardwareModule::eval_events),
+    };
+    Ok(iterations?)
   }
 
   pub fn eval_clocked(&mut self) -> Result<(), DesignError> {
@@ -80,29 +223,64 @@ impl Design {
       )));
     };
 
-    // Can we do this deterministically?
-    self.module.eval();
-    self.module.eval();
-    self.module.eval();
+    self.eval();
+    self.sample_vcd(0);
     self.module.set_signal(clock, Bit::One)?;
-    self.module.eval();
+    self.eval();
+    self.sample_vcd(1);
+    self.sample_saif(1);
     self.module.set_signal(clock, Bit::Zero)?;
-    self.module.eval();
+    self.eval();
+    self.sample_vcd(2);
+    self.sample_saif(2);
+    self.cycles += 1;
     Ok(())
   }
 
+  /// Run the reset sequence described by `reset_config`.
   pub fn reset_clocked(&mut self) -> Result<(), DesignError> {
-    let Some(reset) = self.reset else {
+    let resets = self.get_resets();
+    if resets.is_empty() {
       return Err(DesignError::ModuleError(ModuleError::MissingSignal(
         "reset".to_string(),
       )));
-    };
+    }
+    if !self.reset_config.exclude_statistics {
+      return self.run_reset(&resets);
+    }
 
-    self.module.set_signal(reset, Bit::One)?;
-    self.eval_clocked()?;
-    self.module.set_signal(reset, Bit::Zero)?;
-    self.module.eval();
+    let toggles = self.module.get_toggle_counts();
+    let accesses = self.module.get_memory_access_counts();
+    let (cycles, clocks) = (self.cycles, self.clocks.clone());
+    // Recording pauses rather than seeing values jump, put back even if the sequence fails
+    let (vcd, saif) = (self.vcd.take(), self.saif.take());
+    let result = self.run_reset(&resets);
+    self.module.set_toggle_counts(&toggles);
+    self.module.set_memory_access_counts(&accesses);
+    (self.cycles, self.clocks, self.vcd, self.saif) = (cycles, clocks, vcd, saif);
+    result
+  }
 
+  fn run_reset(&mut self, resets: &[Reset]) -> Result<(), DesignError> {
+    for reset in resets {
+      self
+        .module
+        .set_signal(reset.signal, Bit::from(!reset.active_low))?;
+    }
+    match self.reset_config.clocked {
+      true => {
+        for _ in 0..self.reset_config.cycles {
+          self.eval_clocked()?;
+        }
+      }
+      false => self.eval(),
+    }
+    for reset in resets {
+      self
+        .module
+        .set_signal(reset.signal, Bit::from(reset.active_low))?;
+    }
+    self.eval();
     Ok(())
   }
 
@@ -111,10 +289,21 @@ impl Design {
     Ok(self.cell_library.get_cell_breakdown_area(&breakdown)?)
   }
 
+  /// Get FPGA resource usage (LUTs, FFs, carries) of module, used in place of area for Xilinx netlists.
+  pub fn get_module_resources(&self, name: &str) -> Result<FpgaResources, DesignError> {
+    let breakdown = self.get_module_breakdown(name)?;
+    Ok(FpgaResources::from_breakdown(&breakdown))
+  }
+
   pub fn get_module_breakdown(&self, name: &str) -> Result<HashMap<String, usize>, DesignError> {
     Ok(self.module.search_module_cell_breakdown(name)?)
   }
 
+  /// Area, cell and toggle statistics of every instance in the design.
+  pub fn get_hierarchy_report(&self) -> HierarchyReport {
+    HierarchyReport::new(&self.module, &self.cell_library)
+  }
+
   pub fn get_module_total_toggle_count(&self, name: &str) -> Result<usize, DesignError> {
     Ok(self.module.search_module_total_toggle_count(name)?)
   }
@@ -0,0 +1,498 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::event::component_events;
+use super::hardware_module::{Component, ComponentIndex, HardwareModule, ModuleError};
+use super::path::InstanceRef;
+use super::port::{Port, PortDirection};
+use super::saif::Saif;
+use super::vcd::split_bit;
+use crate::signal::{AccessSignal, Signal, SignalIndex};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Statistic nodes and edges are colored by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DotActivity {
+  /// Toggle counts, relative to the most active node or edge.
+  #[default]
+  Toggles,
+  /// Fraction of cycles ending at 1, from the SAIF recording in progress.
+  Probability,
+}
+
+/// What `HardwareModule::to_dot` draws.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DotConfig {
+  /// Path of the instance drawn (ex, `u_core.u_alu`), the top module when empty.
+  pub scope: String,
+  pub activity: DotActivity,
+  /// Draw the cells of submodules inside a cluster per instance, instead of one node per
+  /// submodule.
+  pub cluster: bool,
+}
+
+/// Net of the graph: the submodule holding it (see `HardwareModule::module_at`) and signal.
+type Key = (Vec<ComponentIndex>, SignalIndex);
+
+#[derive(Debug)]
+struct Node {
+  label: String,
+  shape: &'static str,
+  reads: Vec<Key>,
+  drives: Vec<Key>,
+}
+
+/// Instance drawn as a cluster, with its own nodes.
+#[derive(Debug, Default)]
+struct Cluster {
+  label: String,
+  nodes: Vec<usize>,
+  children: Vec<Cluster>,
+}
+
+/// Quote a DOT string, line breaks included.
+fn quote(text: &str) -> String {
+  let escaped = text
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\n");
+  format!("\"{escaped}\"")
+}
+
+/// Blue when idle to red for the highest activity.
+fn heat(level: Option<f64>) -> String {
+  match level {
+    Some(level) => format!(
+      "\"{:.3} 0.600 1.000\"",
+      (1.0 - level.clamp(0.0, 1.0)) * 0.667
+    ),
+    None => "gray".to_string(),
+  }
+}
+
+/// Signals a component reads and drives, memories included.
+fn component_pins(component: &Component) -> (Vec<SignalIndex>, Vec<SignalIndex>) {
+  match component {
+    Component::Memory(memory) => {
+      let mut reads = vec![];
+      for port in &memory.read_ports {
+        reads.extend(port.clock.map(|(idx, _)| idx));
+        reads.extend([port.enable, port.async_reset, port.sync_reset]);
+        reads.extend(&port.addr);
+      }
+      for port in &memory.write_ports {
+        reads.extend(port.clock.map(|(idx, _)| idx));
+        reads.extend(port.enable.iter().chain(&port.addr).chain(&port.data));
+      }
+      let drives = memory
+        .read_ports
+        .iter()
+        .flat_map(|port| port.data.iter().copied())
+        .collect();
+      (reads, drives)
+    }
+    _ => component_events(component),
+  }
+}
+
+/// Signals of ports read and driven by an instance.
+fn port_pins<'p>(ports: impl Iterator<Item = &'p Port>) -> (Vec<SignalIndex>, Vec<SignalIndex>) {
+  let (mut reads, mut drives) = (vec![], vec![]);
+  for port in ports {
+    if port.direction != PortDirection::Output {
+      reads.extend(&port.signal_idx_list);
+    }
+    if port.direction != PortDirection::Input {
+      drives.extend(&port.signal_idx_list);
+    }
+  }
+  (reads, drives)
+}
+
+struct Graph<'a> {
+  module: &'a HardwareModule,
+  config: &'a DotConfig,
+  saif: Option<&'a Saif>,
+  nodes: Vec<Node>,
+  /// Outputs of the buffers `flatten` adds between port nets, drawn as their input net.
+  buffers: HashMap<SignalIndex, SignalIndex>,
+}
+
+impl<'a> Graph<'a> {
+  /// Net a signal of the instance at `path` belongs to. `aliases` maps port nets of a
+  /// clustered submodule to the nets they connect to outside it.
+  fn key(&self, path: &[ComponentIndex], aliases: &HashMap<SignalIndex, Key>, idx: usize) -> Key {
+    match aliases.get(&idx) {
+      Some(key) => key.clone(),
+      None => {
+        let mut idx = idx;
+        while let Some(input) = self.buffers.get(&idx) {
+          idx = *input;
+        }
+        (path.to_vec(), idx)
+      }
+    }
+  }
+
+  fn add_node(&mut self, cluster: &mut Cluster, label: String, shape: &'static str) -> usize {
+    cluster.nodes.push(self.nodes.len());
+    self.nodes.push(Node {
+      label,
+      shape,
+      reads: vec![],
+      drives: vec![],
+    });
+    self.nodes.len() - 1
+  }
+
+  fn add_ports<'p>(
+    &mut self,
+    cluster: &mut Cluster,
+    ports: impl Iterator<Item = (&'p String, &'p Port)>,
+    path: &[ComponentIndex],
+  ) {
+    for (name, port) in ports {
+      let node = self.add_node(cluster, name.clone(), "octagon");
+      let keys: Vec<Key> = port
+        .signal_idx_list
+        .iter()
+        .map(|idx| self.key(path, &HashMap::new(), *idx))
+        .collect();
+      // Ports drive the nets inside the instance
+      if port.direction != PortDirection::Output {
+        self.nodes[node].drives.extend(keys.clone());
+      }
+      if port.direction != PortDirection::Input {
+        self.nodes[node].reads.extend(keys);
+      }
+    }
+  }
+
+  fn add_component(
+    &mut self,
+    cluster: &mut Cluster,
+    label: String,
+    shape: &'static str,
+    (reads, drives): (Vec<SignalIndex>, Vec<SignalIndex>),
+    path: &[ComponentIndex],
+    aliases: &HashMap<SignalIndex, Key>,
+  ) {
+    let node = self.add_node(cluster, label, shape);
+    let reads = reads
+      .iter()
+      .map(|idx| self.key(path, aliases, *idx))
+      .collect();
+    let drives = drives
+      .iter()
+      .map(|idx| self.key(path, aliases, *idx))
+      .collect();
+    self.nodes[node].reads = reads;
+    self.nodes[node].drives = drives;
+  }
+
+  /// Nodes of the components of a hierarchical module.
+  fn add_module(
+    &mut self,
+    module: &HardwareModule,
+    path: &mut Vec<ComponentIndex>,
+    aliases: &HashMap<SignalIndex, Key>,
+    cluster: &mut Cluster,
+  ) {
+    let names: HashMap<usize, &str> = module
+      .component_map
+      .iter()
+      .map(|(name, idx)| (*idx, name.as_str()))
+      .collect();
+    for (idx, component) in module.components.iter().enumerate() {
+      let name = names.get(&idx).copied().unwrap_or_default();
+      match component {
+        Component::Module(submodule) if self.config.cluster => {
+          let child_aliases: HashMap<SignalIndex, Key> = submodule
+            .input_connections
+            .iter()
+            .chain(&submodule.output_connections)
+            .chain(&submodule.inout_connections)
+            .map(|(external, internal)| (*internal, self.key(path, aliases, *external)))
+            .collect();
+          let mut child = Cluster {
+            label: format!("{name}\n{}", submodule.name),
+            ..Default::default()
+          };
+          path.push(idx);
+          self.add_module(submodule, path, &child_aliases, &mut child);
+          path.pop();
+          cluster.children.push(child);
+        }
+        Component::Module(submodule) => {
+          let label = format!("{name}\n{}", submodule.name);
+          let pins = component_pins(component);
+          self.add_component(cluster, label, "box3d", pins, path, aliases);
+        }
+        _ => {
+          let cell_type = match component {
+            Component::Model(model) => model.name.as_str(),
+            _ => component.cell_name().unwrap_or_default(),
+          };
+          let label = format!("{name}\n{cell_type}");
+          let pins = component_pins(component);
+          self.add_component(cluster, label, "box", pins, path, aliases);
+        }
+      }
+    }
+  }
+
+  /// Nodes of the components of an instance of a flattened module.
+  fn add_instance(&mut self, instance: usize, cluster: &mut Cluster) {
+    let no_aliases = HashMap::new();
+    let instances = &self.module.instances;
+    let prefix = match instances[instance].path.as_str() {
+      "" => String::new(),
+      path => format!("{path}."),
+    };
+    let names: HashMap<usize, &str> = self
+      .module
+      .component_map
+      .iter()
+      .map(|(name, idx)| (*idx, name.strip_prefix(&prefix).unwrap_or(name)))
+      .collect();
+    for idx in &instances[instance].components {
+      let component = &self.module.components[*idx];
+      let cell_type = match component {
+        Component::Model(model) => model.name.as_str(),
+        _ => component.cell_name().unwrap_or_default(),
+      };
+      let label = format!(
+        "{}\n{cell_type}",
+        names.get(idx).copied().unwrap_or_default()
+      );
+      self.add_component(
+        cluster,
+        label,
+        "box",
+        component_pins(component),
+        &[],
+        &no_aliases,
+      );
+    }
+
+    for (child, child_instance) in instances.iter().enumerate() {
+      if child_instance.parent != Some(instance) {
+        continue;
+      }
+      let name = child_instance
+        .path
+        .strip_prefix(&prefix)
+        .unwrap_or_default();
+      let label = format!("{name}\n{}", child_instance.module);
+      if self.config.cluster {
+        let mut child_cluster = Cluster {
+          label,
+          ..Default::default()
+        };
+        self.add_instance(child, &mut child_cluster);
+        cluster.children.push(child_cluster);
+      } else {
+        let pins = port_pins(child_instance.ports.values());
+        self.add_component(cluster, label, "box3d", pins, &[], &no_aliases);
+      }
+    }
+  }
+
+  /// Toggle count or static probability of a net, `None` when it isn't known.
+  fn activity(&self, (path, idx): &Key) -> Option<f64> {
+    match self.config.activity {
+      DotActivity::Toggles => {
+        let signal = &self.module.module_at(path).signals[*idx];
+        Some(signal.get_total_toggle_count() as f64)
+      }
+      DotActivity::Probability => self.saif?.probability(path, *idx),
+    }
+  }
+
+  /// Activity of several nets: total toggles, or average probability.
+  fn total(&self, keys: &[Key]) -> Option<f64> {
+    let known: Vec<f64> = keys.iter().filter_map(|key| self.activity(key)).collect();
+    match (self.config.activity, known.len()) {
+      (_, 0) => None,
+      (DotActivity::Toggles, _) => Some(known.iter().sum()),
+      (DotActivity::Probability, count) => Some(known.iter().sum::<f64>() / count as f64),
+    }
+  }
+
+  /// Activity as a level from 0 to 1, and as a label.
+  fn levels(&self, totals: &[Option<f64>]) -> Vec<(Option<f64>, String)> {
+    let max = totals
+      .iter()
+      .flatten()
+      .fold(0.0, |max: f64, total| max.max(*total));
+    totals
+      .iter()
+      .map(|total| match (self.config.activity, total) {
+        (_, None) => (None, String::new()),
+        (DotActivity::Toggles, Some(total)) => {
+          let level = if max > 0.0 { total / max } else { 0.0 };
+          (Some(level), format!("{total}"))
+        }
+        (DotActivity::Probability, Some(total)) => (Some(*total), format!("p={total:.2}")),
+      })
+      .collect()
+  }
+
+  fn write_cluster(
+    &self,
+    output: &mut String,
+    cluster: &Cluster,
+    nodes: &[String],
+    id: &mut usize,
+  ) {
+    for node in &cluster.nodes {
+      output.push_str(&nodes[*node]);
+    }
+    for child in &cluster.children {
+      *id += 1;
+      writeln!(
+        output,
+        "subgraph cluster_{id} {{\nlabel={};",
+        quote(&child.label)
+      )
+      .unwrap();
+      self.write_cluster(output, child, nodes, id);
+      output.push_str("}\n");
+    }
+  }
+
+  fn write(&self, name: &str, root: &Cluster) -> String {
+    let node_totals: Vec<Option<f64>> = self
+      .nodes
+      .iter()
+      .map(|node| match node.drives.is_empty() {
+        true => self.total(&node.reads),
+        false => self.total(&node.drives),
+      })
+      .collect();
+    let nodes: Vec<String> = self
+      .nodes
+      .iter()
+      .zip(self.levels(&node_totals))
+      .enumerate()
+      .map(|(id, (node, (level, value)))| {
+        let label = match value.is_empty() {
+          true => node.label.clone(),
+          false => format!("{}\n{value}", node.label),
+        };
+        format!(
+          "n{id} [label={}, shape={}, fillcolor={}];\n",
+          quote(&label),
+          node.shape,
+          heat(level)
+        )
+      })
+      .collect();
+
+    // Bits of a bus between the same nodes share an edge
+    let mut drivers: HashMap<&Key, Vec<usize>> = HashMap::new();
+    for (id, node) in self.nodes.iter().enumerate() {
+      for key in &node.drives {
+        drivers.entry(key).or_default().push(id);
+      }
+    }
+    let mut edges: BTreeMap<(usize, usize, String), Vec<Key>> = BTreeMap::new();
+    for (sink, node) in self.nodes.iter().enumerate() {
+      for key in &node.reads {
+        let (path, idx) = key;
+        let signal = &self.module.module_at(path).signals[*idx];
+        if matches!(signal, Signal::Constant(_)) {
+          continue;
+        }
+        let net = split_bit(signal.get_name()).0.to_string();
+        for driver in drivers.get(key).into_iter().flatten() {
+          edges
+            .entry((*driver, sink, net.clone()))
+            .or_default()
+            .push(key.clone());
+        }
+      }
+    }
+    let edge_totals: Vec<Option<f64>> = edges.values().map(|keys| self.total(keys)).collect();
+
+    let mut output = format!(
+      "digraph {} {{\nrankdir=LR;\nnode [style=filled];\n",
+      quote(name)
+    );
+    self.write_cluster(&mut output, root, &nodes, &mut 0);
+    for ((driver, sink, net), (level, value)) in edges.keys().zip(self.levels(&edge_totals)) {
+      let label = match value.is_empty() {
+        true => net.clone(),
+        false => format!("{net}\n{value}"),
+      };
+      writeln!(
+        output,
+        "n{driver} -> n{sink} [label={}, color={}];",
+        quote(&label),
+        heat(level)
+      )
+      .unwrap();
+    }
+    output.push_str("}\n");
+    output
+  }
+}
+
+impl HardwareModule {
+  /// Graphviz DOT graph of the cells and nets of an instance, colored by switching activity.
+  /// `saif` is the recording static probabilities are read from.
+  pub fn to_dot(&self, config: &DotConfig, saif: Option<&Saif>) -> Result<String, ModuleError> {
+    let buffers = self
+      .port_buffers()
+      .into_iter()
+      .filter_map(|idx| match &self.components[idx] {
+        Component::Cell(cell) => Some((cell.output_connection, cell.input_connections[0])),
+        _ => None,
+      })
+      .collect();
+    let mut graph = Graph {
+      module: self,
+      config,
+      saif,
+      nodes: vec![],
+      buffers,
+    };
+
+    let mut root = Cluster::default();
+    let name = match config.scope.is_empty() {
+      true => self.name.as_str(),
+      false => config.scope.as_str(),
+    };
+    match self.search_instance(name) {
+      Some(InstanceRef::Module(module)) => {
+        let mut path = self.resolve_instances(name).0;
+        graph.add_ports(&mut root, module.ports.iter(), &path);
+        graph.add_module(module, &mut path, &HashMap::new(), &mut root);
+      }
+      Some(InstanceRef::Flat(instance)) => {
+        graph.add_ports(&mut root, self.instances[instance].ports.iter(), &[]);
+        graph.add_instance(instance, &mut root);
+      }
+      None => return Err(ModuleError::MissingPath(name.to_string())),
+    }
+    Ok(graph.write(name, &root))
+  }
+}
+
+impl Design {
+  /// Graphviz DOT graph of an instance, see `HardwareModule::to_dot`. Coloring by static
+  /// probability needs a recording started with `start_saif`.
+  pub fn to_dot(&self, config: &DotConfig) -> Result<String, DesignError> {
+    if config.activity == DotActivity::Probability && self.saif.is_none() {
+      return Err(DesignError::MissingSaif);
+    }
+    Ok(self.module.to_dot(config, self.saif.as_ref())?)
+  }
+
+  pub fn save_dot(&self, path: &str, config: &DotConfig) -> Result<(), DesignError> {
+    std::fs::write(path, self.to_dot(config)?)?;
+    Ok(())
+  }
+}
@@ -0,0 +1,201 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::hardware_module::{Component, ComponentIndex, HardwareModule};
+use crate::bit::Bit;
+use crate::signal::{AccessSignal, SignalIndex, SignalList};
+use serde::{Deserialize, Serialize};
+
+/// Which components a `Design` evaluates on each pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Scheduler {
+  /// Evaluate every component in level order.
+  #[default]
+  Levelized,
+  /// Only evaluate components whose inputs changed since they were last evaluated.
+  EventDriven,
+  /// Run the instruction tape built by `Design::compile`, levelized until it is built.
+  Compiled,
+}
+
+/// Signals a component reads and drives in its parent module.
+/// Unlike `levelize::component_signals`, sequential elements read all of their inputs.
+pub fn component_events(component: &Component) -> (Vec<SignalIndex>, Vec<SignalIndex>) {
+  match component {
+    Component::Cell(cell) => (
+      cell.input_connections[..cell.num_inputs].to_vec(),
+      vec![cell.output_connection],
+    ),
+    Component::Behavior(behavior_cell) => {
+      (behavior_cell.inputs.clone(), behavior_cell.outputs.clone())
+    }
+    Component::Word(word_cell) => {
+      let mut reads: Vec<SignalIndex> = [&word_cell.a, &word_cell.b, &word_cell.s]
+        .into_iter()
+        .flatten()
+        .copied()
+        .collect();
+      reads.extend(
+        [word_cell.clock, word_cell.enable, word_cell.reset]
+          .iter()
+          .flatten()
+          .map(|(idx, _)| *idx),
+      );
+      (reads, word_cell.y.clone())
+    }
+    Component::Memory(memory) => (
+      vec![],
+      memory
+        .read_ports
+        .iter()
+        .flat_map(|port| port.data.iter().copied())
+        .collect(),
+    ),
+    Component::Xilinx(xilinx_cell) => (
+      xilinx_cell.inputs.clone(),
+      xilinx_cell.outputs.iter().flatten().copied().collect(),
+    ),
+    Component::Tristate(buffer) => {
+      let mut reads = buffer.inputs.clone();
+      reads.push(buffer.enable);
+      (reads, buffer.outputs.clone())
+    }
+    Component::Model(model) => (
+      model.inputs.values().flatten().copied().collect(),
+      model.outputs.values().flatten().copied().collect(),
+    ),
+    Component::Module(module) => (
+      module
+        .input_connections
+        .iter()
+        .chain(&module.inout_connections)
+        .map(|(external_idx, _)| *external_idx)
+        .collect(),
+      module
+        .output_connections
+        .iter()
+        .chain(&module.inout_connections)
+        .map(|(external_idx, _)| *external_idx)
+        .collect(),
+    ),
+  }
+}
+
+/// Fanout index and pending events of a module, built on its first event-driven pass.
+#[derive(Debug, Clone, Default)]
+pub struct EventQueue {
+  built: bool,
+  /// Components reading each signal.
+  fanout: Vec<Vec<ComponentIndex>>,
+  /// Signals driven by each component.
+  drives: Vec<Vec<SignalIndex>>,
+  /// Components with inputs changed since they were last evaluated.
+  pending: Vec<bool>,
+  /// Components evaluated on every pass.
+  always: Vec<bool>,
+  /// Driven values from before the current evaluation.
+  previous: Vec<Bit>,
+}
+
+/// Scheduling state isn't part of the design.
+impl PartialEq for EventQueue {
+  fn eq(&self, _other: &Self) -> bool {
+    true
+  }
+}
+
+impl EventQueue {
+  /// Build the fanout index of a module, every component starts out pending.
+  pub fn new(module: &HardwareModule) -> Self {
+    let mut fanout = vec![vec![]; module.signals.len()];
+    let mut drives = Vec::with_capacity(module.components.len());
+    let mut always = Vec::with_capacity(module.components.len());
+
+    for (idx, component) in module.components.iter().enumerate() {
+      let (reads, component_drives) = component_events(component);
+      for signal in reads {
+        if fanout[signal].last() != Some(&idx) {
+          fanout[signal].push(idx);
+        }
+      }
+      drives.push(component_drives);
+      // Memories count accesses and user code may hold state, so they can't be skipped.
+      // Submodules are always visited, their own components are event-driven.
+      always.push(
+        !module.tristate_nets.is_empty()
+          || matches!(
+            component,
+            Component::Behavior(_)
+              | Component::Memory(_)
+              | Component::Model(_)
+              | Component::Module(_)
+          ),
+      );
+    }
+
+    Self {
+      built: true,
+      fanout,
+      drives,
+      pending: vec![true; module.components.len()],
+      always,
+      previous: vec![],
+    }
+  }
+
+  pub fn is_built(&self) -> bool {
+    self.built
+  }
+
+  /// Drop the fanout index, e.g. after signals changed without events.
+  pub fn clear(&mut self) {
+    if self.built {
+      *self = Self::default();
+    }
+  }
+
+  /// Mark components reading a signal as pending.
+  pub fn notify(&mut self, signal: SignalIndex) {
+    if !self.built {
+      return;
+    }
+    for idx in &self.fanout[signal] {
+      self.pending[*idx] = true;
+    }
+  }
+
+  /// Mark components reading any signal a component drives as pending.
+  pub fn notify_drives(&mut self, idx: ComponentIndex) {
+    if !self.built {
+      return;
+    }
+    for i in 0..self.drives[idx].len() {
+      self.notify(self.drives[idx][i]);
+    }
+  }
+
+  /// Check if a component needs evaluating and record the values it drives.
+  pub fn begin(&mut self, idx: ComponentIndex, signals: &SignalList) -> bool {
+    if !(self.pending[idx] || self.always[idx]) {
+      return false;
+    }
+    self.pending[idx] = false;
+    self.previous.clear();
+    self.previous.extend(
+      self.drives[idx]
+        .iter()
+        .map(|signal| signals[*signal].get_value()),
+    );
+    true
+  }
+
+  /// Notify the fanout of signals a component changed.
+  pub fn end(&mut self, idx: ComponentIndex, signals: &SignalList) {
+    for i in 0..self.drives[idx].len() {
+      let signal = self.drives[idx][i];
+      if signals[signal].get_value() != self.previous[i] {
+        self.notify(signal);
+      }
+    }
+  }
+}
@@ -0,0 +1,343 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::hardware_module::{
+  Component, ComponentIndex, ComponentIndexMap, HardwareModule, ModuleError, PortMap,
+};
+use crate::bit::Bit;
+use crate::cell::{Cell, Function, CONNECTION_SIZE, STATE_SIZE};
+use crate::signal::{AccessSignal, Signal, SignalIndex};
+use crate::synth::netlist::Netlist;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Submodule instance folded into a flattened module.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Instance {
+  /// Hierarchical instance name, empty for the top module.
+  pub path: String,
+  /// Module type name.
+  pub module: String,
+  /// Ports of the instance's module, connected to flat signals.
+  pub ports: PortMap,
+  /// Index of the parent instance, `None` for the top module.
+  pub parent: Option<usize>,
+  /// Components owned directly by the instance.
+  pub components: Vec<ComponentIndex>,
+  /// Signals of the instance's own module, except its input port nets.
+  /// Nets shared with the parent across a port are listed in both, as in a hierarchical module.
+  pub signals: Vec<SignalIndex>,
+  /// Buffers driving the instance's nets from child output ports that can't share a net with
+  /// them, see `flatten`.
+  #[serde(default)]
+  pub buffers: Vec<ComponentIndex>,
+}
+
+/// Replace every signal index a component connects to with `map[idx]`.
+fn remap_component(component: &mut Component, map: &[SignalIndex]) {
+  let remap = |idx: &mut SignalIndex| *idx = map[*idx];
+  let remap_control = |control: &mut Option<(SignalIndex, Bit)>| {
+    if let Some((idx, _)) = control {
+      remap(idx);
+    }
+  };
+
+  match component {
+    Component::Cell(cell) => {
+      cell.input_connections[..cell.num_inputs]
+        .iter_mut()
+        .for_each(remap);
+      remap(&mut cell.output_connection);
+    }
+    Component::Behavior(behavior_cell) => {
+      behavior_cell.inputs.iter_mut().for_each(remap);
+      behavior_cell.outputs.iter_mut().for_each(remap);
+    }
+    Component::Word(word_cell) => {
+      for bits in [
+        &mut word_cell.a,
+        &mut word_cell.b,
+        &mut word_cell.s,
+        &mut word_cell.y,
+      ] {
+        bits.iter_mut().for_each(remap);
+      }
+      remap_control(&mut word_cell.clock);
+      remap_control(&mut word_cell.enable);
+      remap_control(&mut word_cell.reset);
+    }
+    Component::Memory(memory) => {
+      for port in &mut memory.read_ports {
+        remap_control(&mut port.clock);
+        remap(&mut port.enable);
+        remap(&mut port.async_reset);
+        remap(&mut port.sync_reset);
+        port.addr.iter_mut().for_each(remap);
+        port.data.iter_mut().for_each(remap);
+      }
+      for port in &mut memory.write_ports {
+        remap_control(&mut port.clock);
+        port.enable.iter_mut().for_each(remap);
+        port.addr.iter_mut().for_each(remap);
+        port.data.iter_mut().for_each(remap);
+      }
+    }
+    Component::Xilinx(xilinx_cell) => {
+      xilinx_cell.inputs.iter_mut().for_each(remap);
+      xilinx_cell.outputs.iter_mut().flatten().for_each(remap);
+    }
+    Component::Tristate(buffer) => {
+      buffer.inputs.iter_mut().for_each(remap);
+      remap(&mut buffer.enable);
+      buffer.outputs.iter_mut().for_each(remap);
+    }
+    Component::Model(model) => {
+      model.inputs.values_mut().flatten().for_each(remap);
+      model.outputs.values_mut().flatten().for_each(remap);
+    }
+    Component::Module(_) => unreachable!("submodules are flattened into their parent"),
+  }
+}
+
+impl HardwareModule {
+  /// Fold all submodules into one signal list and component list, so evaluation doesn't copy
+  /// values across module boundaries. The instance tree is kept in `instances`, and submodule
+  /// nets and components are named by hierarchical path (ex, `u_add.sum[0]`).
+  /// Submodules with inout ports can't be flattened.
+  pub fn flatten(&mut self) -> Result<(), ModuleError> {
+    if !self.instances.is_empty() {
+      return Ok(());
+    }
+
+    let components = std::mem::take(&mut self.components);
+    let component_map = std::mem::take(&mut self.component_map);
+    let map: Vec<SignalIndex> = (0..self.signals.len()).collect();
+    self.instances.push(Instance {
+      path: String::new(),
+      module: self.name.clone(),
+      ports: self.ports.clone(),
+      parent: None,
+      components: vec![],
+      signals: map.clone(),
+      buffers: vec![],
+    });
+    self.add_instance(0, "", components, &component_map, &map)?;
+
+    self.tristate_nets = Netlist::generate_tristate_nets(&self.ports, &mut self.components);
+    self.events.clear();
+    self.sampled.clear();
+    self.levelize();
+    Ok(())
+  }
+
+  /// Buffers added by `flatten` between ports, see `Instance::buffers`.
+  pub fn port_buffers(&self) -> HashSet<ComponentIndex> {
+    self
+      .instances
+      .iter()
+      .flat_map(|instance| instance.buffers.iter().copied())
+      .collect()
+  }
+
+  /// Move components of an instance into this module, `map` gives the flat index of each of
+  /// the instance's signals.
+  fn add_instance(
+    &mut self,
+    instance: usize,
+    prefix: &str,
+    components: Vec<Component>,
+    component_map: &ComponentIndexMap,
+    map: &[SignalIndex],
+  ) -> Result<(), ModuleError> {
+    let mut instance_names: Vec<String> =
+      (0..components.len()).map(|idx| idx.to_string()).collect();
+    for (instance_name, idx) in component_map {
+      instance_names[*idx] = instance_name.clone();
+    }
+
+    for (idx, component) in components.into_iter().enumerate() {
+      let name = format!("{prefix}{}", instance_names[idx]);
+      let module = match component {
+        Component::Module(module) => module,
+        mut component => {
+          remap_component(&mut component, map);
+          self.component_map.insert(name, self.components.len());
+          self.instances[instance]
+            .components
+            .push(self.components.len());
+          self.components.push(component);
+          continue;
+        }
+      };
+      if !module.inout_connections.is_empty() {
+        return Err(ModuleError::InoutInstance(name));
+      }
+      for (cell_type, pins) in module.cell_pins.iter() {
+        if !self.cell_pins.contains_key(cell_type) {
+          self.cell_pins.insert(cell_type.clone(), pins.clone());
+        }
+      }
+
+      // Port nets are shared with the parent, outputs that can't be (ex, an output fed
+      // straight from an input or a constant) are driven by a buffer instead
+      let mut child_map = vec![usize::MAX; module.signals.len()];
+      for (external_idx, internal_idx) in &module.input_connections {
+        if child_map[*internal_idx] == usize::MAX {
+          child_map[*internal_idx] = map[*external_idx];
+        }
+      }
+      let mut buffers = vec![];
+      for (external_idx, internal_idx) in &module.output_connections {
+        let external = map[*external_idx];
+        if matches!(self.signals[external], Signal::Constant(_)) {
+          continue;
+        }
+        if child_map[*internal_idx] == usize::MAX
+          && !matches!(module.signals[*internal_idx], Signal::Constant(_))
+        {
+          child_map[*internal_idx] = external;
+        } else {
+          buffers.push((*internal_idx, external));
+        }
+      }
+
+      for (internal_idx, signal) in module.signals.iter().enumerate() {
+        if child_map[internal_idx] != usize::MAX {
+          continue;
+        }
+        let flat_idx = self.signals.len();
+        let mut signal = signal.clone();
+        if let Signal::Net(net) = &mut signal {
+          net.index = flat_idx;
+          if !net.name.is_empty() {
+            net.name = format!("{name}.{}", net.name);
+          }
+        }
+        self.signals.push(signal);
+        child_map[internal_idx] = flat_idx;
+      }
+      // Port nets keep the parent's name, but can still be looked up by the child's
+      for (signal_name, internal_idx) in module.signal_map.iter() {
+        self
+          .signal_map
+          .insert(format!("{name}.{signal_name}"), child_map[*internal_idx]);
+      }
+
+      for (internal_idx, external) in buffers {
+        let mut input_connections = [0; CONNECTION_SIZE];
+        input_connections[0] = child_map[internal_idx];
+        self.instances[instance].buffers.push(self.components.len());
+        self.components.push(Component::Cell(Cell {
+          name: String::new(),
+          function: Function::Buf,
+          state: [Bit::Zero; STATE_SIZE],
+          num_inputs: 1,
+          input_connections,
+          output_connection: external,
+        }));
+      }
+
+      let inputs: HashSet<SignalIndex> = module
+        .input_connections
+        .iter()
+        .map(|(_, internal_idx)| *internal_idx)
+        .collect();
+      let child = self.instances.len();
+      self.instances.push(Instance {
+        path: name.clone(),
+        module: module.name.clone(),
+        ports: module
+          .ports
+          .iter()
+          .map(|(port_name, port)| {
+            let mut port = port.clone();
+            port
+              .signal_idx_list
+              .iter_mut()
+              .for_each(|idx| *idx = child_map[*idx]);
+            (port_name.clone(), port)
+          })
+          .collect(),
+        parent: Some(instance),
+        components: vec![],
+        signals: (0..module.signals.len())
+          .filter(|internal_idx| !inputs.contains(internal_idx))
+          .map(|internal_idx| child_map[internal_idx])
+          .collect(),
+        buffers: vec![],
+      });
+      self.add_instance(
+        child,
+        &format!("{name}."),
+        module.components,
+        &module.component_map,
+        &child_map,
+      )?;
+    }
+    Ok(())
+  }
+
+  /// First instance of a module type in a flattened module.
+  pub fn find_instance(&self, module_name: &str) -> Option<usize> {
+    self
+      .instances
+      .iter()
+      .position(|instance| instance.module == module_name)
+  }
+
+  /// Follow a path of module type names down the instance tree, as in `get_module_port_int`.
+  pub fn find_module_path(&self, path: &[&str]) -> Option<usize> {
+    let mut instance = 0;
+    for module_name in path {
+      instance = (instance + 1..self.instances.len()).find(|idx| {
+        self.instances[*idx].parent == Some(instance) && self.instances[*idx].module == *module_name
+      })?;
+    }
+    Some(instance)
+  }
+
+  /// An instance and every instance below it.
+  pub fn instance_subtree(&self, instance: usize) -> Vec<usize> {
+    let mut subtree = vec![instance];
+    // Instances are stored parents first
+    for idx in instance + 1..self.instances.len() {
+      if let Some(parent) = self.instances[idx].parent {
+        if subtree.contains(&parent) {
+          subtree.push(idx);
+        }
+      }
+    }
+    subtree
+  }
+
+  /// Cell breakdown of an instance, including instances below it.
+  pub fn instance_cell_breakdown(&self, instance: usize) -> HashMap<String, usize> {
+    let mut breakdown = HashMap::<String, usize>::new();
+    for idx in self.instance_subtree(instance) {
+      for component_idx in &self.instances[idx].components {
+        if let Some(name) = self.components[*component_idx].cell_name() {
+          *breakdown.entry(name.to_string()).or_default() += 1;
+        }
+      }
+    }
+    breakdown
+  }
+
+  /// Toggles of an instance's own nets, not including instances below it.
+  pub fn instance_bit_flips(&self, instance: usize) -> usize {
+    self.instances[instance]
+      .signals
+      .iter()
+      .map(|idx| self.signals[*idx].get_total_toggle_count())
+      .sum()
+  }
+
+  /// Toggles of an instance, including instances below it.
+  pub fn instance_toggle_count(&self, instance: usize) -> usize {
+    self
+      .instance_subtree(instance)
+      .into_iter()
+      .map(|idx| self.instance_bit_flips(idx))
+      .sum()
+  }
+}
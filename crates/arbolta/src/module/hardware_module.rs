@@ -1,10 +1,19 @@
 // Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
 // SPDX-License-Identifier: MIT
 
+use super::event::EventQueue;
+use super::flatten::Instance;
+use super::levelize::CombinationalLoop;
+use super::model::{ModelComponent, ModelError};
+use super::path::InstanceRef;
 use super::port::{Port, PortDirection, PortError};
 use crate::bit::{Bit, BitVec};
-use crate::cell::Cell;
-use crate::signal::{AccessSignal, SignalIndex, SignalIndexMap, SignalList};
+use crate::cell::{BehaviorCell, Cell};
+use crate::primitive::memory::{Memory, MemoryError, MemoryStatistics};
+use crate::primitive::tristate::{find_tristate_net, TristateBuffer, TristateNet};
+use crate::primitive::word::WordCell;
+use crate::primitive::xilinx::XilinxCell;
+use crate::signal::{AccessSignal, Signal, SignalIndex, SignalIndexMap, SignalList};
 use ndarray::{Array1, ArrayView1};
 use num_traits::PrimInt;
 use serde::{Deserialize, Serialize};
@@ -14,14 +23,58 @@ use thiserror::Error;
 
 pub type PortMap = BTreeMap<String, Port>;
 
+/// Passes evaluated before giving up on flip-flop outputs settling (ex, a clock derived from
+/// a flip-flop's own output).
+pub const MAX_DELTA_CYCLES: usize = 1024;
+
+/// Evaluate delta cycles with `pass`, which returns if any flip-flop output changed.
+/// Returns if outputs settled within `MAX_DELTA_CYCLES` passes.
+pub(crate) fn eval_delta_cycles(mut pass: impl FnMut() -> bool) -> bool {
+  (0..MAX_DELTA_CYCLES).any(|_| !pass())
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Component {
   Cell(Cell),
-  Module(HardwareModule),
+  Behavior(BehaviorCell),
+  Word(WordCell),
+  Memory(Memory),
+  Xilinx(XilinxCell),
+  Tristate(TristateBuffer),
+  Model(ModelComponent),
+  Module(Box<HardwareModule>),
+}
+
+impl Component {
+  /// Cell type counted in cell breakdowns, `None` for submodules and models.
+  pub fn cell_name(&self) -> Option<&str> {
+    match self {
+      Component::Cell(Cell { name, .. })
+      | Component::Behavior(BehaviorCell { name, .. })
+      | Component::Word(WordCell { name, .. })
+      | Component::Memory(Memory { name, .. })
+      | Component::Xilinx(XilinxCell { name, .. })
+      | Component::Tristate(TristateBuffer { name, .. }) => Some(name),
+      // Models stand in for a module, they aren't cells
+      Component::Model(_) | Component::Module(_) => None,
+    }
+  }
+
+  /// Flip-flop bits of a component, not including submodules.
+  pub fn flop_count(&self) -> usize {
+    match self {
+      Component::Cell(cell) if cell.is_sequential() => 1,
+      Component::Word(word_cell) if word_cell.function.is_sequential() => word_cell.y.len(),
+      Component::Xilinx(xilinx_cell) if xilinx_cell.function.is_sequential() => 1,
+      _ => 0,
+    }
+  }
 }
 
 pub type ComponentIndex = usize;
 pub type ComponentIndexMap = BTreeMap<String, ComponentIndex>;
+/// Pin names of each basic cell type in connection order, output last.
+pub type CellPinMap = BTreeMap<String, Vec<String>>;
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct HardwareModule {
@@ -33,6 +86,33 @@ pub struct HardwareModule {
   pub component_map: ComponentIndexMap,
   pub input_connections: Vec<(SignalIndex, SignalIndex)>,
   pub output_connections: Vec<(SignalIndex, SignalIndex)>,
+  /// (external, internal) signal indices of inout ports.
+  #[serde(default)]
+  pub inout_connections: Vec<(SignalIndex, SignalIndex)>,
+  /// Nets with tri-state drivers or connected to inout ports.
+  #[serde(default)]
+  pub tristate_nets: Vec<TristateNet>,
+  /// Components evaluated at each level, see `levelize`. Empty when not levelized.
+  #[serde(default, with = "super::levelize::schedule_serde")]
+  pub schedule: BTreeMap<usize, Vec<ComponentIndex>>,
+  /// Combinational cycles found by `levelize`, only recorded on the top module.
+  #[serde(default)]
+  pub combinational_loops: Vec<CombinationalLoop>,
+  /// Fanout index for event-driven evaluation, see `eval_events`.
+  #[serde(skip)]
+  pub events: EventQueue,
+  /// Flip-flops, memories and submodules sampled in the current pass, see `commit`.
+  #[serde(skip)]
+  pub sampled: Vec<ComponentIndex>,
+  /// Instance tree of a flattened module, see `flatten`. Empty when hierarchical.
+  #[serde(default)]
+  pub instances: Vec<Instance>,
+  /// Pin names of the basic cells instantiated, recorded at elaboration.
+  #[serde(default)]
+  pub cell_pins: CellPinMap,
+  /// Nets still changing after `MAX_DELTA_CYCLES` passes, see `take_eval_error`.
+  #[serde(skip)]
+  pub unsettled: Option<Vec<String>>,
 }
 
 #[derive(Debug, Error)]
@@ -47,6 +127,26 @@ pub enum ModuleError {
   MissingSignalIndex(SignalIndex),
   #[error("module `{0}` does not exist")]
   MissingModule(String),
+  #[error("module does not have memory `{0}`")]
+  MissingMemory(String),
+  #[error("error accessing memory `{0}`: {1}")]
+  Memory(String, MemoryError),
+  #[error("module does not have user-defined cell `{0}`")]
+  MissingBehavior(String),
+  #[error("design did not settle after {0} evaluations, oscillating nets: {nets}", nets = .1.join(", "))]
+  Oscillation(usize, Vec<String>),
+  #[error("cannot flatten instance `{0}` with inout ports")]
+  InoutInstance(String),
+  #[error("design does not have path `{0}`")]
+  MissingPath(String),
+  #[error("component `{0}` is not supported in bit-parallel simulation")]
+  UnsupportedLanes(String),
+  #[error("component `{0}` cannot be compiled to an instruction tape")]
+  UnsupportedTape(String),
+  #[error("model of module `{0}` failed: {1}")]
+  Model(String, ModelError),
+  #[error("pin names of cell `{0}` are unknown")]
+  MissingPins(String),
 }
 
 impl HardwareModule {
@@ -62,6 +162,7 @@ impl HardwareModule {
       Err(ModuleError::MissingSignalIndex(idx))
     } else {
       self.signals[idx].set_value(val);
+      self.events.notify(idx);
       Ok(())
     }
   }
@@ -75,9 +176,25 @@ impl HardwareModule {
       return self.get_port_int(name);
     }
 
+    if !self.instances.is_empty() {
+      return match self
+        .find_module_path(&path)
+        .and_then(|instance| self.instances[instance].ports.get(name))
+      {
+        Some(port) => Ok(port.get_int(&self.signals)),
+        None => Err(ModuleError::MissingPort(name.to_string())),
+      };
+    }
+
     for component in &self.components {
       match component {
-        Component::Cell(_) => (),
+        Component::Cell(_)
+        | Component::Behavior(_)
+        | Component::Word(_)
+        | Component::Memory(_)
+        | Component::Xilinx(_)
+        | Component::Tristate(_)
+        | Component::Model(_) => (),
         Component::Module(module) => {
           if path[0] == module.name {
             return module.get_module_port_int(path[1..].to_vec(), name);
@@ -97,42 +214,399 @@ impl HardwareModule {
 
     for component in &mut self.components {
       match component {
-        Component::Cell(_) => (),
+        Component::Cell(_)
+        | Component::Behavior(_)
+        | Component::Word(_)
+        | Component::Memory(_)
+        | Component::Xilinx(_)
+        | Component::Tristate(_)
+        | Component::Model(_) => (),
         Component::Module(module) => match module.search_signal(name) {
           Some(val) => return Some(val),
           None => continue,
         },
       }
     }
-    None
+
+    // Flattened submodule nets are prefixed with their instance path
+    let suffix = format!(".{name}");
+    self
+      .signals
+      .iter()
+      .find(|signal| signal.get_name().ends_with(&suffix))
+      .map(|signal| signal.get_value())
   }
 
+  /// Evaluate passes until flip-flop outputs settle. Flip-flops sample their inputs during a
+  /// pass and their outputs commit together after it, like non-blocking assignments. Data moves
+  /// one stage per clock edge, and flip-flops on derived clocks see their edge in a later pass.
   pub fn eval(&mut self) {
+    self.eval_delta(false);
+  }
+
+  fn eval_delta(&mut self, events: bool) {
+    let settled = eval_delta_cycles(|| {
+      self.eval_pass(events);
+      self.commit(events)
+    });
+    if !settled {
+      // One more pass to find the nets still changing
+      let mut before = vec![];
+      self.collect_signal_values(&mut before);
+      self.eval_pass(events);
+      self.commit(events);
+      let mut nets = vec![];
+      self.collect_changed_signals("", &before, &mut 0, &mut nets);
+      self.unsettled.get_or_insert(nets);
+    }
+  }
+
+  /// Record nets of a flat module still changing after `MAX_DELTA_CYCLES` passes, named like
+  /// `eval_until_stable` does. Kept until `take_eval_error`.
+  pub(crate) fn record_unsettled(&mut self, changed: impl IntoIterator<Item = SignalIndex>) {
+    let nets = changed
+      .into_iter()
+      .map(|idx| match self.signals[idx].get_name() {
+        "" => format!("${idx}"),
+        name => name.to_string(),
+      })
+      .collect();
+    self.unsettled.get_or_insert(nets);
+  }
+
+  /// Write outputs of everything sampled in the last pass, returns if any output changed.
+  fn commit(&mut self, events: bool) -> bool {
+    let mut changed = false;
+    for idx in std::mem::take(&mut self.sampled) {
+      let component_changed = match &mut self.components[idx] {
+        Component::Cell(cell) => cell.commit(&mut self.signals),
+        Component::Word(word_cell) => word_cell.commit(&mut self.signals),
+        Component::Memory(memory) => memory.commit(&mut self.signals),
+        Component::Xilinx(xilinx_cell) => xilinx_cell.commit(&mut self.signals),
+        // Submodule outputs are copied out when it's next visited
+        Component::Module(module) => {
+          changed |= module.commit(events);
+          continue;
+        }
+        _ => false,
+      };
+      if events && component_changed {
+        self.events.notify_drives(idx);
+      }
+      changed |= component_changed;
+    }
+    changed
+  }
+
+  /// Evaluate until no signal changes, returns the number of evaluations.
+  /// Fails with the nets still changing after `max_iterations` evaluations.
+  pub fn eval_until_stable(&mut self, max_iterations: usize) -> Result<usize, ModuleError> {
+    self.eval_until_stable_with(max_iterations, Self::eval)
+  }
+
+  /// Same as `eval_until_stable`, evaluating with `eval` (ex, `eval_events`).
+  pub fn eval_until_stable_with(
+    &mut self,
+    max_iterations: usize,
+    mut eval: impl FnMut(&mut Self),
+  ) -> Result<usize, ModuleError> {
+    let mut before = vec![];
+    self.collect_signal_values(&mut before);
+    let mut after = Vec::with_capacity(before.len());
+    for iteration in 1..=max_iterations {
+      eval(self);
+      after.clear();
+      self.collect_signal_values(&mut after);
+      if after == before {
+        return Ok(iteration);
+      }
+      if iteration == max_iterations {
+        let mut nets = vec![];
+        self.collect_changed_signals("", &before, &mut 0, &mut nets);
+        return Err(ModuleError::Oscillation(max_iterations, nets));
+      }
+      std::mem::swap(&mut before, &mut after);
+    }
+    Err(ModuleError::Oscillation(max_iterations, vec![]))
+  }
+
+  /// Values of every signal, including those in submodules.
+  fn collect_signal_values(&self, values: &mut Vec<Bit>) {
+    values.extend(self.signals.iter().map(|signal| signal.get_value()));
+    for component in &self.components {
+      if let Component::Module(module) = component {
+        module.collect_signal_values(values);
+      }
+    }
+  }
+
+  /// Hierarchical names of nets that differ from `values`, see `collect_signal_values`.
+  fn collect_changed_signals(
+    &self,
+    prefix: &str,
+    values: &[Bit],
+    offset: &mut usize,
+    names: &mut Vec<String>,
+  ) {
+    for (signal, value) in self.signals.iter().zip(&values[*offset..]) {
+      if signal.get_value() != *value {
+        match signal.get_name() {
+          "" => names.push(format!("{prefix}${}", signal.get_index())),
+          name => names.push(format!("{prefix}{name}")),
+        }
+      }
+    }
+    *offset += self.signals.len();
+
+    for (idx, component) in self.components.iter().enumerate() {
+      if let Component::Module(module) = component {
+        let instance_name = self
+          .component_map
+          .iter()
+          .find(|(_, component_idx)| **component_idx == idx)
+          .map_or(idx.to_string(), |(name, _)| name.clone());
+        module.collect_changed_signals(&format!("{prefix}{instance_name}."), values, offset, names);
+      }
+    }
+  }
+
+  /// Evaluate only components whose inputs changed since they were last evaluated.
+  /// Results and statistics match `eval`, as long as signals are written through
+  /// `set_signal` or the port setters.
+  pub fn eval_events(&mut self) {
+    self.build_events();
+    self.eval_delta(true);
+  }
+
+  fn build_events(&mut self) {
+    if !self.events.is_built() {
+      self.events = EventQueue::new(self);
+    }
     for component in &mut self.components {
-      match component {
-        Component::Cell(cell) => {
-          cell.eval(&mut self.signals);
+      if let Component::Module(module) = component {
+        module.build_events();
+      }
+    }
+  }
+
+  fn eval_pass(&mut self, events: bool) {
+    if !events {
+      // Signals change without events, rebuild the index on the next event-driven pass
+      self.events.clear();
+    }
+    match self.schedule.keys().next_back().copied() {
+      Some(last_level) => (0..=last_level).for_each(|level| self.eval_level(level, events)),
+      // Not levelized, evaluate in component order
+      None => (0..self.components.len()).for_each(|idx| self.eval_component(idx, None, events)),
+    }
+  }
+
+  /// Evaluate components scheduled at `level`, including those in submodules.
+  fn eval_level(&mut self, level: usize, events: bool) {
+    if !events {
+      self.events.clear();
+    }
+    let Some(order) = self.schedule.get_mut(&level).map(std::mem::take) else {
+      return;
+    };
+    for idx in &order {
+      self.eval_component(*idx, Some(level), events);
+    }
+    self.schedule.insert(level, order);
+  }
+
+  /// Evaluate a component, submodules only evaluate `level` when given.
+  /// With `events`, the component is skipped unless its inputs changed.
+  fn eval_component(&mut self, idx: ComponentIndex, level: Option<usize>, events: bool) {
+    if events && !self.events.begin(idx, &self.signals) {
+      return;
+    }
+
+    if let Component::Module(module) = &self.components[idx] {
+      // Drive shared nets with everything outside the submodule
+      let external: Vec<(SignalIndex, Option<Bit>)> = module
+        .inout_connections
+        .iter()
+        .map(|(external_idx, internal_idx)| {
+          let value = match find_tristate_net(&self.tristate_nets, *external_idx) {
+            Some(net) => self.tristate_nets[net].resolve_excluding(&self.components, idx),
+            None => Some(self.signals[*external_idx].get_value()),
+          };
+          (*internal_idx, value)
+        })
+        .collect();
+      if let Component::Module(module) = &mut self.components[idx] {
+        for (internal_idx, value) in external {
+          module.drive_tristate(internal_idx, value);
         }
-        Component::Module(module) => {
-          // Propagate input connections
-          for (external_idx, internal_idx) in &module.input_connections {
-            let bit = self.signals[*external_idx].get_value();
+      }
+    }
+
+    match &mut self.components[idx] {
+      Component::Cell(cell) if cell.is_sequential() => {
+        cell.sample(&self.signals);
+        self.sampled.push(idx);
+      }
+      Component::Cell(cell) => {
+        cell.eval(&mut self.signals);
+      }
+      Component::Behavior(behavior_cell) => {
+        behavior_cell.eval(&mut self.signals);
+      }
+      Component::Word(word_cell) if word_cell.function.is_sequential() => {
+        word_cell.sample(&self.signals);
+        self.sampled.push(idx);
+      }
+      Component::Word(word_cell) => {
+        word_cell.eval(&mut self.signals);
+      }
+      Component::Memory(memory) => {
+        memory.sample(&self.signals);
+        self.sampled.push(idx);
+      }
+      Component::Xilinx(xilinx_cell) if xilinx_cell.function.is_sequential() => {
+        xilinx_cell.sample(&self.signals);
+        self.sampled.push(idx);
+      }
+      Component::Xilinx(xilinx_cell) => {
+        xilinx_cell.eval(&mut self.signals);
+      }
+      Component::Tristate(buffer) => {
+        buffer.eval(&self.signals);
+      }
+      Component::Model(model) => {
+        model.eval(&mut self.signals);
+      }
+      Component::Module(module) => {
+        // Propagate input connections
+        for (external_idx, internal_idx) in &module.input_connections {
+          let bit = self.signals[*external_idx].get_value();
+          if module.signals[*internal_idx].get_value() != bit {
             module.signals[*internal_idx].set_value(bit);
+            module.events.notify(*internal_idx);
           }
-          module.eval();
-          // Propagate output connections
-          for (external_idx, internal_idx) in &module.output_connections {
-            let bit = module.signals[*internal_idx].get_value();
-            self.signals[*external_idx].set_value(bit);
+        }
+        match level {
+          Some(level) => module.eval_level(level, events),
+          None => module.eval_pass(events),
+        }
+        if !module.sampled.is_empty() && self.sampled.last() != Some(&idx) {
+          self.sampled.push(idx);
+        }
+        // Propagate output connections
+        for (external_idx, internal_idx) in &module.output_connections {
+          let bit = module.signals[*internal_idx].get_value();
+          self.signals[*external_idx].set_value(bit);
+        }
+      }
+    }
+
+    // Resolve nets this component drives
+    match &self.components[idx] {
+      Component::Tristate(buffer) => {
+        for net in &buffer.nets {
+          self.tristate_nets[*net].resolve(&self.components, &mut self.signals);
+        }
+      }
+      Component::Module(module) => {
+        for (external_idx, _) in &module.inout_connections {
+          if let Some(net) = find_tristate_net(&self.tristate_nets, *external_idx) {
+            self.tristate_nets[net].resolve(&self.components, &mut self.signals);
           }
         }
       }
+      _ => (),
+    }
+
+    if events {
+      self.events.end(idx, &self.signals);
+    }
+  }
+
+  /// Drive a tri-state net from outside the module (`None` releases it) and resolve it.
+  pub fn drive_tristate(&mut self, idx: SignalIndex, value: Option<Bit>) {
+    match find_tristate_net(&self.tristate_nets, idx) {
+      Some(net) => {
+        self.tristate_nets[net].external = value;
+        self.tristate_nets[net].resolve(&self.components, &mut self.signals);
+      }
+      None => {
+        if let Some(bit) = value {
+          self.signals[idx].set_value(bit);
+        }
+      }
     }
   }
 
+  /// Notify readers of a port that was written and drive it if it is an inout port.
+  fn drive_port(&mut self, name: &str) {
+    let Some(port) = self.ports.get(name) else {
+      return;
+    };
+    for idx in &port.signal_idx_list {
+      self.events.notify(*idx);
+    }
+    if port.direction != PortDirection::InOut {
+      return;
+    }
+    for idx in port.signal_idx_list.clone() {
+      let bit = self.signals[idx].get_value();
+      self.drive_tristate(idx, Some(bit));
+    }
+  }
+
+  /// Stop driving an inout port, letting the design drive it.
+  pub fn release_port(&mut self, name: &str) -> Result<(), ModuleError> {
+    let Some(port) = self.ports.get(name) else {
+      return Err(ModuleError::MissingPort(name.to_string()));
+    };
+    if port.direction != PortDirection::InOut {
+      return Err(ModuleError::Port(name.to_string(), PortError::Direction));
+    }
+    for idx in port.signal_idx_list.clone() {
+      self.drive_tristate(idx, None);
+    }
+    Ok(())
+  }
+
+  /// Check which port bits are high-impedance (not driven from either side).
+  pub fn get_port_high_z(&self, name: &str) -> Result<Vec<bool>, ModuleError> {
+    let Some(port) = self.ports.get(name) else {
+      return Err(ModuleError::MissingPort(name.to_string()));
+    };
+    Ok(
+      port
+        .signal_idx_list
+        .iter()
+        .map(|idx| match find_tristate_net(&self.tristate_nets, *idx) {
+          Some(net) => self.tristate_nets[net].value.is_none(),
+          None => false,
+        })
+        .collect(),
+    )
+  }
+
+  /// Number of times enabled drivers disagreed on tri-state nets, including submodules.
+  pub fn get_contention_count(&self) -> usize {
+    let mut count: usize = self
+      .tristate_nets
+      .iter()
+      .map(|net| net.contention_count)
+      .sum();
+    for component in &self.components {
+      if let Component::Module(module) = component {
+        count += module.get_contention_count();
+      }
+    }
+    count
+  }
+
   pub fn reset(&mut self) {
+    self.events.clear();
+    self.sampled.clear();
     // Reset signals
     self.signals.iter_mut().for_each(|signal| signal.reset());
+    self.tristate_nets.iter_mut().for_each(|net| net.reset());
 
     // Reset components
     self
@@ -140,10 +614,40 @@ impl HardwareModule {
       .iter_mut()
       .for_each(|component| match component {
         Component::Cell(cell) => cell.reset(),
+        Component::Behavior(behavior_cell) => behavior_cell.reset(),
+        Component::Word(word_cell) => word_cell.reset(),
+        Component::Memory(memory) => memory.reset(),
+        Component::Xilinx(xilinx_cell) => xilinx_cell.reset(),
+        Component::Tristate(buffer) => buffer.reset(),
+        Component::Model(model) => model.reset(),
         Component::Module(module) => module.reset(),
       });
   }
 
+  /// Take the first error raised while evaluating since the last call: flip-flop outputs that
+  /// didn't settle within `MAX_DELTA_CYCLES` passes, then errors raised by behavioral models.
+  /// Errors of every other model are cleared.
+  pub fn take_eval_error(&mut self) -> Result<(), ModuleError> {
+    let mut first = match self.unsettled.take() {
+      Some(nets) => Err(ModuleError::Oscillation(MAX_DELTA_CYCLES, nets)),
+      None => Ok(()),
+    };
+    for component in &mut self.components {
+      let result = match component {
+        Component::Model(model) => match model.model.take_error() {
+          Some(err) => Err(ModuleError::Model(model.name.clone(), err)),
+          None => Ok(()),
+        },
+        Component::Module(module) => module.take_eval_error(),
+        _ => Ok(()),
+      };
+      if first.is_ok() {
+        first = result;
+      }
+    }
+    first
+  }
+
   pub fn set_port_shape(&mut self, name: &str, shape: &[usize; 2]) -> Result<(), ModuleError> {
     match self.ports.get_mut(name) {
       Some(port) => match port.set_shape(shape) {
@@ -176,9 +680,12 @@ impl HardwareModule {
   }
 
   pub fn set_port_bits(&mut self, name: &str, vals: &BitVec) -> Result<(), ModuleError> {
-    match self.ports.get_mut(name) {
+    match self.ports.get(name) {
       Some(port) => match port.set_bits(vals, &mut self.signals) {
-        Ok(()) => Ok(()),
+        Ok(()) => {
+          self.drive_port(name);
+          Ok(())
+        }
         Err(err) => Err(ModuleError::Port(name.to_string(), err)),
       },
       None => Err(ModuleError::MissingPort(name.to_string())),
@@ -200,9 +707,12 @@ impl HardwareModule {
     name: &str,
     val: T,
   ) -> Result<(), ModuleError> {
-    match self.ports.get_mut(name) {
+    match self.ports.get(name) {
       Some(port) => match port.set_int(val, &mut self.signals) {
-        Ok(()) => Ok(()),
+        Ok(()) => {
+          self.drive_port(name);
+          Ok(())
+        }
         Err(err) => Err(ModuleError::Port(name.to_string(), err)),
       },
       None => Err(ModuleError::MissingPort(name.to_string())),
@@ -224,9 +734,12 @@ impl HardwareModule {
     name: &str,
     vals: &[T],
   ) -> Result<(), ModuleError> {
-    match self.ports.get_mut(name) {
+    match self.ports.get(name) {
       Some(port) => match port.set_int_vec(vals, &mut self.signals) {
-        Ok(()) => Ok(()),
+        Ok(()) => {
+          self.drive_port(name);
+          Ok(())
+        }
         Err(err) => Err(ModuleError::Port(name.to_string(), err)),
       },
       None => Err(ModuleError::MissingPort(name.to_string())),
@@ -250,7 +763,10 @@ impl HardwareModule {
   ) -> Result<(), ModuleError> {
     match self.ports.get(name) {
       Some(port) => match port.set_ndarray(vals, &mut self.signals) {
-        Ok(()) => Ok(()),
+        Ok(()) => {
+          self.drive_port(name);
+          Ok(())
+        }
         Err(err) => Err(ModuleError::Port(name.to_string(), err)),
       },
       None => Err(ModuleError::MissingPort(name.to_string())),
@@ -264,16 +780,173 @@ impl HardwareModule {
     }
   }
 
+  /// Find a memory by instance path, instance name or memory name (`MEMID`), searching
+  /// submodules.
+  pub fn search_memory(&self, name: &str) -> Option<&Memory> {
+    if let Some(Component::Memory(memory)) = self.search_component(name) {
+      return Some(memory);
+    }
+
+    let instance = self.component_map.get(name).copied();
+    for (idx, component) in self.components.iter().enumerate() {
+      match component {
+        Component::Memory(memory) if instance == Some(idx) || memory_matches(memory, name) => {
+          return Some(memory)
+        }
+        Component::Module(module) => match module.search_memory(name) {
+          Some(memory) => return Some(memory),
+          None => continue,
+        },
+        _ => (),
+      }
+    }
+    None
+  }
+
+  /// Find a memory by instance path, instance name or memory name (`MEMID`), searching
+  /// submodules.
+  pub fn search_memory_mut(&mut self, name: &str) -> Option<&mut Memory> {
+    if let Some(Component::Memory(_)) = self.search_component(name) {
+      return match self.search_component_mut(name) {
+        Some(Component::Memory(memory)) => Some(memory),
+        _ => None,
+      };
+    }
+
+    let instance = self.component_map.get(name).copied();
+    for (idx, component) in self.components.iter_mut().enumerate() {
+      match component {
+        Component::Memory(memory) if instance == Some(idx) || memory_matches(memory, name) => {
+          return Some(memory)
+        }
+        Component::Module(module) => match module.search_memory_mut(name) {
+          Some(memory) => return Some(memory),
+          None => continue,
+        },
+        _ => (),
+      }
+    }
+    None
+  }
+
+  pub fn get_memory_bits(&self, name: &str) -> Result<BitVec, ModuleError> {
+    match self.search_memory(name) {
+      Some(memory) => Ok(memory.get_bits()),
+      None => Err(ModuleError::MissingMemory(name.to_string())),
+    }
+  }
+
+  pub fn set_memory_bits(&mut self, name: &str, vals: &BitVec) -> Result<(), ModuleError> {
+    match self.search_memory_mut(name) {
+      Some(memory) => match memory.set_bits(vals) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(ModuleError::Memory(name.to_string(), err)),
+      },
+      None => Err(ModuleError::MissingMemory(name.to_string())),
+    }
+  }
+
+  pub fn get_memory_words<T: PrimInt + std::ops::BitXorAssign>(
+    &self,
+    name: &str,
+  ) -> Result<Vec<T>, ModuleError> {
+    match self.search_memory(name) {
+      Some(memory) => Ok(memory.dump_words()),
+      None => Err(ModuleError::MissingMemory(name.to_string())),
+    }
+  }
+
+  pub fn set_memory_words<T: PrimInt>(
+    &mut self,
+    name: &str,
+    vals: &[T],
+  ) -> Result<(), ModuleError> {
+    match self.search_memory_mut(name) {
+      Some(memory) => match memory.load_words(vals) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(ModuleError::Memory(name.to_string(), err)),
+      },
+      None => Err(ModuleError::MissingMemory(name.to_string())),
+    }
+  }
+
+  /// Load memory contents from a `$readmemh`-style hex file.
+  pub fn load_memory_hex(&mut self, name: &str, path: &str) -> Result<(), ModuleError> {
+    let text = match std::fs::read_to_string(path) {
+      Ok(text) => text,
+      Err(err) => {
+        return Err(ModuleError::Memory(
+          name.to_string(),
+          MemoryError::Parse(err.to_string()),
+        ))
+      }
+    };
+
+    match self.search_memory_mut(name) {
+      Some(memory) => match memory.load_hex(&text) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(ModuleError::Memory(name.to_string(), err)),
+      },
+      None => Err(ModuleError::MissingMemory(name.to_string())),
+    }
+  }
+
+  pub fn get_memory_width(&self, name: &str) -> Result<usize, ModuleError> {
+    match self.search_memory(name) {
+      Some(memory) => Ok(memory.width),
+      None => Err(ModuleError::MissingMemory(name.to_string())),
+    }
+  }
+
+  pub fn get_memory_statistics(&self, name: &str) -> Result<MemoryStatistics, ModuleError> {
+    match self.search_memory(name) {
+      Some(memory) => Ok(memory.get_statistics()),
+      None => Err(ModuleError::MissingMemory(name.to_string())),
+    }
+  }
+
+  /// Find a user-defined cell by instance path or instance name, searching submodules.
+  pub fn search_behavior(&self, name: &str) -> Option<&BehaviorCell> {
+    if let Some(Component::Behavior(behavior_cell)) = self.search_component(name) {
+      return Some(behavior_cell);
+    }
+
+    for component in &self.components {
+      if let Component::Module(module) = component {
+        if let Some(behavior_cell) = module.search_behavior(name) {
+          return Some(behavior_cell);
+        }
+      }
+    }
+    None
+  }
+
+  pub fn get_behavior_statistics(&self, name: &str) -> Result<HashMap<String, usize>, ModuleError> {
+    match self.search_behavior(name) {
+      Some(behavior_cell) => Ok(behavior_cell.get_statistics()),
+      None => Err(ModuleError::MissingBehavior(name.to_string())),
+    }
+  }
+
   pub fn get_cell_breakdown(&self) -> HashMap<String, usize> {
+    if !self.instances.is_empty() {
+      return self.instance_cell_breakdown(0);
+    }
+
     let mut breakdown = HashMap::<String, usize>::new();
     for component in &self.components {
       match component {
-        Component::Cell(cell) => {
-          if !breakdown.contains_key(&cell.name) {
-            breakdown.insert(cell.name.clone(), 0);
+        Component::Cell(Cell { name, .. })
+        | Component::Behavior(BehaviorCell { name, .. })
+        | Component::Word(WordCell { name, .. })
+        | Component::Memory(Memory { name, .. })
+        | Component::Xilinx(XilinxCell { name, .. })
+        | Component::Tristate(TristateBuffer { name, .. }) => {
+          if !breakdown.contains_key(name) {
+            breakdown.insert(name.clone(), 0);
           }
 
-          *breakdown.get_mut(&cell.name).unwrap() += 1;
+          *breakdown.get_mut(name).unwrap() += 1;
         }
         Component::Module(module) => {
           for (cell_name, count) in module.get_cell_breakdown() {
@@ -283,6 +956,8 @@ impl HardwareModule {
             *breakdown.get_mut(&cell_name).unwrap() += count;
           }
         }
+        // Models stand in for a module, they aren't cells
+        Component::Model(_) => (),
       }
     }
     breakdown
@@ -292,12 +967,32 @@ impl HardwareModule {
     &self,
     name: &str,
   ) -> Result<HashMap<String, usize>, ModuleError> {
+    // Instance paths take precedence over module names
+    match self.search_instance(name) {
+      Some(InstanceRef::Module(module)) => return Ok(module.get_cell_breakdown()),
+      Some(InstanceRef::Flat(instance)) => return Ok(self.instance_cell_breakdown(instance)),
+      None => (),
+    }
+
+    if !self.instances.is_empty() {
+      return match self.find_instance(name) {
+        Some(instance) => Ok(self.instance_cell_breakdown(instance)),
+        None => Err(ModuleError::MissingModule(name.to_string())),
+      };
+    }
+
     if name == self.name {
       Ok(self.get_cell_breakdown())
     } else {
       for component in &self.components {
         match component {
-          Component::Cell(_) => continue,
+          Component::Cell(_)
+          | Component::Behavior(_)
+          | Component::Word(_)
+          | Component::Memory(_)
+          | Component::Xilinx(_)
+          | Component::Tristate(_)
+          | Component::Model(_) => continue,
           Component::Module(sub_module) => match sub_module.search_module_cell_breakdown(name) {
             Ok(breakdown) => return Ok(breakdown),
             Err(_) => continue,
@@ -310,36 +1005,149 @@ impl HardwareModule {
 
   // TODO: Add tests for these
 
-  pub fn get_total_toggle_count(&self) -> usize {
-    let mut total_toggles: usize = 0;
+  /// Toggles of the module's own nets, not including submodules. Input port nets are counted
+  /// by the parent module.
+  pub fn get_local_toggle_count(&self) -> usize {
     let input_connections: HashSet<SignalIndex> = self
       .input_connections
       .iter()
       .map(|(_, internal_idx)| *internal_idx)
       .collect();
-    self.signals.iter().for_each(|signal| {
-      if !input_connections.contains(&signal.get_index()) {
-        total_toggles += signal.get_total_toggle_count();
-      }
-    });
+    self
+      .signals
+      .iter()
+      .filter(|signal| !input_connections.contains(&signal.get_index()))
+      .map(|signal| signal.get_total_toggle_count())
+      .sum()
+  }
+
+  pub fn get_total_toggle_count(&self) -> usize {
+    if !self.instances.is_empty() {
+      return self.instance_toggle_count(0);
+    }
+
+    let mut total_toggles = self.get_local_toggle_count();
     self
       .components
       .iter()
       .for_each(|component| match component {
-        Component::Cell(_) => (),
+        Component::Cell(_)
+        | Component::Behavior(_)
+        | Component::Word(_)
+        | Component::Memory(_)
+        | Component::Xilinx(_)
+        | Component::Tristate(_)
+        | Component::Model(_) => (),
         Component::Module(module) => total_toggles += module.get_total_toggle_count(),
       });
 
     total_toggles
   }
 
+  /// Rising and falling toggles of every signal, module by module, each module followed by its
+  /// submodules in component order.
+  pub fn get_toggle_counts(&self) -> Vec<(usize, usize)> {
+    let mut toggles = vec![];
+    self.save_toggles(&mut toggles);
+    toggles
+  }
+
+  /// Overwrite toggle counts with ones from `get_toggle_counts`, leaving values alone.
+  pub fn set_toggle_counts(&mut self, toggles: &[(usize, usize)]) {
+    self.restore_toggles(toggles, &mut 0);
+  }
+
+  fn save_toggles(&self, toggles: &mut Vec<(usize, usize)>) {
+    toggles.extend(self.signals.iter().map(|signal| {
+      (
+        signal.get_toggle_count_rising(),
+        signal.get_toggle_count_falling(),
+      )
+    }));
+    for component in &self.components {
+      if let Component::Module(module) = component {
+        module.save_toggles(toggles);
+      }
+    }
+  }
+
+  fn restore_toggles(&mut self, toggles: &[(usize, usize)], offset: &mut usize) {
+    for signal in &mut self.signals {
+      if let (Signal::Net(net), Some(counts)) = (signal, toggles.get(*offset)) {
+        (net.toggle_count_rising, net.toggle_count_falling) = *counts;
+      }
+      *offset += 1;
+    }
+    for component in &mut self.components {
+      if let Component::Module(module) = component {
+        module.restore_toggles(toggles, offset);
+      }
+    }
+  }
+
+  /// Access counts of every memory, in the same order as `get_toggle_counts`.
+  pub fn get_memory_access_counts(&self) -> Vec<MemoryStatistics> {
+    let mut statistics = vec![];
+    self.save_memory_access_counts(&mut statistics);
+    statistics
+  }
+
+  /// Overwrite memory access counts with ones from `get_memory_access_counts`.
+  pub fn set_memory_access_counts(&mut self, statistics: &[MemoryStatistics]) {
+    self.restore_memory_access_counts(statistics, &mut 0);
+  }
+
+  fn save_memory_access_counts(&self, statistics: &mut Vec<MemoryStatistics>) {
+    for component in &self.components {
+      match component {
+        Component::Memory(memory) => statistics.push(memory.get_statistics()),
+        Component::Module(module) => module.save_memory_access_counts(statistics),
+        _ => {}
+      }
+    }
+  }
+
+  fn restore_memory_access_counts(&mut self, statistics: &[MemoryStatistics], offset: &mut usize) {
+    for component in &mut self.components {
+      match component {
+        Component::Memory(memory) => {
+          if let Some(counts) = statistics.get(*offset) {
+            memory.set_statistics(counts);
+          }
+          *offset += 1;
+        }
+        Component::Module(module) => module.restore_memory_access_counts(statistics, offset),
+        _ => {}
+      }
+    }
+  }
+
   pub fn search_module_total_toggle_count(&self, name: &str) -> Result<usize, ModuleError> {
+    match self.search_instance(name) {
+      Some(InstanceRef::Module(module)) => return Ok(module.get_total_toggle_count()),
+      Some(InstanceRef::Flat(instance)) => return Ok(self.instance_toggle_count(instance)),
+      None => (),
+    }
+
+    if !self.instances.is_empty() {
+      return match self.find_instance(name) {
+        Some(instance) => Ok(self.instance_toggle_count(instance)),
+        None => Err(ModuleError::MissingModule(name.to_string())),
+      };
+    }
+
     if name == self.name {
       Ok(self.get_total_toggle_count())
     } else {
       for component in &self.components {
         match component {
-          Component::Cell(_) => continue,
+          Component::Cell(_)
+          | Component::Behavior(_)
+          | Component::Word(_)
+          | Component::Memory(_)
+          | Component::Xilinx(_)
+          | Component::Tristate(_)
+          | Component::Model(_) => continue,
           Component::Module(sub_module) => {
             match sub_module.search_module_total_toggle_count(name) {
               Ok(count) => return Ok(count),
@@ -354,21 +1162,15 @@ impl HardwareModule {
 
   // need wrapper function to get input ports and not use connections
   pub fn get_module_bit_flips(&self, name: &str) -> usize {
-    if self.name == name {
-      let mut total_toggles = 0;
-
-      let input_connections: HashSet<SignalIndex> = self
-        .input_connections
-        .iter()
-        .map(|(_, internal_idx)| *internal_idx)
-        .collect();
-      self.signals.iter().for_each(|signal| {
-        if !input_connections.contains(&signal.get_index()) {
-          total_toggles += signal.get_total_toggle_count();
-        }
-      });
+    if !self.instances.is_empty() {
+      return match self.find_instance(name) {
+        Some(instance) => self.instance_bit_flips(instance),
+        None => 0,
+      };
+    }
 
-      return total_toggles;
+    if self.name == name {
+      return self.get_local_toggle_count();
     } else {
       for component in &self.components {
         match component {
@@ -382,3 +1184,8 @@ impl HardwareModule {
     0
   }
 }
+
+/// Yosys prefixes public memory names with `\`.
+fn memory_matches(memory: &Memory, name: &str) -> bool {
+  memory.memid == name || memory.memid.strip_prefix('\\') == Some(name)
+}
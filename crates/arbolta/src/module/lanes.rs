@@ -0,0 +1,364 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::hardware_module::{eval_delta_cycles, Component, ComponentIndex, ModuleError};
+use super::port::{PortDirection, PortError};
+use crate::bit::Bit;
+use crate::cell::STATE_SIZE;
+use crate::signal::{AccessSignal, Signal, SignalIndex};
+use num_traits::PrimInt;
+
+/// Stimuli evaluated together, one per bit of a lane mask.
+pub const LANES: usize = 64;
+
+/// Lane values and toggle counters of every signal.
+#[derive(Debug, Clone, Default)]
+struct LaneSignals {
+  /// Bit `i` is the value in lane `i`.
+  values: Vec<u64>,
+  constant: Vec<bool>,
+  /// Lanes holding stimuli, toggles in other lanes aren't counted.
+  active: u64,
+  /// Transitions summed over active lanes.
+  rising: Vec<usize>,
+  falling: Vec<usize>,
+}
+
+impl LaneSignals {
+  /// Write a signal, returns if any lane changed.
+  fn write(&mut self, idx: SignalIndex, value: u64) -> bool {
+    if self.constant[idx] {
+      return false;
+    }
+    let last = self.values[idx];
+    let changed = (last ^ value) & self.active;
+    self.rising[idx] += (changed & value).count_ones() as usize;
+    self.falling[idx] += (changed & last).count_ones() as usize;
+    self.values[idx] = value;
+    last != value
+  }
+}
+
+/// Bit-parallel simulation of a gate-level design, evaluating 64 independent stimuli per pass.
+/// Each lane keeps its values between calls, so it behaves as its own sequential stream.
+/// Toggles are counted per lane and summed into the design by `flush_statistics`.
+#[derive(Debug, Clone)]
+pub struct LaneDesign {
+  /// Flattened copy of the design, statistics are flushed into it.
+  pub design: Design,
+  /// Components in evaluation order.
+  order: Vec<ComponentIndex>,
+  signals: LaneSignals,
+  /// Stored data and last clock of each flip-flop, indexed by component.
+  state: Vec<[u64; STATE_SIZE]>,
+  sampled: Vec<ComponentIndex>,
+}
+
+impl LaneDesign {
+  /// Flatten a copy of a design. Only basic cells can be simulated bit-parallel.
+  pub fn new(design: &Design) -> Result<Self, DesignError> {
+    let mut design = design.clone();
+    design.module.flatten()?;
+
+    let module = &design.module;
+    for (idx, component) in module.components.iter().enumerate() {
+      if !matches!(component, Component::Cell(_)) {
+        let name = module
+          .component_map
+          .iter()
+          .find(|(_, component_idx)| **component_idx == idx)
+          .map_or(idx.to_string(), |(name, _)| name.clone());
+        return Err(ModuleError::UnsupportedLanes(name).into());
+      }
+    }
+
+    let order = match module.schedule.is_empty() {
+      true => (0..module.components.len()).collect(),
+      false => module.schedule.values().flatten().copied().collect(),
+    };
+    let lane = |bit: Bit| match bit {
+      Bit::Zero => 0,
+      Bit::One => !0,
+    };
+    let signals = LaneSignals {
+      values: module
+        .signals
+        .iter()
+        .map(|signal| lane(signal.get_value()))
+        .collect(),
+      constant: module
+        .signals
+        .iter()
+        .map(|signal| matches!(signal, Signal::Constant(_)))
+        .collect(),
+      active: !0,
+      rising: vec![0; module.signals.len()],
+      falling: vec![0; module.signals.len()],
+    };
+    let state = module
+      .components
+      .iter()
+      .map(|component| match component {
+        Component::Cell(cell) => cell.state.map(lane),
+        _ => [0; STATE_SIZE],
+      })
+      .collect();
+
+    Ok(Self {
+      design,
+      order,
+      signals,
+      state,
+      sampled: vec![],
+    })
+  }
+
+  /// Number of lanes holding stimuli, counted from lane 0.
+  pub fn set_lanes(&mut self, lanes: usize) {
+    self.signals.active = match lanes {
+      LANES.. => !0,
+      _ => (1 << lanes) - 1,
+    };
+  }
+
+  pub fn get_lanes(&self) -> usize {
+    self.signals.active.count_ones() as usize
+  }
+
+  /// Set a signal in every lane, bit `i` of `value` is lane `i`.
+  pub fn set_signal_lanes(&mut self, idx: SignalIndex, value: u64) -> Result<(), ModuleError> {
+    if idx >= self.signals.values.len() {
+      return Err(ModuleError::MissingSignalIndex(idx));
+    }
+    self.signals.write(idx, value);
+    Ok(())
+  }
+
+  pub fn get_signal_lanes(&self, idx: SignalIndex) -> Result<u64, ModuleError> {
+    match self.signals.values.get(idx) {
+      Some(value) => Ok(*value),
+      None => Err(ModuleError::MissingSignalIndex(idx)),
+    }
+  }
+
+  /// Set a port to one value per lane, lanes past `vals` keep their values.
+  pub fn set_port_lanes<T: PrimInt>(&mut self, name: &str, vals: &[T]) -> Result<(), ModuleError> {
+    let Some(port) = self.design.module.ports.get(name) else {
+      return Err(ModuleError::MissingPort(name.to_string()));
+    };
+    if port.direction == PortDirection::Output {
+      return Err(ModuleError::Port(name.to_string(), PortError::Direction));
+    }
+
+    let type_size = std::mem::size_of::<T>() * 8;
+    let kept = match vals.len() {
+      LANES.. => 0,
+      len => !((1 << len) - 1),
+    };
+    for (bit, idx) in port.signal_idx_list.iter().enumerate() {
+      let value = vals
+        .iter()
+        .take(LANES)
+        .enumerate()
+        .filter(|(_, val)| bit < type_size && (**val >> bit) & T::one() == T::one())
+        .fold(self.signals.values[*idx] & kept, |value, (lane, _)| {
+          value | (1 << lane)
+        });
+      self.signals.write(*idx, value);
+    }
+    Ok(())
+  }
+
+  /// Value of a port in each active lane, bits past the width of `T` are dropped.
+  pub fn get_port_lanes<T: PrimInt>(&self, name: &str) -> Result<Vec<T>, ModuleError> {
+    let Some(port) = self.design.module.ports.get(name) else {
+      return Err(ModuleError::MissingPort(name.to_string()));
+    };
+
+    let type_size = std::mem::size_of::<T>() * 8;
+    Ok(
+      (0..self.get_lanes())
+        .map(|lane| {
+          port
+            .signal_idx_list
+            .iter()
+            .take(type_size)
+            .enumerate()
+            .filter(|(_, idx)| (self.signals.values[**idx] >> lane) & 1 == 1)
+            .fold(T::zero(), |val, (bit, _)| val | (T::one() << bit))
+        })
+        .collect(),
+    )
+  }
+
+  /// Evaluate every lane, with the same delta cycles as `HardwareModule::eval`.
+  /// Nets that didn't settle are recorded on `design.module`, see
+  /// `HardwareModule::take_eval_error`.
+  pub fn eval(&mut self) {
+    let settled = eval_delta_cycles(|| {
+      self.eval_pass();
+      self.commit()
+    });
+    if !settled {
+      // One more pass to find the nets still changing
+      let before = self.signals.values.clone();
+      self.eval_pass();
+      self.commit();
+      let changed = (0..before.len()).filter(|idx| self.signals.values[*idx] != before[*idx]);
+      self.design.module.record_unsettled(changed);
+    }
+  }
+
+  fn eval_pass(&mut self) {
+    for idx in &self.order {
+      let Component::Cell(cell) = &self.design.module.components[*idx] else {
+        unreachable!("only cells are simulated bit-parallel");
+      };
+      if cell.is_sequential() {
+        cell.sample_lanes(&self.signals.values, &mut self.state[*idx]);
+        self.sampled.push(*idx);
+      } else {
+        let value = cell.eval_lanes(&self.signals.values);
+        self.signals.write(cell.output_connection, value);
+      }
+    }
+  }
+
+  /// Write outputs of flip-flops sampled in the last pass, returns if any changed.
+  fn commit(&mut self) -> bool {
+    let mut changed = false;
+    for idx in std::mem::take(&mut self.sampled) {
+      if let Component::Cell(cell) = &self.design.module.components[idx] {
+        changed |= self
+          .signals
+          .write(cell.output_connection, self.state[idx][0]);
+      }
+    }
+    changed
+  }
+
+  /// Pulse the design clock in every lane, see `Design::eval_clocked`.
+  pub fn eval_clocked(&mut self) -> Result<(), DesignError> {
+    let Some(clock) = self.design.clock else {
+      return Err(DesignError::ModuleError(ModuleError::MissingSignal(
+        "clock".to_string(),
+      )));
+    };
+
+    self.eval();
+    self.set_signal_lanes(clock, !0)?;
+    self.eval();
+    self.set_signal_lanes(clock, 0)?;
+    self.eval();
+    Ok(())
+  }
+
+  /// Run the design's reset sequence in every lane, see `Design::reset_clocked`.
+  pub fn reset_clocked(&mut self) -> Result<(), DesignError> {
+    let config = self.design.reset_config.clone();
+    let resets = self.design.get_resets();
+    if resets.is_empty() {
+      return Err(DesignError::ModuleError(ModuleError::MissingSignal(
+        "reset".to_string(),
+      )));
+    }
+    let statistics = match config.exclude_statistics {
+      true => Some((self.signals.rising.clone(), self.signals.falling.clone())),
+      false => None,
+    };
+
+    let lanes = |active: bool| match active {
+      true => !0,
+      false => 0,
+    };
+    for reset in &resets {
+      self.set_signal_lanes(reset.signal, lanes(!reset.active_low))?;
+    }
+    match config.clocked {
+      true => {
+        for _ in 0..config.cycles {
+          self.eval_clocked()?;
+        }
+      }
+      false => self.eval(),
+    }
+    for reset in &resets {
+      self.set_signal_lanes(reset.signal, lanes(reset.active_low))?;
+    }
+    self.eval();
+
+    if let Some((rising, falling)) = statistics {
+      (self.signals.rising, self.signals.falling) = (rising, falling);
+    }
+    Ok(())
+  }
+
+  /// Evaluate a batch of stimuli, 64 at a time. Element `i` of each input runs in lane `i % 64`,
+  /// so elements 64 apart form one sequential stream. Returns the outputs of each element.
+  pub fn eval_batch<T: PrimInt>(
+    &mut self,
+    inputs: &[(&str, &[T])],
+    outputs: &[&str],
+    clocked: bool,
+  ) -> Result<Vec<Vec<T>>, DesignError> {
+    let len = inputs.first().map_or(0, |(_, vals)| vals.len());
+    if let Some((name, vals)) = inputs.iter().find(|(_, vals)| vals.len() != len) {
+      return Err(
+        ModuleError::Port(
+          name.to_string(),
+          PortError::Shape {
+            requested: [vals.len(), 1],
+            actual: [len, 1],
+          },
+        )
+        .into(),
+      );
+    }
+
+    let mut results = vec![Vec::with_capacity(len); outputs.len()];
+    for start in (0..len).step_by(LANES) {
+      let end = (start + LANES).min(len);
+      self.set_lanes(end - start);
+      for (name, vals) in inputs {
+        self.set_port_lanes(name, &vals[start..end])?;
+      }
+      match clocked {
+        true => self.eval_clocked()?,
+        false => self.eval(),
+      }
+      self.design.module.take_eval_error()?;
+      for (result, name) in results.iter_mut().zip(outputs) {
+        result.extend(self.get_port_lanes::<T>(name)?);
+      }
+    }
+    self.set_lanes(LANES);
+    Ok(results)
+  }
+
+  /// Add toggles counted since the last flush to the design's nets, so its statistics
+  /// (toggle counts, hierarchy report) cover every lane.
+  pub fn flush_statistics(&mut self) -> &Design {
+    for (idx, signal) in self.design.module.signals.iter_mut().enumerate() {
+      if let Signal::Net(net) = signal {
+        net.toggle_count_rising += std::mem::take(&mut self.signals.rising[idx]);
+        net.toggle_count_falling += std::mem::take(&mut self.signals.falling[idx]);
+      }
+    }
+    &self.design
+  }
+
+  /// Zero every lane, flip-flop and statistic.
+  pub fn reset(&mut self) {
+    self.design.module.reset();
+    for (value, constant) in self.signals.values.iter_mut().zip(&self.signals.constant) {
+      if !constant {
+        *value = 0;
+      }
+    }
+    self.signals.rising.fill(0);
+    self.signals.falling.fill(0);
+    self.state.fill([0; STATE_SIZE]);
+    self.sampled.clear();
+  }
+}
@@ -0,0 +1,499 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::hardware_module::{Component, ComponentIndex, HardwareModule};
+use crate::cell::Function;
+use crate::primitive::word::WordFunction;
+use crate::primitive::xilinx::XilinxFunction;
+use crate::signal::{Signal, SignalIndex};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Schedules are stored as a list of levels, since flexbuffers map keys must be strings.
+pub(crate) mod schedule_serde {
+  use super::ComponentIndex;
+  use serde::{Deserialize, Deserializer, Serialize, Serializer};
+  use std::collections::BTreeMap;
+
+  pub fn serialize<S: Serializer>(
+    schedule: &BTreeMap<usize, Vec<ComponentIndex>>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    schedule.iter().collect::<Vec<_>>().serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<BTreeMap<usize, Vec<ComponentIndex>>, D::Error> {
+    Ok(
+      Vec::<(usize, Vec<ComponentIndex>)>::deserialize(deserializer)?
+        .into_iter()
+        .collect(),
+    )
+  }
+}
+
+/// Combinational cycle found while levelizing, named by hierarchical path.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct CombinationalLoop {
+  /// Cells (or submodules passing signals through) on the cycle.
+  pub cells: Vec<String>,
+  /// Nets driven and read within the cycle.
+  pub nets: Vec<String>,
+}
+
+/// Signals a component must wait on and signals it drives.
+/// Sequential elements only wait on their clock and asynchronous inputs, data is sampled
+/// from the previous evaluation.
+pub fn component_signals(component: &Component) -> (Vec<SignalIndex>, Vec<SignalIndex>) {
+  match component {
+    Component::Cell(cell) => {
+      let reads = match cell.function {
+        Function::DffPosEdge => vec![cell.input_connections[0]],
+        _ => cell.input_connections[..cell.num_inputs].to_vec(),
+      };
+      (reads, vec![cell.output_connection])
+    }
+    Component::Behavior(behavior_cell) => {
+      (behavior_cell.inputs.clone(), behavior_cell.outputs.clone())
+    }
+    Component::Word(word_cell) => {
+      let reads = if word_cell.function.is_sequential() {
+        let mut reads: Vec<SignalIndex> = word_cell.clock.iter().map(|(idx, _)| *idx).collect();
+        if matches!(word_cell.function, WordFunction::Adff | WordFunction::Adffe) {
+          reads.extend(word_cell.reset.iter().map(|(idx, _)| *idx));
+        }
+        reads
+      } else {
+        [&word_cell.a, &word_cell.b, &word_cell.s]
+          .into_iter()
+          .flatten()
+          .copied()
+          .collect()
+      };
+      (reads, word_cell.y.clone())
+    }
+    Component::Memory(memory) => {
+      let mut reads = vec![];
+      let mut drives = vec![];
+      for port in &memory.read_ports {
+        match port.clock {
+          Some((clock, _)) => reads.push(clock),
+          None => reads.extend(&port.addr),
+        }
+        reads.push(port.async_reset);
+        drives.extend(&port.data);
+      }
+      reads.extend(
+        memory
+          .write_ports
+          .iter()
+          .filter_map(|port| port.clock.map(|(idx, _)| idx)),
+      );
+      (reads, drives)
+    }
+    Component::Xilinx(xilinx_cell) => {
+      let reads = match xilinx_cell.function {
+        XilinxFunction::Fdre | XilinxFunction::Fdse => vec![xilinx_cell.inputs[0]],
+        XilinxFunction::Fdce | XilinxFunction::Fdpe => {
+          vec![xilinx_cell.inputs[0], xilinx_cell.inputs[3]]
+        }
+        _ => xilinx_cell.inputs.clone(),
+      };
+      (
+        reads,
+        xilinx_cell.outputs.iter().flatten().copied().collect(),
+      )
+    }
+    Component::Tristate(buffer) => {
+      let mut reads = buffer.inputs.clone();
+      reads.push(buffer.enable);
+      (reads, buffer.outputs.clone())
+    }
+    Component::Model(model) => (
+      model.inputs.values().flatten().copied().collect(),
+      model.outputs.values().flatten().copied().collect(),
+    ),
+    Component::Module(_) => (vec![], vec![]),
+  }
+}
+
+/// Component (or submodule visit) in the flattened design.
+pub struct Node {
+  /// Component indices from the top module.
+  pub path: Vec<ComponentIndex>,
+  /// Global net indices read.
+  pub reads: Vec<usize>,
+  /// Global net indices driven.
+  pub drives: Vec<usize>,
+  /// Hierarchical instance name.
+  pub name: String,
+}
+
+/// Flattened design, nets joined across port connections.
+#[derive(Default)]
+pub struct Graph {
+  parent: Vec<usize>,
+  /// Hierarchical name of each signal, empty for unnamed nets and constants.
+  signal_names: Vec<String>,
+  pub nodes: Vec<Node>,
+}
+
+impl Graph {
+  pub fn new(module: &HardwareModule) -> Self {
+    let mut graph = Self::default();
+    graph.add_module(module, &mut vec![], "");
+    for node_idx in 0..graph.nodes.len() {
+      for i in 0..graph.nodes[node_idx].reads.len() {
+        let net = graph.find(graph.nodes[node_idx].reads[i]);
+        graph.nodes[node_idx].reads[i] = net;
+      }
+      for i in 0..graph.nodes[node_idx].drives.len() {
+        let net = graph.find(graph.nodes[node_idx].drives[i]);
+        graph.nodes[node_idx].drives[i] = net;
+      }
+    }
+    graph
+  }
+
+  fn find(&mut self, mut idx: usize) -> usize {
+    while self.parent[idx] != idx {
+      self.parent[idx] = self.parent[self.parent[idx]];
+      idx = self.parent[idx];
+    }
+    idx
+  }
+
+  fn union(&mut self, a: usize, b: usize) {
+    let (root_a, root_b) = (self.find(a), self.find(b));
+    self.parent[root_b] = root_a;
+  }
+
+  /// Add module's components and nets, returns offset of its signals in the global net list.
+  fn add_module(
+    &mut self,
+    module: &HardwareModule,
+    path: &mut Vec<ComponentIndex>,
+    prefix: &str,
+  ) -> usize {
+    let offset = self.parent.len();
+    self.parent.extend(offset..offset + module.signals.len());
+    self
+      .signal_names
+      .extend(module.signals.iter().map(|signal| match signal {
+        Signal::Net(net) if !net.name.is_empty() => format!("{prefix}{}", net.name),
+        _ => String::new(),
+      }));
+
+    let mut instance_names: Vec<String> = (0..module.components.len())
+      .map(|idx| idx.to_string())
+      .collect();
+    for (instance_name, idx) in module.component_map.iter() {
+      instance_names[*idx] = instance_name.clone();
+    }
+
+    for (idx, component) in module.components.iter().enumerate() {
+      path.push(idx);
+      let name = format!("{prefix}{}", instance_names[idx]);
+      match component {
+        Component::Module(submodule) => {
+          let sub_offset = self.add_module(submodule, path, &format!("{name}."));
+          for (external_idx, internal_idx) in submodule
+            .input_connections
+            .iter()
+            .chain(&submodule.output_connections)
+            .chain(&submodule.inout_connections)
+          {
+            self.union(offset + external_idx, sub_offset + internal_idx);
+          }
+
+          // Pass-through and constant outputs are only copied when the submodule is visited
+          let inputs: HashMap<SignalIndex, Vec<SignalIndex>> =
+            submodule.input_connections.iter().fold(
+              HashMap::new(),
+              |mut inputs, (external_idx, internal_idx)| {
+                inputs.entry(*internal_idx).or_default().push(*external_idx);
+                inputs
+              },
+            );
+          let (mut reads, mut drives) = (vec![], vec![]);
+          for (external_idx, internal_idx) in &submodule.output_connections {
+            if *internal_idx < 2 || inputs.contains_key(internal_idx) {
+              drives.push(offset + external_idx);
+              if let Some(external_inputs) = inputs.get(internal_idx) {
+                reads.extend(external_inputs.iter().map(|idx| offset + idx));
+              }
+            }
+          }
+          if !drives.is_empty() {
+            self.nodes.push(Node {
+              path: path.clone(),
+              reads,
+              drives,
+              name,
+            });
+          }
+        }
+        _ => {
+          let (reads, drives) = component_signals(component);
+          self.nodes.push(Node {
+            path: path.clone(),
+            reads: reads.iter().map(|idx| offset + idx).collect(),
+            drives: drives.iter().map(|idx| offset + idx).collect(),
+            name,
+          });
+        }
+      }
+      path.pop();
+    }
+    offset
+  }
+
+  /// Edges from each node to the nodes reading what it drives.
+  pub fn fanout(&self) -> Vec<Vec<usize>> {
+    let mut drivers: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (node_idx, node) in self.nodes.iter().enumerate() {
+      for net in &node.drives {
+        drivers.entry(*net).or_default().push(node_idx);
+      }
+    }
+
+    let mut fanout = vec![vec![]; self.nodes.len()];
+    for (node_idx, node) in self.nodes.iter().enumerate() {
+      let mut preds = HashSet::new();
+      for net in &node.reads {
+        for driver in drivers.get(net).into_iter().flatten() {
+          if *driver != node_idx && preds.insert(*driver) {
+            fanout[*driver].push(node_idx);
+          }
+        }
+      }
+    }
+    fanout
+  }
+
+  /// Mask over global signal indices of the nets driven from `net` onwards. Sequential
+  /// elements only read their clock, so from a clock net this is the logic of its domain.
+  pub fn fanout_cone(&mut self, net: usize) -> Vec<bool> {
+    let root = self.find(net);
+    let fanout = self.fanout();
+    let mut visited = vec![false; self.nodes.len()];
+    let mut queue: Vec<usize> = vec![];
+    for (node_idx, node) in self.nodes.iter().enumerate() {
+      if node.reads.contains(&root) {
+        visited[node_idx] = true;
+        queue.push(node_idx);
+      }
+    }
+
+    let mut reached = HashSet::from([root]);
+    while let Some(node_idx) = queue.pop() {
+      reached.extend(self.nodes[node_idx].drives.iter().copied());
+      for next in &fanout[node_idx] {
+        if !visited[*next] {
+          visited[*next] = true;
+          queue.push(*next);
+        }
+      }
+    }
+    (0..self.parent.len())
+      .map(|idx| reached.contains(&self.find(idx)))
+      .collect()
+  }
+
+  /// Longest-path level of each node over its strongly connected components. Nodes on a
+  /// combinational loop share a level, logic reading the loop is placed after it.
+  pub fn levels(&self) -> Vec<usize> {
+    let fanout = self.fanout();
+    let components = strongly_connected(&fanout);
+    let mut component_of = vec![0_usize; self.nodes.len()];
+    for (component_idx, members) in components.iter().enumerate() {
+      for node_idx in members {
+        component_of[*node_idx] = component_idx;
+      }
+    }
+
+    // Components are found after everything they reach, reversed they're in topological order
+    let mut levels = vec![0_usize; self.nodes.len()];
+    for members in components.iter().rev() {
+      let level = members
+        .iter()
+        .map(|node_idx| levels[*node_idx])
+        .max()
+        .unwrap_or(0);
+      for node_idx in members {
+        levels[*node_idx] = level;
+      }
+      for node_idx in members {
+        for next in &fanout[*node_idx] {
+          if component_of[*next] != component_of[*node_idx] {
+            levels[*next] = levels[*next].max(level + 1);
+          }
+        }
+      }
+    }
+    levels
+  }
+
+  /// Combinational cycles: strongly connected nodes, and nodes reading what they drive.
+  pub fn loops(&self) -> Vec<Vec<usize>> {
+    let mut loops: Vec<Vec<usize>> = strongly_connected(&self.fanout())
+      .into_iter()
+      .filter(|members| {
+        let node = &self.nodes[members[0]];
+        members.len() > 1 || node.reads.iter().any(|net| node.drives.contains(net))
+      })
+      .map(|mut members| {
+        members.sort_by(|a, b| self.nodes[*a].path.cmp(&self.nodes[*b].path));
+        members
+      })
+      .collect();
+    loops.sort_by(|a, b| self.nodes[a[0]].path.cmp(&self.nodes[b[0]].path));
+    loops
+  }
+
+  /// Name cells and nets of each combinational cycle. Nets take the name closest to the top.
+  pub fn combinational_loops(&mut self) -> Vec<CombinationalLoop> {
+    let mut net_names: HashMap<usize, String> = HashMap::new();
+    for idx in 0..self.signal_names.len() {
+      let root = self.find(idx);
+      if !self.signal_names[idx].is_empty() && !net_names.contains_key(&root) {
+        net_names.insert(root, self.signal_names[idx].clone());
+      }
+    }
+
+    self
+      .loops()
+      .into_iter()
+      .map(|members| {
+        let driven: HashSet<usize> = members
+          .iter()
+          .flat_map(|node_idx| self.nodes[*node_idx].drives.iter().copied())
+          .collect();
+        let mut nets = vec![];
+        for node_idx in &members {
+          for net in &self.nodes[*node_idx].reads {
+            if let Some(name) = driven.contains(net).then(|| net_names.get(net)).flatten() {
+              if !nets.contains(name) {
+                nets.push(name.clone());
+              }
+            }
+          }
+        }
+        nets.sort();
+        CombinationalLoop {
+          cells: members
+            .iter()
+            .map(|node_idx| self.nodes[*node_idx].name.clone())
+            .collect(),
+          nets,
+        }
+      })
+      .collect()
+  }
+}
+
+/// Strongly connected components of a graph given as fanout edges, each component listed after
+/// every component it reaches (Tarjan's algorithm).
+fn strongly_connected(fanout: &[Vec<usize>]) -> Vec<Vec<usize>> {
+  let num_nodes = fanout.len();
+  let mut index = vec![usize::MAX; num_nodes];
+  let mut lowlink = vec![0_usize; num_nodes];
+  let mut on_stack = vec![false; num_nodes];
+  let mut stack = vec![];
+  let mut next_index = 0;
+  let mut components = vec![];
+
+  // Iterative Tarjan, deep netlists would overflow the call stack
+  for root in 0..num_nodes {
+    if index[root] != usize::MAX {
+      continue;
+    }
+    let mut calls: Vec<(usize, usize)> = vec![(root, 0)];
+    index[root] = next_index;
+    lowlink[root] = next_index;
+    next_index += 1;
+    stack.push(root);
+    on_stack[root] = true;
+
+    while let Some(&(node_idx, child)) = calls.last() {
+      if child < fanout[node_idx].len() {
+        calls.last_mut().unwrap().1 += 1;
+        let next = fanout[node_idx][child];
+        if index[next] == usize::MAX {
+          index[next] = next_index;
+          lowlink[next] = next_index;
+          next_index += 1;
+          stack.push(next);
+          on_stack[next] = true;
+          calls.push((next, 0));
+        } else if on_stack[next] {
+          lowlink[node_idx] = lowlink[node_idx].min(index[next]);
+        }
+        continue;
+      }
+
+      calls.pop();
+      if let Some(&(parent, _)) = calls.last() {
+        lowlink[parent] = lowlink[parent].min(lowlink[node_idx]);
+      }
+      if lowlink[node_idx] != index[node_idx] {
+        continue;
+      }
+      let mut component = vec![];
+      while let Some(member) = stack.pop() {
+        on_stack[member] = false;
+        component.push(member);
+        if member == node_idx {
+          break;
+        }
+      }
+      components.push(component);
+    }
+  }
+  components
+}
+
+impl HardwareModule {
+  /// Schedule components by level across the hierarchy, so one `eval` settles the
+  /// combinational logic regardless of component order.
+  /// Combinational cycles are recorded in `combinational_loops`.
+  pub fn levelize(&mut self) {
+    let mut graph = Graph::new(self);
+    let levels = graph.levels();
+    self.combinational_loops = graph.combinational_loops();
+
+    self.clear_schedule();
+    let mut order: Vec<usize> = (0..graph.nodes.len()).collect();
+    order.sort_by(|a, b| {
+      (levels[*a], &graph.nodes[*a].path).cmp(&(levels[*b], &graph.nodes[*b].path))
+    });
+
+    for node_idx in order {
+      let level = levels[node_idx];
+      let mut module: &mut HardwareModule = self;
+      for (depth, component_idx) in graph.nodes[node_idx].path.iter().enumerate() {
+        let scheduled = module.schedule.entry(level).or_default();
+        if scheduled.last() != Some(component_idx) {
+          scheduled.push(*component_idx);
+        }
+        if depth + 1 == graph.nodes[node_idx].path.len() {
+          break;
+        }
+        module = match &mut module.components[*component_idx] {
+          Component::Module(submodule) => submodule,
+          _ => unreachable!("only modules have children"),
+        };
+      }
+    }
+  }
+
+  fn clear_schedule(&mut self) {
+    self.schedule = Default::default();
+    for component in &mut self.components {
+      if let Component::Module(module) = component {
+        module.clear_schedule();
+      }
+    }
+  }
+}
@@ -1,6 +1,22 @@
 // Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
 // SPDX-License-Identifier: MIT
 
+pub mod batch;
+pub mod checkpoint;
+pub mod clock;
 pub mod design;
+pub mod dot;
+pub mod event;
+pub mod flatten;
 pub mod hardware_module;
+pub mod lanes;
+pub mod levelize;
+pub mod model;
+pub mod path;
 pub mod port;
+pub mod replay;
+pub mod report;
+pub mod saif;
+pub mod tape;
+pub mod vcd;
+pub mod verilog;
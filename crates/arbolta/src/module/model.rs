@@ -0,0 +1,85 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use crate::bit::{Bit, BitVec};
+use crate::signal::{AccessSignal, SignalIndexList, SignalList};
+use dyn_clone::DynClone;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+/// Error raised by a model during evaluation.
+pub type ModelError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Behavioral model used in place of a module definition (ex, black-box macros).
+/// Implementations must be tagged with `#[typetag::serde]` so designs can be saved and loaded.
+#[typetag::serde]
+pub trait ModuleModel: Debug + DynClone + Send + Sync {
+  /// Compute output ports from input ports.
+  /// `outputs` holds every output port with its last value; bits past a port's width are ignored.
+  fn eval(&mut self, inputs: &BTreeMap<String, BitVec>, outputs: &mut BTreeMap<String, BitVec>);
+
+  /// Clear any state held by the model.
+  fn reset(&mut self) {}
+
+  /// Take the first error raised by `eval` since the last call, if any.
+  /// Models that can fail keep their error here, see `HardwareModule::take_eval_error`.
+  fn take_error(&mut self) -> Option<ModelError> {
+    None
+  }
+}
+
+dyn_clone::clone_trait_object!(ModuleModel);
+
+/// Model instance, the only component of the module it stands in for.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ModelComponent {
+  /// Name of module being modeled.
+  pub name: String,
+  /// Input port signal indices.
+  pub inputs: BTreeMap<String, SignalIndexList>,
+  /// Output port signal indices.
+  pub outputs: BTreeMap<String, SignalIndexList>,
+  pub model: Box<dyn ModuleModel>,
+}
+
+/// Models can't be compared, only the module name and ports are.
+impl PartialEq for ModelComponent {
+  fn eq(&self, other: &Self) -> bool {
+    self.name == other.name && self.inputs == other.inputs && self.outputs == other.outputs
+  }
+}
+
+impl ModelComponent {
+  pub fn eval(&mut self, signals: &mut SignalList) {
+    let read_ports = |ports: &BTreeMap<String, SignalIndexList>| -> BTreeMap<String, BitVec> {
+      ports
+        .iter()
+        .map(|(name, idx_list)| {
+          let bits: Vec<Bit> = idx_list
+            .iter()
+            .map(|idx| signals[*idx].get_value())
+            .collect();
+          (name.clone(), BitVec::from(bits))
+        })
+        .collect()
+    };
+
+    let inputs = read_ports(&self.inputs);
+    let mut outputs = read_ports(&self.outputs);
+    self.model.eval(&inputs, &mut outputs);
+
+    for (name, idx_list) in &self.outputs {
+      let Some(bits) = outputs.get(name) else {
+        continue;
+      };
+      for (idx, bit) in idx_list.iter().zip(&bits.bits) {
+        signals[*idx].set_value(*bit);
+      }
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.model.reset();
+  }
+}
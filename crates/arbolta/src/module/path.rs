@@ -0,0 +1,351 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::hardware_module::{Component, ComponentIndex, HardwareModule, ModuleError};
+use super::port::PortError;
+use crate::bit::{Bit, BitVec};
+use crate::signal::{AccessSignal, SignalIndex};
+use num_traits::PrimInt;
+use std::collections::HashMap;
+
+/// What a hierarchical path ends in, see `HardwareModule::resolve_path`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathTarget {
+  /// The instance itself.
+  Instance,
+  /// Port of the instance.
+  Port(String),
+  /// Net bits, least significant first.
+  Signals(Vec<SignalIndex>),
+}
+
+/// Instance a path resolves to, see `HardwareModule::search_instance`.
+#[derive(Debug, Clone, Copy)]
+pub enum InstanceRef<'a> {
+  /// Submodule of a hierarchical module.
+  Module(&'a HardwareModule),
+  /// Index into `instances` of a flattened module.
+  Flat(usize),
+}
+
+/// Named nets of an instance and of the instances below it, see `HardwareModule::net_tree`.
+#[derive(Debug, Clone, Default)]
+pub struct NetTree {
+  /// Instance name, the module name for the top module.
+  pub name: String,
+  /// Submodule holding the nets, see `HardwareModule::module_at`. Empty in a flattened module.
+  pub instances: Vec<ComponentIndex>,
+  /// Instances leading here from the top module, see `HardwareModule::instance_path`.
+  pub path: Vec<usize>,
+  /// Net bit names (ex, `sum_o[3]`) and their signals. Aliases are listed separately.
+  pub nets: Vec<(String, SignalIndex)>,
+  pub children: Vec<NetTree>,
+}
+
+/// Split `path` after the longest `.`-separated prefix accepted by `matches`.
+/// Instance names can contain `.` themselves (ex, `gen_stage_0.u_add[3]`).
+fn split_name(path: &str, matches: impl Fn(&str) -> bool) -> Option<(&str, &str)> {
+  let mut end = path.len();
+  loop {
+    if matches(&path[..end]) {
+      return Some((&path[..end], path.get(end + 1..).unwrap_or("")));
+    }
+    end = path[..end].rfind('.')?;
+  }
+}
+
+impl HardwareModule {
+  /// Drop the leading top module name from a path.
+  fn strip_top<'a>(&self, path: &'a str) -> &'a str {
+    if path == self.name {
+      return "";
+    }
+    path
+      .strip_prefix(self.name.as_str())
+      .and_then(|rest| rest.strip_prefix('.'))
+      .unwrap_or(path)
+  }
+
+  /// Submodule reached by following component indices down the hierarchy.
+  pub fn module_at(&self, instances: &[ComponentIndex]) -> &HardwareModule {
+    instances
+      .iter()
+      .fold(self, |module, idx| match &module.components[*idx] {
+        Component::Module(submodule) => submodule,
+        _ => unreachable!("path instances are always submodules"),
+      })
+  }
+
+  pub fn module_at_mut(&mut self, instances: &[ComponentIndex]) -> &mut HardwareModule {
+    instances
+      .iter()
+      .fold(self, |module, idx| match &mut module.components[*idx] {
+        Component::Module(submodule) => submodule,
+        _ => unreachable!("path instances are always submodules"),
+      })
+  }
+
+  /// Follow instance names at the start of a path, returning the component index of each
+  /// submodule and the rest of the path. The top module name may lead the path.
+  pub fn resolve_instances<'a>(&self, path: &'a str) -> (Vec<ComponentIndex>, &'a str) {
+    let mut instances = vec![];
+    let mut module = self;
+    let mut rest = self.strip_top(path);
+
+    while let Some((name, tail)) = split_name(rest, |name| {
+      matches!(
+        module
+          .component_map
+          .get(name)
+          .map(|idx| &module.components[*idx]),
+        Some(Component::Module(_))
+      )
+    }) {
+      let idx = module.component_map[name];
+      instances.push(idx);
+      module = module.module_at(&[idx]);
+      rest = tail;
+    }
+    (instances, rest)
+  }
+
+  /// Resolve a hierarchical path such as `top.u_tree.gen_stage_0.u_add[3].sum_o[4]`.
+  /// Instances are matched by instance name, and the path may end in a net bit, a port,
+  /// a multi-bit net, or nothing to refer to the instance itself.
+  pub fn resolve_path(&self, path: &str) -> Result<(Vec<ComponentIndex>, PathTarget), ModuleError> {
+    let missing = || ModuleError::MissingPath(path.to_string());
+
+    if !self.instances.is_empty() {
+      // Flattened nets are already named by their path
+      let rest = self.strip_top(path);
+      if rest.is_empty() {
+        return Ok((vec![], PathTarget::Instance));
+      }
+      let (instance, name) = match split_name(rest, |name| self.find_instance_path(name).is_some())
+      {
+        Some((instance, name)) => (self.find_instance_path(instance).unwrap(), name),
+        None => (0, rest),
+      };
+      let target = if name.is_empty() {
+        PathTarget::Instance
+      } else if instance == 0 && self.ports.contains_key(name) {
+        PathTarget::Port(name.to_string())
+      } else if let Some(idx) = self.signal_map.get(rest) {
+        PathTarget::Signals(vec![*idx])
+      } else if let Some(port) = self.instances[instance].ports.get(name) {
+        PathTarget::Signals(port.signal_idx_list.clone())
+      } else {
+        PathTarget::Signals(self.find_bus(rest).ok_or_else(missing)?)
+      };
+      return Ok((vec![], target));
+    }
+
+    let (instances, name) = self.resolve_instances(path);
+    let module = self.module_at(&instances);
+    let target = if name.is_empty() {
+      PathTarget::Instance
+    } else if module.ports.contains_key(name) {
+      PathTarget::Port(name.to_string())
+    } else if let Some(idx) = module.signal_map.get(name) {
+      PathTarget::Signals(vec![*idx])
+    } else {
+      PathTarget::Signals(module.find_bus(name).ok_or_else(missing)?)
+    };
+    Ok((instances, target))
+  }
+
+  /// Bits of a multi-bit net, named `name[0]`, `name[1]`, ...
+  fn find_bus(&self, name: &str) -> Option<Vec<SignalIndex>> {
+    let bits: Vec<SignalIndex> = (0..)
+      .map_while(|i| self.signal_map.get(&format!("{name}[{i}]")).copied())
+      .collect();
+    (!bits.is_empty()).then_some(bits)
+  }
+
+  /// Flattened instance with a hierarchical instance name, relative to the top module.
+  pub fn find_instance_path(&self, path: &str) -> Option<usize> {
+    self
+      .instances
+      .iter()
+      .skip(1)
+      .position(|instance| instance.path == path)
+      .map(|idx| idx + 1)
+  }
+
+  /// Find an instance by hierarchical path, including the top module itself.
+  pub fn search_instance(&self, path: &str) -> Option<InstanceRef<'_>> {
+    match self.resolve_path(path) {
+      Ok((instances, PathTarget::Instance)) if self.instances.is_empty() => {
+        Some(InstanceRef::Module(self.module_at(&instances)))
+      }
+      Ok((_, PathTarget::Instance)) => {
+        let rest = self.strip_top(path);
+        if rest.is_empty() {
+          Some(InstanceRef::Flat(0))
+        } else {
+          self.find_instance_path(rest).map(InstanceRef::Flat)
+        }
+      }
+      _ => None,
+    }
+  }
+
+  /// Find a component by hierarchical path (ex, `u_core.u_ram`).
+  pub fn search_component(&self, path: &str) -> Option<&Component> {
+    let (instances, name) = self.resolve_instances(path);
+    let module = self.module_at(&instances);
+    module
+      .component_map
+      .get(name)
+      .map(|idx| &module.components[*idx])
+  }
+
+  pub fn search_component_mut(&mut self, path: &str) -> Option<&mut Component> {
+    let (instances, name) = self.resolve_instances(path);
+    let module = self.module_at_mut(&instances);
+    match module.component_map.get(name) {
+      Some(idx) => Some(&mut module.components[*idx]),
+      None => None,
+    }
+  }
+
+  /// Peek at the value of a net, port or bus by hierarchical path.
+  pub fn get_path_bits(&self, path: &str) -> Result<BitVec, ModuleError> {
+    let (instances, target) = self.resolve_path(path)?;
+    let module = self.module_at(&instances);
+    match target {
+      PathTarget::Instance => Err(ModuleError::MissingPath(path.to_string())),
+      PathTarget::Port(name) => module.get_port_bits(&name),
+      PathTarget::Signals(bits) => Ok(BitVec::from(
+        bits
+          .iter()
+          .map(|idx| module.signals[*idx].get_value())
+          .collect::<Vec<Bit>>(),
+      )),
+    }
+  }
+
+  /// Poke a net, port or bus by hierarchical path. Nets keep the value until they are driven.
+  pub fn set_path_bits(&mut self, path: &str, vals: &BitVec) -> Result<(), ModuleError> {
+    let (instances, target) = self.resolve_path(path)?;
+    let module = self.module_at_mut(&instances);
+    match target {
+      PathTarget::Instance => Err(ModuleError::MissingPath(path.to_string())),
+      PathTarget::Port(name) => module.set_port_bits(&name, vals),
+      PathTarget::Signals(bits) => {
+        for (idx, val) in bits.iter().zip(&vals.bits) {
+          module.set_signal(*idx, *val)?;
+        }
+        Ok(())
+      }
+    }
+  }
+
+  pub fn get_path_int<T: PrimInt + std::ops::BitXorAssign>(
+    &self,
+    path: &str,
+  ) -> Result<T, ModuleError> {
+    Ok(self.get_path_bits(path)?.to_int())
+  }
+
+  pub fn set_path_int<T: PrimInt>(&mut self, path: &str, val: T) -> Result<(), ModuleError> {
+    match BitVec::from_int(val) {
+      Ok(bits) => self.set_path_bits(path, &bits),
+      Err(_) => Err(ModuleError::Port(path.to_string(), PortError::Conversion)),
+    }
+  }
+
+  /// Instances leading to the one at `path` from the top module: component indices in a
+  /// hierarchical module, indices into `instances` in a flattened one.
+  pub fn instance_path(&self, path: &str) -> Option<Vec<usize>> {
+    match self.search_instance(path)? {
+      InstanceRef::Module(_) => Some(self.resolve_instances(path).0),
+      InstanceRef::Flat(idx) => Some(self.flat_instance_path(idx)),
+    }
+  }
+
+  fn flat_instance_path(&self, mut idx: usize) -> Vec<usize> {
+    let mut path = vec![];
+    while let Some(parent) = self.instances[idx].parent {
+      path.insert(0, idx);
+      idx = parent;
+    }
+    path
+  }
+
+  /// Named nets of every instance, mirroring the instance hierarchy whether the module is
+  /// flattened or not.
+  pub fn net_tree(&self) -> NetTree {
+    match self.instances.is_empty() {
+      true => self.module_net_tree(&self.name, &mut vec![]),
+      false => self.flat_net_tree(),
+    }
+  }
+
+  fn module_net_tree(&self, name: &str, instances: &mut Vec<ComponentIndex>) -> NetTree {
+    let mut tree = NetTree {
+      name: name.to_string(),
+      instances: instances.clone(),
+      path: instances.clone(),
+      nets: self
+        .signal_map
+        .iter()
+        .map(|(name, idx)| (name.clone(), *idx))
+        .collect(),
+      children: vec![],
+    };
+    for (instance_name, idx) in self.component_map.iter() {
+      if let Component::Module(submodule) = &self.components[*idx] {
+        instances.push(*idx);
+        let child = submodule.module_net_tree(instance_name, instances);
+        tree.children.push(child);
+        instances.pop();
+      }
+    }
+    tree
+  }
+
+  /// Rebuild the hierarchy of a flattened module from `instances`, splitting the instance path
+  /// off net names.
+  fn flat_net_tree(&self) -> NetTree {
+    let paths: HashMap<&str, usize> = self
+      .instances
+      .iter()
+      .enumerate()
+      .skip(1)
+      .map(|(idx, instance)| (instance.path.as_str(), idx))
+      .collect();
+    let mut trees: Vec<NetTree> = self
+      .instances
+      .iter()
+      .enumerate()
+      .map(|(idx, instance)| {
+        let name = match instance.parent.map(|parent| &self.instances[parent].path) {
+          Some(parent) if !parent.is_empty() => &instance.path[parent.len() + 1..],
+          Some(_) => &instance.path,
+          None => &self.name,
+        };
+        NetTree {
+          name: name.to_string(),
+          path: self.flat_instance_path(idx),
+          ..Default::default()
+        }
+      })
+      .collect();
+    for (name, idx) in self.signal_map.iter() {
+      match split_name(name, |path| paths.contains_key(path)) {
+        Some((path, net)) => trees[paths[path]].nets.push((net.to_string(), *idx)),
+        None => trees[0].nets.push((name.clone(), *idx)),
+      }
+    }
+
+    // Children always come after their parent
+    for idx in (1..trees.len()).rev() {
+      let tree = std::mem::take(&mut trees[idx]);
+      trees[self.instances[idx].parent.unwrap()]
+        .children
+        .insert(0, tree);
+    }
+    trees.swap_remove(0)
+  }
+}
@@ -13,6 +13,7 @@ use thiserror::Error;
 pub enum PortDirection {
   Input,
   Output,
+  InOut,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
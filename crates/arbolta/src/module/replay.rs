@@ -0,0 +1,290 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::hardware_module::ModuleError;
+use super::port::PortDirection;
+use crate::bit::{Bit, BitVec};
+use crate::signal::AccessSignal;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, BufReader};
+use std::process::{Command, Stdio};
+use thiserror::Error;
+
+/// Which waveform variables drive the design in `Design::replay`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayConfig {
+  /// Scope of the design's top module in the waveform (ex, `tb.u_dut`).
+  pub scope: String,
+  /// Hierarchical name of the waveform variable driving an input port, by port name. Other
+  /// input ports are driven by the variable of the same name in `scope`.
+  pub mapping: HashMap<String, String>,
+  /// Hierarchical name of the waveform variable whose rising edges mark clock cycles. Defaults
+  /// to the design's clock in `scope`.
+  pub clock: Option<String>,
+  /// Stop after this many cycles.
+  pub cycles: Option<usize>,
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+  #[error("{0}")]
+  IoError(#[from] io::Error),
+  #[error("could not convert FST waveform with `fst2vcd`: {0}")]
+  Fst(io::Error),
+  #[error("malformed waveform at line {0}: {1}")]
+  Parse(usize, String),
+  #[error("waveform does not have variable `{0}`")]
+  MissingVar(String),
+}
+
+/// Whitespace-separated tokens of a VCD file, read line by line.
+struct Tokens<R> {
+  reader: R,
+  line: usize,
+  tokens: VecDeque<String>,
+}
+
+impl<R: BufRead> Tokens<R> {
+  fn next(&mut self) -> Result<Option<String>, ReplayError> {
+    while self.tokens.is_empty() {
+      let mut line = String::new();
+      if self.reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+      }
+      self.line += 1;
+      self
+        .tokens
+        .extend(line.split_whitespace().map(str::to_string));
+    }
+    Ok(self.tokens.pop_front())
+  }
+
+  fn expect(&mut self) -> Result<String, ReplayError> {
+    self
+      .next()?
+      .ok_or_else(|| self.error("unexpected end of file"))
+  }
+
+  /// Skip to the `$end` closing the current command, returning the tokens before it.
+  fn until_end(&mut self) -> Result<Vec<String>, ReplayError> {
+    let mut tokens = vec![];
+    loop {
+      match self.expect()? {
+        token if token == "$end" => return Ok(tokens),
+        token => tokens.push(token),
+      }
+    }
+  }
+
+  fn error(&self, message: &str) -> ReplayError {
+    ReplayError::Parse(self.line, message.to_string())
+  }
+}
+
+/// Value of a VCD vector such as `b0101`, least significant bit first and resized to `width`.
+/// `x` and `z` read as 0.
+fn parse_vector(digits: &str, width: usize) -> Vec<Bit> {
+  let mut bits: Vec<Bit> = digits
+    .chars()
+    .rev()
+    .map(|digit| Bit::from(digit == '1'))
+    .collect();
+  bits.resize(width, Bit::Zero);
+  bits
+}
+
+/// Variable of the waveform read by the replay.
+struct Var {
+  width: usize,
+  value: Vec<Bit>,
+  /// A value was read, it isn't unknown anymore.
+  known: bool,
+}
+
+impl Design {
+  /// Drive input ports from a VCD waveform, or an FST one converted with GTKWave's `fst2vcd`,
+  /// running `eval_clocked` at every rising edge of its clock. Ports take the values they had
+  /// just before the edge. Returns the number of cycles run.
+  pub fn replay(&mut self, path: &str, config: &ReplayConfig) -> Result<usize, DesignError> {
+    if !path.ends_with(".fst") {
+      let file = std::fs::File::open(path).map_err(ReplayError::from)?;
+      return self.replay_vcd(BufReader::new(file), config);
+    }
+
+    let mut child = Command::new("fst2vcd")
+      .arg(path)
+      .stdout(Stdio::piped())
+      .spawn()
+      .map_err(ReplayError::Fst)?;
+    let cycles = self.replay_vcd(BufReader::new(child.stdout.take().unwrap()), config);
+    // Stop the conversion when the replay ends early
+    _ = child.kill();
+    _ = child.wait();
+    cycles
+  }
+
+  /// Replay a VCD waveform read from `reader`, see `replay`.
+  pub fn replay_vcd(
+    &mut self,
+    reader: impl BufRead,
+    config: &ReplayConfig,
+  ) -> Result<usize, DesignError> {
+    let Some(clock) = self.clock else {
+      return Err(DesignError::ModuleError(ModuleError::MissingSignal(
+        "clock".to_string(),
+      )));
+    };
+    let clock_name = self.module.signals[clock].get_name().to_string();
+    let mut tokens = Tokens {
+      reader,
+      line: 0,
+      tokens: VecDeque::new(),
+    };
+
+    // Identifier code and width of every variable, by hierarchical name
+    let mut declared: HashMap<String, (String, usize)> = HashMap::new();
+    let mut scopes: Vec<String> = vec![];
+    while let Some(token) = tokens.next()? {
+      match token.as_str() {
+        "$scope" => {
+          let words = tokens.until_end()?;
+          scopes.push(words.last().cloned().unwrap_or_default());
+        }
+        "$upscope" => {
+          scopes.pop();
+          tokens.until_end()?;
+        }
+        "$var" => {
+          let words = tokens.until_end()?;
+          let [_, width, code, reference, select @ ..] = words.as_slice() else {
+            return Err(tokens.error("incomplete `$var`").into());
+          };
+          let width = width
+            .parse()
+            .map_err(|_| tokens.error("invalid variable width"))?;
+          // Ranges are dropped, single bits kept (ex, `data [7:0]`, `data[7:0]`, `data [3]`)
+          let reference = match (reference.split_once('['), select.first()) {
+            (Some((base, range)), _) if range.contains(':') => base.to_string(),
+            (None, Some(select)) if !select.contains(':') => format!("{reference}{select}"),
+            _ => reference.clone(),
+          };
+          scopes.push(reference);
+          declared.insert(scopes.join("."), (code.clone(), width));
+          scopes.pop();
+        }
+        "$enddefinitions" => {
+          tokens.until_end()?;
+          break;
+        }
+        _ => {
+          tokens.until_end()?;
+        }
+      }
+    }
+
+    // Variables read, by identifier code
+    let mut vars: Vec<Var> = vec![];
+    let mut codes: HashMap<String, usize> = HashMap::new();
+    let mut lookup = |name: &str| -> Option<usize> {
+      let (code, width) = declared.get(name)?;
+      Some(*codes.entry(code.clone()).or_insert_with(|| {
+        vars.push(Var {
+          width: *width,
+          value: vec![Bit::Zero; *width],
+          known: false,
+        });
+        vars.len() - 1
+      }))
+    };
+    let in_scope = |name: &str| match config.scope.is_empty() {
+      true => name.to_string(),
+      false => format!("{}.{name}", config.scope),
+    };
+    let clock_var = match &config.clock {
+      Some(name) => name.clone(),
+      None => in_scope(&clock_name),
+    };
+    let clock_var = lookup(&clock_var).ok_or(ReplayError::MissingVar(clock_var))?;
+
+    let mut ports = vec![];
+    for (port_name, port) in self.module.ports.iter() {
+      if port.direction != PortDirection::Input || port.signal_idx_list == [clock] {
+        continue;
+      }
+      let name = match config.mapping.get(port_name) {
+        Some(name) => name.clone(),
+        None => in_scope(port_name),
+      };
+      // Variable and bit driving each bit of the port
+      let sources: Vec<(usize, usize)> = match lookup(&name) {
+        Some(var) => (0..port.signal_idx_list.len())
+          .map(|bit| (var, bit))
+          .collect(),
+        // Buses split into one variable per bit
+        None => (0..port.signal_idx_list.len())
+          .map(|bit| lookup(&format!("{name}[{bit}]")).map(|var| (var, 0)))
+          .collect::<Option<_>>()
+          .ok_or(ReplayError::MissingVar(name))?,
+      };
+      ports.push((port_name.clone(), sources));
+    }
+
+    let mut cycles = 0;
+    let mut pending: Vec<(usize, Vec<Bit>)> = vec![];
+    loop {
+      let token = tokens.next()?;
+      let end = token.as_ref().is_none_or(|token| token.starts_with('#'));
+      if end && !pending.is_empty() {
+        // Changes of a time step take effect after the clock edge at that time
+        let clock = &vars[clock_var];
+        let rising = pending
+          .iter()
+          .rev()
+          .find(|(var, _)| *var == clock_var)
+          .is_some_and(|(_, value)| {
+            clock.known && clock.value[0] == Bit::Zero && value[0] == Bit::One
+          });
+        if rising {
+          for (port_name, sources) in &ports {
+            let bits: Vec<Bit> = sources
+              .iter()
+              .map(|(var, bit)| vars[*var].value.get(*bit).copied().unwrap_or(Bit::Zero))
+              .collect();
+            self.module.set_port_bits(port_name, &BitVec::from(bits))?;
+          }
+          self.eval_clocked()?;
+          cycles += 1;
+        }
+        for (var, value) in pending.drain(..) {
+          vars[var].value = value;
+          vars[var].known = true;
+        }
+        if config.cycles.is_some_and(|limit| cycles >= limit) {
+          break;
+        }
+      }
+
+      let Some(token) = token else { break };
+      let (value, code) = match token.chars().next() {
+        Some('#') | Some('$') => {
+          if token == "$comment" {
+            tokens.until_end()?;
+          }
+          continue;
+        }
+        Some('b') | Some('B') => (token[1..].to_string(), tokens.expect()?),
+        Some('r') | Some('R') | Some('s') | Some('S') => {
+          tokens.expect()?;
+          continue;
+        }
+        Some(_) => (token[..1].to_string(), token[1..].to_string()),
+        None => continue,
+      };
+      if let Some(var) = codes.get(&code) {
+        pending.push((*var, parse_vector(&value, vars[*var].width)));
+      }
+    }
+    Ok(cycles)
+  }
+}
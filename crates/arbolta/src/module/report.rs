@@ -0,0 +1,291 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::hardware_module::{Component, HardwareModule};
+use crate::cell::{CellLibrary, Function};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Statistics of one instance, including every instance below it unless noted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InstanceReport {
+  /// Hierarchical path, starting with the top module name.
+  pub path: String,
+  /// Module type name.
+  pub module: String,
+  /// Index of the parent instance in the report, `None` for the top module.
+  pub parent: Option<usize>,
+  pub depth: usize,
+  /// Cell counts by cell type.
+  pub cells: BTreeMap<String, usize>,
+  pub area: f64,
+  /// Share of the top module's area.
+  pub area_percent: f64,
+  /// Area in units of the library's smallest 2-input NAND, `None` if it has none.
+  pub nand2_equivalents: Option<f64>,
+  /// Flip-flop bits.
+  pub flops: usize,
+  pub toggles: usize,
+  /// Toggles of the instance's own nets only.
+  pub local_toggles: usize,
+}
+
+/// Statistics of all instances of a module type.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModuleReport {
+  pub module: String,
+  pub instances: usize,
+  pub cells: BTreeMap<String, usize>,
+  pub area: f64,
+  pub area_percent: f64,
+  pub nand2_equivalents: Option<f64>,
+  pub flops: usize,
+  pub toggles: usize,
+}
+
+/// How rows of a `HierarchyReport` are grouped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Aggregation {
+  #[default]
+  Instance,
+  Module,
+}
+
+/// Area, cell and toggle statistics of every instance in a design.
+/// Cells missing from the cell library (ex, Xilinx primitives) count as zero area.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HierarchyReport {
+  /// Every instance, parents before their children.
+  pub instances: Vec<InstanceReport>,
+  /// Instances summed by module type, in order of first appearance.
+  pub modules: Vec<ModuleReport>,
+}
+
+impl HardwareModule {
+  /// Own statistics of every instance, parents before their children. Works for hierarchical
+  /// and flattened modules.
+  fn collect_instances(&self, reports: &mut Vec<InstanceReport>) {
+    if self.instances.is_empty() {
+      self.collect_submodules(&self.name, None, reports);
+      return;
+    }
+
+    for (idx, instance) in self.instances.iter().enumerate() {
+      let mut report = InstanceReport {
+        path: match instance.parent {
+          Some(_) => format!("{}.{}", self.name, instance.path),
+          None => self.name.clone(),
+        },
+        module: instance.module.clone(),
+        parent: instance.parent,
+        depth: instance
+          .parent
+          .map_or(0, |parent| reports[parent].depth + 1),
+        local_toggles: self.instance_bit_flips(idx),
+        ..Default::default()
+      };
+      for component_idx in &instance.components {
+        add_component(&mut report, &self.components[*component_idx]);
+      }
+      reports.push(report);
+    }
+  }
+
+  fn collect_submodules(
+    &self,
+    path: &str,
+    parent: Option<usize>,
+    reports: &mut Vec<InstanceReport>,
+  ) {
+    let idx = reports.len();
+    let mut report = InstanceReport {
+      path: path.to_string(),
+      module: self.name.clone(),
+      parent,
+      depth: parent.map_or(0, |parent| reports[parent].depth + 1),
+      local_toggles: self.get_local_toggle_count(),
+      ..Default::default()
+    };
+    for component in &self.components {
+      add_component(&mut report, component);
+    }
+    reports.push(report);
+
+    let mut instance_names: Vec<String> = (0..self.components.len())
+      .map(|idx| idx.to_string())
+      .collect();
+    for (instance_name, idx) in self.component_map.iter() {
+      instance_names[*idx] = instance_name.clone();
+    }
+    for (component_idx, component) in self.components.iter().enumerate() {
+      if let Component::Module(module) = component {
+        let child_path = format!("{path}.{}", instance_names[component_idx]);
+        module.collect_submodules(&child_path, Some(idx), reports);
+      }
+    }
+  }
+}
+
+/// Count a component's cells and flip-flops towards an instance.
+fn add_component(report: &mut InstanceReport, component: &Component) {
+  if let Some(name) = component.cell_name() {
+    *report.cells.entry(name.to_string()).or_default() += 1;
+  }
+  report.flops += component.flop_count();
+}
+
+fn breakdown_area(cells: &BTreeMap<String, usize>, cell_library: &CellLibrary) -> f64 {
+  cells
+    .iter()
+    .map(|(name, count)| *count as f64 * cell_library.get_cell_area(name).unwrap_or(0.0))
+    .sum()
+}
+
+/// Quote a CSV field if needed.
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+/// Cell counts as `NAND:4;NOT:2`.
+fn csv_cells(cells: &BTreeMap<String, usize>) -> String {
+  let cells: Vec<String> = cells
+    .iter()
+    .map(|(name, count)| format!("{name}:{count}"))
+    .collect();
+  csv_field(&cells.join(";"))
+}
+
+fn csv_nand2(nand2_equivalents: Option<f64>) -> String {
+  nand2_equivalents.map_or(String::new(), |nand2| nand2.to_string())
+}
+
+impl HierarchyReport {
+  pub fn new(module: &HardwareModule, cell_library: &CellLibrary) -> Self {
+    let mut instances = vec![];
+    module.collect_instances(&mut instances);
+
+    // Children come after their parents, so walk backwards to sum subtrees
+    for idx in (1..instances.len()).rev() {
+      let Some(parent) = instances[idx].parent else {
+        continue;
+      };
+      let child = instances[idx].clone();
+      for (name, count) in child.cells {
+        *instances[parent].cells.entry(name).or_default() += count;
+      }
+      instances[parent].flops += child.flops;
+      instances[parent].toggles += child.toggles + child.local_toggles;
+    }
+
+    let nand2_area = cell_library
+      .cells
+      .values()
+      .filter(|cell| cell.function == Function::Nand && cell.num_inputs == 2)
+      .map(|cell| cell.area)
+      .reduce(f64::min);
+    let total_area = instances
+      .first()
+      .map_or(0.0, |top| breakdown_area(&top.cells, cell_library));
+    let percent = |area: f64| {
+      if total_area > 0.0 {
+        100.0 * area / total_area
+      } else {
+        0.0
+      }
+    };
+
+    for instance in &mut instances {
+      instance.toggles += instance.local_toggles;
+      instance.area = breakdown_area(&instance.cells, cell_library);
+      instance.area_percent = percent(instance.area);
+      instance.nand2_equivalents = nand2_area.map(|nand2| instance.area / nand2);
+    }
+
+    let mut modules: Vec<ModuleReport> = vec![];
+    let mut module_map = HashMap::<&str, usize>::new();
+    for instance in &instances {
+      let idx = *module_map.entry(&instance.module).or_insert_with(|| {
+        modules.push(ModuleReport {
+          module: instance.module.clone(),
+          ..Default::default()
+        });
+        modules.len() - 1
+      });
+      let report = &mut modules[idx];
+      report.instances += 1;
+      for (name, count) in &instance.cells {
+        *report.cells.entry(name.clone()).or_default() += count;
+      }
+      report.area += instance.area;
+      report.flops += instance.flops;
+      report.toggles += instance.toggles;
+    }
+    for report in &mut modules {
+      report.area_percent = percent(report.area);
+      report.nand2_equivalents = nand2_area.map(|nand2| report.area / nand2);
+    }
+
+    Self { instances, modules }
+  }
+
+  /// Find an instance by hierarchical path.
+  pub fn get_instance(&self, path: &str) -> Option<&InstanceReport> {
+    self.instances.iter().find(|instance| instance.path == path)
+  }
+
+  pub fn get_module(&self, module: &str) -> Option<&ModuleReport> {
+    self.modules.iter().find(|report| report.module == module)
+  }
+
+  pub fn to_json(&self) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(self)
+  }
+
+  /// One row per instance or module type, cell counts are written as `NAND:4;NOT:2`.
+  pub fn to_csv(&self, aggregation: Aggregation) -> String {
+    let mut csv = String::new();
+    match aggregation {
+      Aggregation::Instance => {
+        csv.push_str(
+          "path,module,depth,area,area_percent,nand2_equivalents,flops,toggles,local_toggles,cells\n",
+        );
+        for instance in &self.instances {
+          csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            csv_field(&instance.path),
+            csv_field(&instance.module),
+            instance.depth,
+            instance.area,
+            instance.area_percent,
+            csv_nand2(instance.nand2_equivalents),
+            instance.flops,
+            instance.toggles,
+            instance.local_toggles,
+            csv_cells(&instance.cells),
+          ));
+        }
+      }
+      Aggregation::Module => {
+        csv.push_str("module,instances,area,area_percent,nand2_equivalents,flops,toggles,cells\n");
+        for report in &self.modules {
+          csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            csv_field(&report.module),
+            report.instances,
+            report.area,
+            report.area_percent,
+            csv_nand2(report.nand2_equivalents),
+            report.flops,
+            report.toggles,
+            csv_cells(&report.cells),
+          ));
+        }
+      }
+    }
+    csv
+  }
+}
@@ -0,0 +1,183 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::hardware_module::{ComponentIndex, HardwareModule};
+use super::path::NetTree;
+use crate::bit::Bit;
+use crate::signal::{AccessSignal, SignalIndex};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Switching activity recorded by `Design::eval_clocked` or `Design::step`, written in SAIF
+/// for power analysis tools. Nets are sampled after the clock rises and after it falls, each
+/// value held for half a period, and after every `step` until the next one. Toggles include
+/// every change inside a cycle.
+#[derive(Debug, Clone)]
+pub struct Saif {
+  tree: NetTree,
+  /// Clock period of `eval_clocked` in nanoseconds.
+  period: u64,
+  /// Time recorded in nanoseconds.
+  duration: u64,
+  /// Submodule and signal of every distinct net, indexing `high` and `toggles`.
+  probes: Vec<(Vec<ComponentIndex>, SignalIndex)>,
+  probe_map: HashMap<(Vec<ComponentIndex>, SignalIndex), usize>,
+  /// Time each net was at 1.
+  high: Vec<u64>,
+  /// Toggle counts when recording started, toggles since then once finished.
+  toggles: Vec<usize>,
+}
+
+fn toggle_count(
+  module: &HardwareModule,
+  (instances, idx): &(Vec<ComponentIndex>, SignalIndex),
+) -> usize {
+  let signal = &module.module_at(instances).signals[*idx];
+  signal.get_toggle_count_rising() + signal.get_toggle_count_falling()
+}
+
+/// Escape characters SAIF identifiers can't hold (ex, `sum_o\[3\]`).
+fn escape(name: &str) -> String {
+  let mut escaped = String::with_capacity(name.len());
+  for c in name.chars() {
+    if !c.is_ascii_alphanumeric() && c != '_' {
+      escaped.push('\\');
+    }
+    escaped.push(c);
+  }
+  escaped
+}
+
+impl Saif {
+  pub fn new(module: &HardwareModule, period: u64) -> Self {
+    let mut saif = Self {
+      tree: module.net_tree(),
+      period,
+      duration: 0,
+      probes: vec![],
+      probe_map: HashMap::new(),
+      high: vec![],
+      toggles: vec![],
+    };
+    let tree = std::mem::take(&mut saif.tree);
+    saif.add_probes(&tree);
+    saif.tree = tree;
+    saif.toggles = saif
+      .probes
+      .iter()
+      .map(|probe| toggle_count(module, probe))
+      .collect();
+    saif.high = vec![0; saif.probes.len()];
+    saif
+  }
+
+  fn add_probes(&mut self, tree: &NetTree) {
+    for (_, idx) in &tree.nets {
+      let probe = (tree.instances.clone(), *idx);
+      if !self.probe_map.contains_key(&probe) {
+        self.probe_map.insert(probe.clone(), self.probes.len());
+        self.probes.push(probe);
+      }
+    }
+    tree
+      .children
+      .iter()
+      .for_each(|child| self.add_probes(child));
+  }
+
+  /// Count `duration` nanoseconds holding the current net values.
+  pub(super) fn sample(&mut self, module: &HardwareModule, duration: u64) {
+    self.duration += duration;
+    for ((instances, idx), high) in self.probes.iter().zip(&mut self.high) {
+      if module.module_at(instances).signals[*idx].get_value() == Bit::One {
+        *high += duration;
+      }
+    }
+  }
+
+  /// Replace starting toggle counts with the toggles counted since. Counts put back by
+  /// restoring a checkpoint are clamped to 0.
+  fn finish(&mut self, module: &HardwareModule) {
+    for (probe, toggles) in self.probes.iter().zip(&mut self.toggles) {
+      *toggles = toggle_count(module, probe).saturating_sub(*toggles);
+    }
+  }
+
+  /// Fraction of recorded time a net was at 1, `None` for nets that aren't recorded.
+  pub fn probability(&self, instances: &[ComponentIndex], idx: SignalIndex) -> Option<f64> {
+    let probe = self.probe_map.get(&(instances.to_vec(), idx))?;
+    (self.duration > 0).then(|| self.high[*probe] as f64 / self.duration as f64)
+  }
+
+  pub fn save(&self, path: &str) -> Result<(), DesignError> {
+    std::fs::write(path, self.to_string())?;
+    Ok(())
+  }
+
+  fn write_instance(&self, f: &mut fmt::Formatter, tree: &NetTree, depth: usize) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    writeln!(f, "{indent}(INSTANCE {}", escape(&tree.name))?;
+    if !tree.nets.is_empty() {
+      writeln!(f, "{indent}  (NET")?;
+      for (name, idx) in &tree.nets {
+        let probe = self.probe_map[&(tree.instances.clone(), *idx)];
+        let t1 = self.high[probe];
+        let t0 = self.duration - t1;
+        writeln!(f, "{indent}    ({}", escape(name))?;
+        writeln!(f, "{indent}      (T0 {t0}) (T1 {t1}) (TX 0)")?;
+        writeln!(f, "{indent}      (TC {}) (IG 0)", self.toggles[probe])?;
+        writeln!(f, "{indent}    )")?;
+      }
+      writeln!(f, "{indent}  )")?;
+    }
+    for child in &tree.children {
+      self.write_instance(f, child, depth + 1)?;
+    }
+    writeln!(f, "{indent})")
+  }
+}
+
+impl fmt::Display for Saif {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "(SAIFILE")?;
+    writeln!(f, "(SAIFVERSION \"2.0\")")?;
+    writeln!(f, "(DIRECTION \"backward\")")?;
+    writeln!(f, "(DESIGN \"{}\")", self.tree.name)?;
+    writeln!(f, "(PROGRAM_NAME \"arbolta\")")?;
+    writeln!(f, "(VERSION \"{}\")", env!("CARGO_PKG_VERSION"))?;
+    writeln!(f, "(DIVIDER / )")?;
+    writeln!(f, "(TIMESCALE 1 ns)")?;
+    writeln!(f, "(DURATION {})", self.duration)?;
+    self.write_instance(f, &self.tree, 0)?;
+    writeln!(f, ")")
+  }
+}
+
+impl Design {
+  /// Record switching activity in `eval_clocked` and `step` until `finish_saif`, with a clock
+  /// period of `period` nanoseconds in `eval_clocked`. Time advanced by `step` is counted in
+  /// nanoseconds.
+  pub fn start_saif(&mut self, period: u64) {
+    self.saif = Some(Saif::new(&self.module, period));
+  }
+
+  /// Stop recording and return the activity, `None` if no recording was started.
+  pub fn finish_saif(&mut self) -> Option<Saif> {
+    let mut saif = self.saif.take()?;
+    saif.finish(&self.module);
+    Some(saif)
+  }
+
+  /// Count the values after evaluation `phase` of `eval_clocked`, held while the clock is high
+  /// (1) or low (2).
+  pub(super) fn sample_saif(&mut self, phase: u64) {
+    if let Some(saif) = &mut self.saif {
+      let duration = match phase {
+        1 => saif.period / 2,
+        _ => saif.period - saif.period / 2,
+      };
+      saif.sample(&self.module, duration);
+    }
+  }
+}
@@ -0,0 +1,264 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::hardware_module::{
+  eval_delta_cycles, Component, ComponentIndex, HardwareModule, ModuleError,
+};
+use crate::bit::Bit;
+use crate::cell::Function;
+use crate::signal::{AccessSignal, Signal};
+
+/// Operation of one tape instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+  Buf,
+  Not,
+  And,
+  Or,
+  Nand,
+  Nor,
+  Xor,
+  Xnor,
+}
+
+/// One cell lowered to the tape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+  /// Gate reading `operands[first..first + len]`.
+  Gate {
+    opcode: Opcode,
+    len: u8,
+    first: u32,
+    output: u32,
+  },
+  /// Flip-flop sampling its inputs, indexes `flops`.
+  Flop(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flop {
+  /// Cell the flip-flop was lowered from.
+  pub component: ComponentIndex,
+  pub clock: u32,
+  pub data: u32,
+  pub output: u32,
+  /// Stored data and last clock, as in `Cell::state`.
+  state: [u8; 2],
+}
+
+/// Cell-only module lowered into a linear program over a dense value array, one byte per
+/// signal. Runs the same passes and delta cycles as `HardwareModule::eval`, reading the module's
+/// signals and flip-flops before and writing them back after, so statistics stay on the module.
+#[derive(Debug, Clone, Default)]
+pub struct Tape {
+  pub instructions: Vec<Instruction>,
+  pub operands: Vec<u32>,
+  pub flops: Vec<Flop>,
+  /// Signal values, the last entry takes writes to constants.
+  values: Vec<u8>,
+  rising: Vec<usize>,
+  falling: Vec<usize>,
+  sampled: Vec<usize>,
+}
+
+fn to_byte(bit: Bit) -> u8 {
+  match bit {
+    Bit::Zero => 0,
+    Bit::One => 1,
+  }
+}
+
+/// Write a value, counting its toggle. Returns if it changed.
+fn write(
+  values: &mut [u8],
+  rising: &mut [usize],
+  falling: &mut [usize],
+  idx: usize,
+  value: u8,
+) -> bool {
+  if values[idx] == value {
+    return false;
+  }
+  match value {
+    0 => falling[idx] += 1,
+    _ => rising[idx] += 1,
+  }
+  values[idx] = value;
+  true
+}
+
+impl Tape {
+  /// Lower a module in evaluation order. Every component must be a basic cell, so hierarchical
+  /// modules have to be flattened first.
+  pub fn compile(module: &HardwareModule) -> Result<Self, ModuleError> {
+    let order: Vec<ComponentIndex> = match module.schedule.is_empty() {
+      true => (0..module.components.len()).collect(),
+      false => module.schedule.values().flatten().copied().collect(),
+    };
+    let scratch = module.signals.len();
+    let target = |idx: usize| match module.signals[idx] {
+      Signal::Constant(_) => scratch as u32,
+      Signal::Net(_) => idx as u32,
+    };
+
+    let mut tape = Self {
+      values: vec![0; scratch + 1],
+      rising: vec![0; scratch + 1],
+      falling: vec![0; scratch + 1],
+      ..Default::default()
+    };
+    for idx in order {
+      let Component::Cell(cell) = &module.components[idx] else {
+        let name = module
+          .component_map
+          .iter()
+          .find(|(_, component_idx)| **component_idx == idx)
+          .map_or(idx.to_string(), |(name, _)| name.clone());
+        return Err(ModuleError::UnsupportedTape(name));
+      };
+
+      let opcode = match cell.function {
+        Function::Buf => Opcode::Buf,
+        Function::Inverter => Opcode::Not,
+        Function::And => Opcode::And,
+        Function::Or => Opcode::Or,
+        Function::Nand => Opcode::Nand,
+        Function::Nor => Opcode::Nor,
+        Function::Xor => Opcode::Xor,
+        Function::Xnor => Opcode::Xnor,
+        Function::DffPosEdge => {
+          tape
+            .instructions
+            .push(Instruction::Flop(tape.flops.len() as u32));
+          tape.flops.push(Flop {
+            component: idx,
+            clock: cell.input_connections[0] as u32,
+            data: cell.input_connections[1] as u32,
+            output: target(cell.output_connection),
+            state: cell.state.map(to_byte),
+          });
+          continue;
+        }
+      };
+      tape.instructions.push(Instruction::Gate {
+        opcode,
+        len: cell.num_inputs as u8,
+        first: tape.operands.len() as u32,
+        output: target(cell.output_connection),
+      });
+      tape.operands.extend(
+        cell.input_connections[..cell.num_inputs]
+          .iter()
+          .map(|idx| *idx as u32),
+      );
+    }
+    Ok(tape)
+  }
+
+  /// Evaluate the module the tape was compiled from, see `HardwareModule::eval`.
+  pub fn eval(&mut self, module: &mut HardwareModule) {
+    for (value, signal) in self.values.iter_mut().zip(&module.signals) {
+      *value = to_byte(signal.get_value());
+    }
+    for flop in &mut self.flops {
+      if let Component::Cell(cell) = &module.components[flop.component] {
+        flop.state = cell.state.map(to_byte);
+      }
+    }
+
+    let settled = eval_delta_cycles(|| {
+      self.eval_pass();
+      self.commit()
+    });
+    if !settled {
+      // One more pass to find the nets still changing
+      let before = self.values.clone();
+      self.eval_pass();
+      self.commit();
+      // Skips the scratch slot constants are written to
+      let changed = (0..module.signals.len()).filter(|idx| self.values[*idx] != before[*idx]);
+      module.record_unsettled(changed);
+    }
+
+    for (idx, signal) in module.signals.iter_mut().enumerate() {
+      if let Signal::Net(net) = signal {
+        net.value = Bit::from(self.values[idx] == 1);
+        net.toggle_count_rising += std::mem::take(&mut self.rising[idx]);
+        net.toggle_count_falling += std::mem::take(&mut self.falling[idx]);
+      }
+    }
+    for flop in &self.flops {
+      if let Component::Cell(cell) = &mut module.components[flop.component] {
+        cell.state = flop.state.map(|byte| Bit::from(byte == 1));
+      }
+    }
+    // Signals changed without events, rebuild the index on the next event-driven pass
+    module.events.clear();
+  }
+
+  fn eval_pass(&mut self) {
+    let Self {
+      instructions,
+      operands,
+      flops,
+      values,
+      rising,
+      falling,
+      sampled,
+    } = self;
+
+    for instruction in instructions.iter() {
+      let (opcode, len, first, output) = match *instruction {
+        Instruction::Gate {
+          opcode,
+          len,
+          first,
+          output,
+        } => (opcode, len as usize, first as usize, output as usize),
+        Instruction::Flop(idx) => {
+          let flop = &mut flops[idx as usize];
+          let (clock, data) = (values[flop.clock as usize], values[flop.data as usize]);
+          // Detect rising edge, clock new data
+          let stored = match clock == 1 && flop.state[1] == 0 {
+            true => data,
+            false => flop.state[0],
+          };
+          flop.state = [stored, clock];
+          sampled.push(idx as usize);
+          continue;
+        }
+      };
+
+      let mut read = operands[first..first + len]
+        .iter()
+        .map(|idx| values[*idx as usize]);
+      let value = match opcode {
+        Opcode::Buf => read.next().unwrap_or(0),
+        Opcode::Not => read.next().unwrap_or(0) ^ 1,
+        Opcode::And => read.fold(1, |acc, value| acc & value),
+        Opcode::Or => read.fold(0, |acc, value| acc | value),
+        Opcode::Nand => read.fold(1, |acc, value| acc & value) ^ 1,
+        Opcode::Nor => read.fold(0, |acc, value| acc | value) ^ 1,
+        Opcode::Xor => read.fold(0, |acc, value| acc ^ value),
+        Opcode::Xnor => read.fold(0, |acc, value| acc ^ value) ^ 1,
+      };
+      write(values, rising, falling, output, value);
+    }
+  }
+
+  /// Write outputs of flip-flops sampled in the last pass, returns if any changed.
+  fn commit(&mut self) -> bool {
+    let mut changed = false;
+    for flop in self.sampled.drain(..) {
+      let flop = &self.flops[flop];
+      changed |= write(
+        &mut self.values,
+        &mut self.rising,
+        &mut self.falling,
+        flop.output as usize,
+        flop.state[0],
+      );
+    }
+    changed
+  }
+}
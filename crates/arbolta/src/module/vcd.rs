@@ -0,0 +1,336 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::hardware_module::{HardwareModule, ModuleError};
+use super::path::NetTree;
+use crate::bit::Bit;
+use crate::signal::{AccessSignal, SignalIndex};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Evaluations in one `Design::eval_clocked`: before the rising edge, after it and after the
+/// falling edge.
+const EVALS_PER_CYCLE: u64 = 3;
+
+/// What `Design::start_vcd` records.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VcdConfig {
+  /// Instance paths to dump, with every instance below them (ex, `u_core.u_alu`). Everything
+  /// is dumped when empty.
+  pub scopes: Vec<String>,
+  /// Net names to dump, without bit indices. A trailing `*` matches any suffix. Every net is
+  /// dumped when empty.
+  pub signals: Vec<String>,
+  /// First clock cycle dumped, counted by `Design::cycles`, or in rising edges of the first
+  /// clock in `Design::clocks` with `step`.
+  pub start: usize,
+  /// Clock cycle dumping stops at, `None` to dump until the end.
+  pub end: Option<usize>,
+  /// Record values after every evaluation of a cycle instead of once at its end, so values
+  /// before the clock edge and glitches between edges show up.
+  pub glitches: bool,
+}
+
+/// Net bits dumped as one VCD variable, least significant first.
+#[derive(Debug, Clone)]
+struct Var {
+  /// Submodule holding the bits, see `HardwareModule::module_at`.
+  instances: Vec<usize>,
+  bits: Vec<SignalIndex>,
+  code: String,
+  last: Option<Vec<Bit>>,
+}
+
+/// Scope of the VCD header, mirroring an instance.
+#[derive(Debug, Default)]
+struct Scope {
+  name: String,
+  /// Name, width and identifier code of each variable.
+  nets: Vec<(String, usize, String)>,
+  children: Vec<Scope>,
+}
+
+/// VCD waveform recorded by `Design::eval_clocked` or `Design::step`. Time is counted in clock
+/// cycles, or in evaluations when recording glitches, and is the design's simulated time
+/// with `step`.
+#[derive(Debug, Clone)]
+pub struct Vcd {
+  config: VcdConfig,
+  vars: Vec<Var>,
+  output: String,
+  /// Initial values were written.
+  dumped: bool,
+}
+
+/// Short printable identifier of the `idx`th variable.
+fn id_code(mut idx: usize) -> String {
+  let mut code = String::new();
+  loop {
+    code.push(char::from(b'!' + (idx % 94) as u8));
+    idx /= 94;
+    if idx == 0 {
+      return code;
+    }
+  }
+}
+
+/// Split a bit name such as `sum_o[3]` into its net name and index.
+pub(super) fn split_bit(name: &str) -> (&str, Option<usize>) {
+  name
+    .strip_suffix(']')
+    .and_then(|rest| rest.rsplit_once('['))
+    .and_then(|(net, bit)| Some((net, Some(bit.parse().ok()?))))
+    .unwrap_or((name, None))
+}
+
+/// Collects the variables of selected scopes and nets.
+struct Builder<'a> {
+  config: &'a VcdConfig,
+  /// Instances selected by `config.scopes`, as paths from the top module.
+  selected: Vec<Vec<usize>>,
+  vars: Vec<Var>,
+  /// Variable of each set of bits, so aliases share an identifier code.
+  codes: HashMap<(Vec<usize>, Vec<SignalIndex>), String>,
+}
+
+impl Builder<'_> {
+  fn selected_scope(&self, path: &[usize]) -> bool {
+    self.config.scopes.is_empty() || self.selected.iter().any(|root| path.starts_with(root))
+  }
+
+  fn selected_net(&self, name: &str) -> bool {
+    self.config.signals.is_empty()
+      || self
+        .config
+        .signals
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+          Some(prefix) => name.starts_with(prefix),
+          None => name == pattern,
+        })
+  }
+
+  /// Declare nets named `names` in `scope`. `path` selects the scope, and `instances` leads to
+  /// the module holding the signals.
+  fn add_nets<'n>(
+    &mut self,
+    scope: &mut Scope,
+    path: &[usize],
+    instances: &[usize],
+    names: impl Iterator<Item = (&'n str, SignalIndex)>,
+  ) {
+    if !self.selected_scope(path) {
+      return;
+    }
+    let mut nets: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+    for (name, idx) in names {
+      let (net, bit) = split_bit(name);
+      nets.entry(net).or_default().push((bit, name, idx));
+    }
+
+    for (net, mut bits) in nets {
+      if net.is_empty() || !self.selected_net(net) {
+        continue;
+      }
+      bits.sort();
+      // Bits of a bus are grouped into a vector, stray ones are dumped on their own
+      let bus = bits.len() > 1
+        && bits
+          .iter()
+          .enumerate()
+          .all(|(i, (bit, _, _))| *bit == Some(i));
+      let groups: Vec<(&str, Vec<SignalIndex>)> = match bus {
+        true => vec![(net, bits.iter().map(|(_, _, idx)| *idx).collect())],
+        false => bits
+          .iter()
+          .map(|(_, name, idx)| (*name, vec![*idx]))
+          .collect(),
+      };
+
+      for (name, signals) in groups {
+        let key = (instances.to_vec(), signals);
+        let code = match self.codes.get(&key) {
+          Some(code) => code.clone(),
+          None => {
+            let code = id_code(self.vars.len());
+            self.vars.push(Var {
+              instances: key.0.clone(),
+              bits: key.1.clone(),
+              code: code.clone(),
+              last: None,
+            });
+            self.codes.insert(key.clone(), code.clone());
+            code
+          }
+        };
+        let name = name.replace(char::is_whitespace, "_");
+        scope.nets.push((name, key.1.len(), code));
+      }
+    }
+  }
+
+  /// Scope of an instance and the instances below it.
+  fn add_tree(&mut self, tree: &NetTree) -> Scope {
+    let mut scope = Scope {
+      name: tree.name.clone(),
+      ..Default::default()
+    };
+    let nets = tree.nets.iter().map(|(name, idx)| (name.as_str(), *idx));
+    self.add_nets(&mut scope, &tree.path, &tree.instances, nets);
+    scope.children = tree
+      .children
+      .iter()
+      .map(|child| self.add_tree(child))
+      .collect();
+    scope
+  }
+}
+
+/// Write `scope` to the header, skipping scopes without variables.
+fn write_scope(output: &mut String, scope: &Scope) -> bool {
+  let mut body = String::new();
+  for (name, width, code) in &scope.nets {
+    match width {
+      1 => writeln!(body, "$var wire 1 {code} {name} $end"),
+      _ => writeln!(
+        body,
+        "$var wire {width} {code} {name} [{}:0] $end",
+        width - 1
+      ),
+    }
+    .unwrap();
+  }
+  let mut empty = scope.nets.is_empty();
+  for child in &scope.children {
+    empty &= !write_scope(&mut body, child);
+  }
+  if !empty {
+    let name = scope.name.replace(char::is_whitespace, "_");
+    writeln!(output, "$scope module {name} $end\n{body}$upscope $end").unwrap();
+  }
+  !empty
+}
+
+impl Vcd {
+  /// Select variables of `module` and write the header.
+  pub fn new(module: &HardwareModule, config: VcdConfig) -> Result<Self, ModuleError> {
+    let mut selected = vec![];
+    for scope in &config.scopes {
+      match module.instance_path(scope) {
+        Some(path) => selected.push(path),
+        None => return Err(ModuleError::MissingPath(scope.clone())),
+      }
+    }
+
+    let mut builder = Builder {
+      config: &config,
+      selected,
+      vars: vec![],
+      codes: HashMap::new(),
+    };
+    let scope = builder.add_tree(&module.net_tree());
+    let vars = builder.vars;
+
+    let mut output = String::from("$version arbolta $end\n$timescale 1ns $end\n");
+    write_scope(&mut output, &scope);
+    output.push_str("$enddefinitions $end\n");
+    Ok(Self {
+      config,
+      vars,
+      output,
+      dumped: false,
+    })
+  }
+
+  fn in_window(&self, cycle: usize) -> bool {
+    cycle >= self.config.start && self.config.end.is_none_or(|end| cycle < end)
+  }
+
+  /// Record values after evaluation `phase` (0 to 2) of clock cycle `cycle`.
+  fn sample(&mut self, module: &HardwareModule, cycle: usize, phase: u64) {
+    if !self.in_window(cycle) {
+      return;
+    }
+    match self.config.glitches {
+      true => self.record(module, cycle as u64 * EVALS_PER_CYCLE + phase),
+      false if phase == EVALS_PER_CYCLE - 1 => self.record(module, cycle as u64),
+      false => (),
+    }
+  }
+
+  /// Write values that changed since the last record at `time`.
+  fn record(&mut self, module: &HardwareModule, time: u64) {
+    let mut changes = String::new();
+    for var in &mut self.vars {
+      let submodule = module.module_at(&var.instances);
+      let values: Vec<Bit> = var
+        .bits
+        .iter()
+        .map(|idx| submodule.signals[*idx].get_value())
+        .collect();
+      if var.last.as_ref() == Some(&values) {
+        continue;
+      }
+      match values.as_slice() {
+        [value] => writeln!(changes, "{value}{}", var.code).unwrap(),
+        _ => {
+          changes.push('b');
+          values
+            .iter()
+            .rev()
+            .for_each(|value| write!(changes, "{value}").unwrap());
+          writeln!(changes, " {}", var.code).unwrap();
+        }
+      }
+      var.last = Some(values);
+    }
+
+    if !self.dumped {
+      self.dumped = true;
+      writeln!(self.output, "#{time}\n$dumpvars\n{changes}$end").unwrap();
+    } else if !changes.is_empty() {
+      writeln!(self.output, "#{time}").unwrap();
+      self.output.push_str(&changes);
+    }
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.output
+  }
+
+  pub fn save(&self, path: &str) -> Result<(), DesignError> {
+    std::fs::write(path, &self.output)?;
+    Ok(())
+  }
+}
+
+impl Design {
+  /// Record a VCD waveform in `eval_clocked` and `step` until `finish_vcd`, see `VcdConfig`.
+  pub fn start_vcd(&mut self, config: VcdConfig) -> Result<(), DesignError> {
+    self.vcd = Some(Vcd::new(&self.module, config)?);
+    Ok(())
+  }
+
+  /// Stop recording and return the waveform, `None` if no recording was started.
+  pub fn finish_vcd(&mut self) -> Option<Vcd> {
+    self.vcd.take()
+  }
+
+  /// Record values after evaluation `phase` of the current clock cycle.
+  pub(super) fn sample_vcd(&mut self, phase: u64) {
+    if let Some(vcd) = &mut self.vcd {
+      vcd.sample(&self.module, self.cycles, phase);
+    }
+  }
+
+  /// Record values after a `step` to simulated time `time`.
+  pub(super) fn sample_vcd_step(&mut self, time: u64) {
+    let cycle = self.clocks.first().map_or(0, |clock| clock.cycles);
+    if let Some(vcd) = &mut self.vcd {
+      if vcd.in_window(cycle) {
+        vcd.record(&self.module, time);
+      }
+    }
+  }
+}
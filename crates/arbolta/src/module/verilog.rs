@@ -0,0 +1,818 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use super::design::{Design, DesignError};
+use super::hardware_module::{Component, HardwareModule, ModuleError};
+use super::port::PortDirection;
+use super::vcd::split_bit;
+use crate::bit::Bit;
+use crate::cell::CellLibrary;
+use crate::primitive::memory::Memory;
+use crate::primitive::word::{WordCell, WordFunction};
+use crate::primitive::xilinx::XilinxCell;
+use crate::signal::{AccessSignal, Signal, SignalIndex};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+/// What `HardwareModule::to_verilog` writes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerilogConfig {
+  /// Fold submodules into one module, see `HardwareModule::flatten`. Flattened modules are
+  /// always written as one module.
+  pub flatten: bool,
+  /// Comment each port and wire with the toggle count of its bits, most significant first.
+  pub toggles: bool,
+}
+
+/// Verilog keywords, names matching one are escaped.
+const KEYWORDS: &str = "always and assign begin buf case default else end endcase endmodule for \
+  function if initial inout input integer module nand nor not or output parameter reg signed \
+  wire xnor xor";
+
+/// Name as a Verilog identifier, escaped when it isn't a simple one (ex, `\u_add.sum `).
+fn identifier(name: &str) -> String {
+  let simple = name
+    .chars()
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    && !KEYWORDS.split_whitespace().any(|keyword| keyword == name);
+  match simple {
+    true => name.to_string(),
+    false => format!("\\{} ", name.replace(char::is_whitespace, "_")),
+  }
+}
+
+/// Constant of `bits`, least significant first, in hexadecimal.
+fn literal(bits: &[Bit]) -> String {
+  if bits.is_empty() {
+    return "0".to_string();
+  }
+  let digits: String = bits
+    .chunks(4)
+    .rev()
+    .map(|nibble| {
+      let value = nibble
+        .iter()
+        .enumerate()
+        .fold(0, |value, (i, bit)| value | (bit.to_int::<u32>() << i));
+      char::from_digit(value, 16).unwrap()
+    })
+    .collect();
+  format!("{}'h{digits}", bits.len())
+}
+
+fn string(value: &str) -> String {
+  format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn flag(value: bool) -> String {
+  format!("1'b{}", value as u8)
+}
+
+/// Group pins with one entry per bit (ex, `DI[0]`, `DI[1]`) into buses.
+fn group_pins<T>(pins: impl IntoIterator<Item = (String, T)>) -> Vec<(String, Vec<T>)> {
+  let mut groups: Vec<(String, Vec<T>)> = vec![];
+  for (pin, bit) in pins {
+    let (name, index) = split_bit(&pin);
+    match groups.last_mut() {
+      Some((last, bits)) if index.is_some_and(|index| index > 0) && last == name => bits.push(bit),
+      _ => groups.push((name.to_string(), vec![bit])),
+    }
+  }
+  groups
+}
+
+/// Bit of the module body a signal is connected to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BitRef {
+  /// Bit of a declared port or wire, `None` when it is a single bit.
+  Net(usize, Option<usize>),
+  Const(Bit),
+  /// Net without a name, declared as `_<signal index>_`.
+  Unnamed(SignalIndex),
+  /// Unconnected pin bit.
+  Floating,
+}
+
+/// Port or wire declared in the module body.
+struct Decl {
+  name: String,
+  kind: &'static str,
+  signed: bool,
+  /// Signals, least significant first.
+  bits: Vec<SignalIndex>,
+}
+
+/// Names of the signals of one module.
+struct Body<'a> {
+  module: &'a HardwareModule,
+  decls: Vec<Decl>,
+  refs: HashMap<SignalIndex, BitRef>,
+  unnamed: BTreeSet<SignalIndex>,
+  /// Ports and wires driven by a bit with another name.
+  aliases: Vec<(BitRef, BitRef)>,
+}
+
+impl<'a> Body<'a> {
+  fn new(module: &'a HardwareModule) -> Self {
+    let mut decls = vec![];
+    for (direction, kind) in [
+      (PortDirection::Input, "input"),
+      (PortDirection::InOut, "inout"),
+      (PortDirection::Output, "output"),
+    ] {
+      for (name, port) in module.ports.iter() {
+        if port.direction == direction {
+          decls.push(Decl {
+            name: name.clone(),
+            kind,
+            signed: port.signed,
+            bits: port.signal_idx_list.clone(),
+          });
+        }
+      }
+    }
+
+    // Bits of a bus are declared as a vector, stray ones on their own
+    let mut nets: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+    for (name, idx) in module.signal_map.iter() {
+      let (net, bit) = split_bit(name);
+      nets
+        .entry(net)
+        .or_default()
+        .push((bit, name.as_str(), *idx));
+    }
+    for (net, mut bits) in nets {
+      if module.ports.contains_key(net) {
+        continue;
+      }
+      bits.sort();
+      let bus = bits.len() > 1
+        && bits
+          .iter()
+          .enumerate()
+          .all(|(i, (bit, _, _))| *bit == Some(i));
+      let groups: Vec<(&str, Vec<SignalIndex>)> = match bus {
+        true => vec![(net, bits.iter().map(|(_, _, idx)| *idx).collect())],
+        false => bits
+          .iter()
+          .map(|(_, name, idx)| (*name, vec![*idx]))
+          .collect(),
+      };
+      for (name, bits) in groups {
+        decls.push(Decl {
+          name: name.to_string(),
+          kind: "wire",
+          signed: false,
+          bits,
+        });
+      }
+    }
+
+    // Ports drive the other names of a signal, then the name the signal carries itself, which
+    // in a flattened module is its name in the outermost instance
+    let bit_ref = |d: usize, bit: usize| BitRef::Net(d, (decls[d].bits.len() > 1).then_some(bit));
+    let mut refs = HashMap::new();
+    for primary in [true, false] {
+      for (d, decl) in decls.iter().enumerate() {
+        for (bit, idx) in decl.bits.iter().enumerate() {
+          let name = match bit_ref(d, bit) {
+            BitRef::Net(_, Some(bit)) => format!("{}[{bit}]", decl.name),
+            _ => decl.name.clone(),
+          };
+          let signal = &module.signals[*idx];
+          let first = decl.kind != "wire" || signal.get_name() == name;
+          if first == primary && matches!(signal, Signal::Net(_)) {
+            refs.entry(*idx).or_insert(bit_ref(d, bit));
+          }
+        }
+      }
+    }
+    let mut aliases = vec![];
+    for (d, decl) in decls.iter().enumerate() {
+      for (bit, idx) in decl.bits.iter().enumerate() {
+        let source = match &module.signals[*idx] {
+          Signal::Constant(value) => BitRef::Const(*value),
+          Signal::Net(_) => refs[idx],
+        };
+        if source != bit_ref(d, bit) && decl.kind != "input" {
+          aliases.push((bit_ref(d, bit), source));
+        }
+      }
+    }
+
+    Self {
+      module,
+      decls,
+      refs,
+      unnamed: BTreeSet::new(),
+      aliases,
+    }
+  }
+
+  fn lookup(&mut self, idx: SignalIndex) -> BitRef {
+    if let Some(bit) = self.refs.get(&idx) {
+      return *bit;
+    }
+    match &self.module.signals[idx] {
+      Signal::Constant(value) => BitRef::Const(*value),
+      Signal::Net(_) => {
+        self.unnamed.insert(idx);
+        BitRef::Unnamed(idx)
+      }
+    }
+  }
+
+  /// Expression connecting `bits`, least significant first.
+  fn expr(&mut self, bits: &[SignalIndex]) -> String {
+    let refs: Vec<BitRef> = bits.iter().map(|idx| self.lookup(*idx)).collect();
+    self.refs_expr(&refs)
+  }
+
+  fn refs_expr(&self, refs: &[BitRef]) -> String {
+    let mut pieces = vec![];
+    // Runs of bits are written most significant first
+    let mut end = refs.len();
+    while end > 0 {
+      let mut start = end - 1;
+      let piece = match refs[start] {
+        BitRef::Net(d, Some(high)) => {
+          let mut low = high;
+          while start > 0 && low > 0 && refs[start - 1] == BitRef::Net(d, Some(low - 1)) {
+            start -= 1;
+            low -= 1;
+          }
+          let decl = &self.decls[d];
+          let name = identifier(&decl.name);
+          if high == low {
+            format!("{name}[{high}]")
+          } else if low == 0 && high + 1 == decl.bits.len() {
+            name
+          } else {
+            format!("{name}[{high}:{low}]")
+          }
+        }
+        BitRef::Net(d, None) => identifier(&self.decls[d].name),
+        BitRef::Unnamed(idx) => format!("_{idx}_"),
+        BitRef::Const(_) | BitRef::Floating => {
+          let constant = |bit: &BitRef| matches!(bit, BitRef::Const(_) | BitRef::Floating);
+          while start > 0 && constant(&refs[start - 1]) {
+            start -= 1;
+          }
+          let digits: String = refs[start..end]
+            .iter()
+            .rev()
+            .map(|bit| match bit {
+              BitRef::Const(value) => format!("{value}"),
+              _ => "z".to_string(),
+            })
+            .collect();
+          format!("{}'b{digits}", end - start)
+        }
+      };
+      pieces.push(piece);
+      end = start;
+    }
+    match pieces.len() {
+      0 => String::new(),
+      1 => pieces.pop().unwrap(),
+      _ => format!("{{{}}}", pieces.join(", ")),
+    }
+  }
+
+  fn toggles(&self, bits: &[SignalIndex]) -> String {
+    let counts: Vec<String> = bits
+      .iter()
+      .rev()
+      .map(|idx| {
+        self.module.signals[*idx]
+          .get_total_toggle_count()
+          .to_string()
+      })
+      .collect();
+    format!(" // toggles: {}", counts.join(" "))
+  }
+}
+
+/// Instance of a cell or module, with its parameters and pin connections.
+struct Instance {
+  cell_type: String,
+  params: Vec<(String, String)>,
+  pins: Vec<(String, String)>,
+}
+
+impl Instance {
+  fn new(cell_type: &str) -> Self {
+    Self {
+      cell_type: cell_type.to_string(),
+      params: vec![],
+      pins: vec![],
+    }
+  }
+
+  fn param(&mut self, name: &str, value: impl ToString) {
+    self.params.push((name.to_string(), value.to_string()));
+  }
+
+  fn pin(&mut self, body: &mut Body, name: &str, bits: &[SignalIndex]) {
+    self.pins.push((name.to_string(), body.expr(bits)));
+  }
+
+  fn write(&self, output: &mut String, name: &str) {
+    write!(output, "  {}", identifier(&self.cell_type)).unwrap();
+    if !self.params.is_empty() {
+      let params: Vec<String> = self
+        .params
+        .iter()
+        .map(|(param, value)| format!("    .{param}({value})"))
+        .collect();
+      write!(output, " #(\n{}\n  )", params.join(",\n")).unwrap();
+    }
+    let pins: Vec<String> = self
+      .pins
+      .iter()
+      .map(|(pin, expr)| format!("    .{pin}({expr})"))
+      .collect();
+    writeln!(
+      output,
+      " {} (\n{}\n  );",
+      identifier(name),
+      pins.join(",\n")
+    )
+    .unwrap();
+  }
+}
+
+fn word_instance(body: &mut Body, cell: &WordCell) -> Instance {
+  let mut instance = Instance::new(&cell.name);
+  if cell.function.is_sequential() {
+    instance.param("WIDTH", cell.y.len());
+    let (clock, polarity) = cell.clock.unwrap_or_default();
+    instance.param("CLK_POLARITY", format!("1'b{polarity}"));
+    instance.pin(body, "CLK", &[clock]);
+    if let Some((reset, polarity)) = cell.reset {
+      let pin = match cell.function {
+        WordFunction::Adff | WordFunction::Adffe => "ARST",
+        _ => "SRST",
+      };
+      instance.param(&format!("{pin}_POLARITY"), format!("1'b{polarity}"));
+      instance.param(&format!("{pin}_VALUE"), literal(&cell.reset_value));
+      instance.pin(body, pin, &[reset]);
+    }
+    if let Some((enable, polarity)) = cell.enable {
+      instance.param("EN_POLARITY", format!("1'b{polarity}"));
+      instance.pin(body, "EN", &[enable]);
+    }
+    instance.pin(body, "D", &cell.a);
+    instance.pin(body, "Q", &cell.y);
+    return instance;
+  }
+
+  match cell.function {
+    WordFunction::Mux | WordFunction::Pmux => {
+      instance.param("WIDTH", cell.y.len());
+      if cell.function == WordFunction::Pmux {
+        instance.param("S_WIDTH", cell.s.len());
+      }
+      instance.pin(body, "A", &cell.a);
+      instance.pin(body, "B", &cell.b);
+      instance.pin(body, "S", &cell.s);
+    }
+    _ => {
+      instance.param("A_SIGNED", flag(cell.a_signed));
+      instance.param("A_WIDTH", cell.a.len());
+      instance.pin(body, "A", &cell.a);
+      if !cell.b.is_empty() {
+        instance.param("B_SIGNED", flag(cell.b_signed));
+        instance.param("B_WIDTH", cell.b.len());
+        instance.pin(body, "B", &cell.b);
+      }
+      instance.param("Y_WIDTH", cell.y.len());
+    }
+  }
+  instance.pin(body, "Y", &cell.y);
+  instance
+}
+
+fn memory_instance(body: &mut Body, memory: &Memory) -> Instance {
+  let (reads, writes) = (&memory.read_ports, &memory.write_ports);
+  let abits = reads
+    .iter()
+    .map(|port| port.addr.len())
+    .chain(writes.iter().map(|port| port.addr.len()))
+    .next()
+    .unwrap_or(0);
+  let clocked = |clock: &Option<(SignalIndex, Bit)>| Bit::from(clock.is_some());
+  let polarity = |clock: &Option<(SignalIndex, Bit)>| clock.map_or(Bit::Zero, |(_, level)| level);
+  let clock = |clock: &Option<(SignalIndex, Bit)>| clock.map_or(0, |(idx, _)| idx);
+
+  let mut instance = Instance::new(&memory.name);
+  instance.param("MEMID", string(&memory.memid));
+  instance.param("SIZE", memory.size);
+  instance.param("OFFSET", memory.offset);
+  instance.param("ABITS", abits);
+  instance.param("WIDTH", memory.width);
+  instance.param("INIT", literal(&memory.contents));
+  instance.param("RD_PORTS", reads.len());
+  let read_params: [(&str, Vec<Bit>); 9] = [
+    (
+      "RD_CLK_ENABLE",
+      reads.iter().map(|port| clocked(&port.clock)).collect(),
+    ),
+    (
+      "RD_CLK_POLARITY",
+      reads.iter().map(|port| polarity(&port.clock)).collect(),
+    ),
+    (
+      "RD_TRANSPARENCY_MASK",
+      reads
+        .iter()
+        .flat_map(|port| port.transparent.iter().map(|bit| Bit::from(*bit)))
+        .collect(),
+    ),
+    (
+      "RD_COLLISION_X_MASK",
+      vec![Bit::Zero; reads.len() * writes.len()],
+    ),
+    ("RD_WIDE_CONTINUATION", vec![Bit::Zero; reads.len()]),
+    (
+      "RD_CE_OVER_SRST",
+      reads
+        .iter()
+        .map(|port| Bit::from(port.ce_over_srst))
+        .collect(),
+    ),
+    (
+      "RD_ARST_VALUE",
+      reads
+        .iter()
+        .flat_map(|port| port.async_reset_value.clone())
+        .collect(),
+    ),
+    (
+      "RD_SRST_VALUE",
+      reads
+        .iter()
+        .flat_map(|port| port.sync_reset_value.clone())
+        .collect(),
+    ),
+    (
+      "RD_INIT_VALUE",
+      reads
+        .iter()
+        .flat_map(|port| port.init_value.clone())
+        .collect(),
+    ),
+  ];
+  for (param, bits) in read_params {
+    instance.param(param, literal(&bits));
+  }
+  instance.param("WR_PORTS", writes.len());
+  let write_params: [(&str, Vec<Bit>); 4] = [
+    (
+      "WR_CLK_ENABLE",
+      writes.iter().map(|port| clocked(&port.clock)).collect(),
+    ),
+    (
+      "WR_CLK_POLARITY",
+      writes.iter().map(|port| polarity(&port.clock)).collect(),
+    ),
+    (
+      "WR_PRIORITY_MASK",
+      vec![Bit::Zero; writes.len() * writes.len()],
+    ),
+    ("WR_WIDE_CONTINUATION", vec![Bit::Zero; writes.len()]),
+  ];
+  for (param, bits) in write_params {
+    instance.param(param, literal(&bits));
+  }
+
+  // Per-port pins are packed, first port least significant
+  let read_pins: [(&str, Vec<SignalIndex>); 6] = [
+    (
+      "RD_CLK",
+      reads.iter().map(|port| clock(&port.clock)).collect(),
+    ),
+    ("RD_EN", reads.iter().map(|port| port.enable).collect()),
+    (
+      "RD_ARST",
+      reads.iter().map(|port| port.async_reset).collect(),
+    ),
+    (
+      "RD_SRST",
+      reads.iter().map(|port| port.sync_reset).collect(),
+    ),
+    (
+      "RD_ADDR",
+      reads.iter().flat_map(|port| port.addr.clone()).collect(),
+    ),
+    (
+      "RD_DATA",
+      reads.iter().flat_map(|port| port.data.clone()).collect(),
+    ),
+  ];
+  let write_pins: [(&str, Vec<SignalIndex>); 4] = [
+    (
+      "WR_CLK",
+      writes.iter().map(|port| clock(&port.clock)).collect(),
+    ),
+    (
+      "WR_EN",
+      writes.iter().flat_map(|port| port.enable.clone()).collect(),
+    ),
+    (
+      "WR_ADDR",
+      writes.iter().flat_map(|port| port.addr.clone()).collect(),
+    ),
+    (
+      "WR_DATA",
+      writes.iter().flat_map(|port| port.data.clone()).collect(),
+    ),
+  ];
+  for (pin, bits) in read_pins.into_iter().chain(write_pins) {
+    instance.pin(body, pin, &bits);
+  }
+  instance
+}
+
+fn xilinx_instance(body: &mut Body, cell: &XilinxCell) -> Instance {
+  let mut instance = Instance::new(&cell.name);
+  if !cell.init.is_empty() {
+    instance.param("INIT", literal(&cell.init));
+  }
+  if cell.dual_carry {
+    instance.param("CARRY_TYPE", string("DUAL_CY4"));
+  }
+  let input_pins = cell.function.input_pins();
+  for ((pin, _), inverted) in input_pins.iter().zip(&cell.inverted) {
+    if *inverted {
+      instance.param(&format!("IS_{pin}_INVERTED"), flag(true));
+    }
+  }
+
+  let inputs = input_pins
+    .into_iter()
+    .map(|(pin, _)| pin)
+    .zip(cell.inputs.iter().map(|idx| Some(*idx)));
+  let outputs = cell
+    .function
+    .output_pins()
+    .into_iter()
+    .zip(cell.outputs.iter().copied());
+  for (pin, bits) in group_pins(inputs.chain(outputs)) {
+    let refs: Vec<BitRef> = bits
+      .iter()
+      .map(|idx| idx.map_or(BitRef::Floating, |idx| body.lookup(idx)))
+      .collect();
+    let expr = match refs.iter().all(|bit| *bit == BitRef::Floating) {
+      true => String::new(),
+      false => body.refs_expr(&refs),
+    };
+    instance.pins.push((pin, expr));
+  }
+  instance
+}
+
+/// Writes each module type once.
+struct Writer<'a> {
+  cell_library: &'a CellLibrary,
+  config: &'a VerilogConfig,
+  output: String,
+  /// Submodule types to write, in the order they were found.
+  pending: Vec<&'a HardwareModule>,
+  found: HashSet<&'a str>,
+}
+
+impl<'a> Writer<'a> {
+  fn write_module(&mut self, module: &'a HardwareModule) -> Result<(), ModuleError> {
+    let mut body = Body::new(module);
+    let names: HashMap<usize, &str> = module
+      .component_map
+      .iter()
+      .map(|(name, idx)| (*idx, name.as_str()))
+      .collect();
+
+    let buffers = module.port_buffers();
+    let mut instances = String::new();
+    for (idx, component) in module.components.iter().enumerate() {
+      let name = match names.get(&idx) {
+        Some(name) => name.to_string(),
+        None => format!("_c{idx}_"),
+      };
+      let instance = match component {
+        // Buffers added by `flatten` between ports
+        Component::Cell(cell) if buffers.contains(&idx) => {
+          let output = body.expr(&[cell.output_connection]);
+          let input = body.expr(&cell.input_connections[..1]);
+          writeln!(instances, "  assign {output} = {input};").unwrap();
+          continue;
+        }
+        Component::Cell(cell) => {
+          let info = self.cell_library.cells.get(cell.name.as_str());
+          let pins = match module.cell_pins.get(cell.name.as_str()) {
+            Some(pins) if pins.len() == cell.num_inputs + 1 => pins,
+            _ => match info {
+              Some(info) if info.pins.len() == cell.num_inputs + 1 => &info.pins,
+              _ => return Err(ModuleError::MissingPins(cell.name.to_string())),
+            },
+          };
+          let mut instance = Instance::new(&cell.name);
+          let bits = cell.input_connections[..cell.num_inputs]
+            .iter()
+            .chain([&cell.output_connection]);
+          for (pin, idx) in pins.iter().zip(bits) {
+            instance.pin(&mut body, pin, &[*idx]);
+          }
+          instance
+        }
+        Component::Behavior(cell) => {
+          let mut instance = Instance::new(&cell.name);
+          let pins: Vec<&String> = match self.cell_library.behaviors.get(cell.name.as_str()) {
+            Some(info) => info.inputs.iter().chain(&info.outputs).collect(),
+            None => vec![],
+          };
+          let bits: Vec<SignalIndex> = cell.inputs.iter().chain(&cell.outputs).copied().collect();
+          if pins.is_empty() || pins.len() != cell.widths.len() {
+            // Pin widths weren't kept, all bits go to one input and one output pin
+            instance.pin(&mut body, "A", &cell.inputs);
+            instance.pin(&mut body, "Y", &cell.outputs);
+          } else {
+            let mut start = 0;
+            for (pin, width) in pins.iter().zip(&cell.widths) {
+              instance.pin(&mut body, pin, &bits[start..start + width]);
+              start += width;
+            }
+          }
+          instance
+        }
+        Component::Word(cell) => word_instance(&mut body, cell),
+        Component::Memory(memory) => memory_instance(&mut body, memory),
+        Component::Xilinx(cell) => xilinx_instance(&mut body, cell),
+        Component::Tristate(buffer) => {
+          let mut instance = Instance::new(&buffer.name);
+          let pins = match buffer.name.as_str() {
+            "$_TBUF_" => ["A", "E", "Y"],
+            "OBUFT" => ["I", "T", "O"],
+            _ => {
+              instance.param("WIDTH", buffer.outputs.len());
+              ["A", "EN", "Y"]
+            }
+          };
+          instance.pin(&mut body, pins[0], &buffer.inputs);
+          instance.pin(&mut body, pins[1], &[buffer.enable]);
+          instance.pin(&mut body, pins[2], &buffer.outputs);
+          instance
+        }
+        Component::Model(model) => {
+          let mut instance = Instance::new(&model.name);
+          let ports: BTreeMap<_, _> = model.inputs.iter().chain(&model.outputs).collect();
+          for (port, bits) in ports {
+            instance.pin(&mut body, port, bits);
+          }
+          instance
+        }
+        Component::Module(submodule) => {
+          let external: HashMap<SignalIndex, SignalIndex> = submodule
+            .input_connections
+            .iter()
+            .chain(&submodule.output_connections)
+            .chain(&submodule.inout_connections)
+            .map(|(external, internal)| (*internal, *external))
+            .collect();
+          let mut instance = Instance::new(&submodule.name);
+          for (port_name, port) in submodule.ports.iter() {
+            let refs: Vec<BitRef> = port
+              .signal_idx_list
+              .iter()
+              .map(|idx| match external.get(idx) {
+                Some(idx) => body.lookup(*idx),
+                None => BitRef::Floating,
+              })
+              .collect();
+            instance
+              .pins
+              .push((port_name.clone(), body.refs_expr(&refs)));
+          }
+          if self.found.insert(&submodule.name) {
+            self.pending.push(submodule);
+          }
+          instance
+        }
+      };
+      instance.write(&mut instances, &name);
+    }
+
+    let ports: Vec<String> = module.ports.keys().map(|name| identifier(name)).collect();
+    writeln!(
+      self.output,
+      "module {}({});",
+      identifier(&module.name),
+      ports.join(", ")
+    )
+    .unwrap();
+    for decl in &body.decls {
+      let signed = if decl.signed { " signed" } else { "" };
+      let range = match decl.bits.len() {
+        1 => String::new(),
+        width => format!(" [{}:0]", width - 1),
+      };
+      let toggles = match self.config.toggles {
+        true => body.toggles(&decl.bits),
+        false => String::new(),
+      };
+      writeln!(
+        self.output,
+        "  {}{signed}{range} {};{toggles}",
+        decl.kind,
+        identifier(&decl.name)
+      )
+      .unwrap();
+    }
+    for idx in &body.unnamed {
+      let toggles = match self.config.toggles {
+        true => body.toggles(&[*idx]),
+        false => String::new(),
+      };
+      writeln!(self.output, "  wire _{idx}_;{toggles}").unwrap();
+    }
+
+    // Consecutive bits of a net driven by other names are assigned together
+    let mut runs: Vec<(Vec<BitRef>, Vec<BitRef>)> = vec![];
+    for (target, source) in &body.aliases {
+      match (runs.last_mut(), target) {
+        (Some((targets, sources)), BitRef::Net(d, Some(bit)))
+          if *bit > 0 && targets.last() == Some(&BitRef::Net(*d, Some(bit - 1))) =>
+        {
+          targets.push(*target);
+          sources.push(*source);
+        }
+        _ => runs.push((vec![*target], vec![*source])),
+      }
+    }
+    for (targets, sources) in runs {
+      writeln!(
+        self.output,
+        "  assign {} = {};",
+        body.refs_expr(&targets),
+        body.refs_expr(&sources)
+      )
+      .unwrap();
+    }
+    self.output.push_str(&instances);
+    self.output.push_str("endmodule\n");
+    Ok(())
+  }
+}
+
+impl HardwareModule {
+  /// Structural Verilog netlist of the module, with one module per submodule type. Cells are
+  /// instantiated by pin name, Yosys coarse-grain cells with the parameters they were built
+  /// from.
+  pub fn to_verilog(
+    &self,
+    cell_library: &CellLibrary,
+    config: &VerilogConfig,
+  ) -> Result<String, ModuleError> {
+    let flat;
+    let module = match config.flatten && self.instances.is_empty() {
+      true => {
+        let mut module = self.clone();
+        module.flatten()?;
+        flat = module;
+        &flat
+      }
+      false => self,
+    };
+
+    let mut writer = Writer {
+      cell_library,
+      config,
+      output: String::new(),
+      pending: vec![],
+      found: HashSet::from([module.name.as_str()]),
+    };
+    writer.write_module(module)?;
+    let mut written = 0;
+    while written < writer.pending.len() {
+      writer.output.push('\n');
+      writer.write_module(writer.pending[written])?;
+      written += 1;
+    }
+    Ok(writer.output)
+  }
+}
+
+impl Design {
+  /// Structural Verilog netlist of the design, see `HardwareModule::to_verilog`.
+  pub fn to_verilog(&self, config: &VerilogConfig) -> Result<String, DesignError> {
+    Ok(self.module.to_verilog(&self.cell_library, config)?)
+  }
+
+  pub fn save_verilog(&self, path: &str, config: &VerilogConfig) -> Result<(), DesignError> {
+    std::fs::write(path, self.to_verilog(config)?)?;
+    Ok(())
+  }
+}
@@ -0,0 +1,501 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use crate::bit::{Bit, BitVec};
+use crate::signal::{AccessSignal, SignalIndex, SignalIndexList, SignalList};
+use crate::synth::netlist::SynthCell;
+use num_traits::PrimInt;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MemoryError {
+  #[error("unsupported cell type `{0}`")]
+  UnsupportedType(String),
+  #[error("missing parameter `{0}`")]
+  MissingParameter(String),
+  #[error("memory has {size} words but {requested} were given")]
+  Size { size: usize, requested: usize },
+  #[error("couldn't parse memory contents: {0}")]
+  Parse(String),
+}
+
+/// Read port of a memory.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct MemoryReadPort {
+  /// Clock signal index and active edge level, `None` for asynchronous reads.
+  pub clock: Option<(SignalIndex, Bit)>,
+  pub enable: SignalIndex,
+  pub async_reset: SignalIndex,
+  pub sync_reset: SignalIndex,
+  pub addr: SignalIndexList,
+  pub data: SignalIndexList,
+  /// Write ports whose data is forwarded when writing the address being read.
+  pub transparent: Vec<bool>,
+  /// Enable has priority over synchronous reset.
+  pub ce_over_srst: bool,
+  pub async_reset_value: Vec<Bit>,
+  pub sync_reset_value: Vec<Bit>,
+  pub init_value: Vec<Bit>,
+  /// Registered output of a synchronous port.
+  pub state: Vec<Bit>,
+  pub last_clock: Bit,
+  pub last_addr: Option<usize>,
+  /// Number of reads performed.
+  pub reads: usize,
+}
+
+/// Write port of a memory.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct MemoryWritePort {
+  /// Clock signal index and active edge level, `None` for asynchronous writes.
+  pub clock: Option<(SignalIndex, Bit)>,
+  /// Per-bit write enable signal indices.
+  pub enable: SignalIndexList,
+  pub addr: SignalIndexList,
+  pub data: SignalIndexList,
+  pub last_clock: Bit,
+  /// Address and data of the last asynchronous write, repeated writes aren't counted.
+  pub last_write: Option<(usize, Vec<Bit>)>,
+  /// Number of writes performed.
+  pub writes: usize,
+}
+
+/// Access counts of each memory port.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+pub struct MemoryStatistics {
+  pub reads: Vec<usize>,
+  pub writes: Vec<usize>,
+}
+
+/// Memory array with any number of read/write ports (Yosys `$mem_v2`).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Memory {
+  /// Name of cell (Yosys cell type).
+  pub name: String,
+  /// Name of memory in the source design.
+  pub memid: String,
+  /// Number of words.
+  pub size: usize,
+  /// Address of first word.
+  pub offset: usize,
+  /// Bits per word.
+  pub width: usize,
+  /// Contents, word-major with the least-significant bit of each word first.
+  pub contents: Vec<Bit>,
+  pub read_ports: Vec<MemoryReadPort>,
+  pub write_ports: Vec<MemoryWritePort>,
+}
+
+impl Memory {
+  /// Check if a Yosys cell type can be built as a `Memory`.
+  pub fn is_supported(cell_type: &str) -> bool {
+    cell_type == "$mem_v2"
+  }
+
+  pub fn eval(&mut self, signals: &mut SignalList) {
+    self.sample(signals);
+    self.commit(signals);
+  }
+
+  /// Clock read registers and write ports without writing read data, see `commit`.
+  pub fn sample(&mut self, signals: &SignalList) {
+    // Detect clock edges before anything changes
+    let read_edges: Vec<bool> = self
+      .read_ports
+      .iter_mut()
+      .map(|port| clock_edge(port.clock, &mut port.last_clock, signals))
+      .collect();
+    let write_edges: Vec<bool> = self
+      .write_ports
+      .iter_mut()
+      .map(|port| clock_edge(port.clock, &mut port.last_clock, signals))
+      .collect();
+
+    // Synchronous reads see contents from before this edge's writes
+    for (i, edge) in read_edges.iter().enumerate() {
+      if !edge {
+        continue;
+      }
+      let port = &self.read_ports[i];
+      let enabled = signals[port.enable].get_value() == Bit::One;
+      let reset = signals[port.sync_reset].get_value() == Bit::One;
+
+      let next = if reset && (enabled || !port.ce_over_srst) {
+        Some(port.sync_reset_value.clone())
+      } else if enabled {
+        let addr = self.address(&port.addr, signals);
+        let mut word = self.read_word(addr);
+        // Forward data from transparent write ports
+        for (j, write_port) in self.write_ports.iter().enumerate() {
+          if !(write_edges[j] && port.transparent[j])
+            || self.address(&write_port.addr, signals) != addr
+          {
+            continue;
+          }
+          for (k, bit) in word.iter_mut().enumerate() {
+            if signals[write_port.enable[k]].get_value() == Bit::One {
+              *bit = signals[write_port.data[k]].get_value();
+            }
+          }
+        }
+        self.read_ports[i].reads += 1;
+        Some(word)
+      } else {
+        None
+      };
+
+      if let Some(next) = next {
+        self.read_ports[i].state = next;
+      }
+    }
+
+    // Writes, later ports have priority
+    for (i, edge) in write_edges.iter().enumerate() {
+      let port = &self.write_ports[i];
+      if port.clock.is_some() && !edge {
+        continue;
+      }
+      let mask: Vec<bool> = port
+        .enable
+        .iter()
+        .map(|idx| signals[*idx].get_value() == Bit::One)
+        .collect();
+      if !mask.contains(&true) {
+        self.write_ports[i].last_write = None;
+        continue;
+      }
+      let addr = self.address(&port.addr, signals);
+      let data: Vec<Bit> = port
+        .data
+        .iter()
+        .map(|idx| signals[*idx].get_value())
+        .collect();
+      let Some((addr, word)) = addr.and_then(|addr| Some((addr, self.word_range(addr)?))) else {
+        continue;
+      };
+      for (k, bit) in self.contents[word].iter_mut().enumerate() {
+        if mask[k] {
+          *bit = data[k];
+        }
+      }
+      // Asynchronous ports write on every evaluation while enabled, count only new writes
+      let port = &mut self.write_ports[i];
+      if port.clock.is_none() {
+        let write = Some((addr, data));
+        if port.last_write == write {
+          continue;
+        }
+        port.last_write = write;
+      }
+      port.writes += 1;
+    }
+  }
+
+  /// Write read port data, returns if any output changed.
+  pub fn commit(&mut self, signals: &mut SignalList) -> bool {
+    let mut changed = false;
+    for i in 0..self.read_ports.len() {
+      let port = &self.read_ports[i];
+      let word = if signals[port.async_reset].get_value() == Bit::One {
+        port.async_reset_value.clone()
+      } else if port.clock.is_some() {
+        port.state.clone()
+      } else {
+        // Asynchronous ports count a read whenever their address changes
+        let addr = self.address(&port.addr, signals);
+        if addr != port.last_addr {
+          self.read_ports[i].last_addr = addr;
+          self.read_ports[i].reads += 1;
+        }
+        self.read_word(addr)
+      };
+
+      for (idx, bit) in self.read_ports[i].data.iter().zip(word) {
+        changed |= signals[*idx].get_value() != bit;
+        signals[*idx].set_value(bit);
+      }
+    }
+    changed
+  }
+
+  /// Reset port registers and access counts. Contents are kept.
+  pub fn reset(&mut self) {
+    for port in &mut self.read_ports {
+      port.state = port.init_value.clone();
+      port.last_clock = Bit::Zero;
+      port.last_addr = None;
+      port.reads = 0;
+    }
+    for port in &mut self.write_ports {
+      port.last_clock = Bit::Zero;
+      port.last_write = None;
+      port.writes = 0;
+    }
+  }
+
+  pub fn get_statistics(&self) -> MemoryStatistics {
+    MemoryStatistics {
+      reads: self.read_ports.iter().map(|port| port.reads).collect(),
+      writes: self.write_ports.iter().map(|port| port.writes).collect(),
+    }
+  }
+
+  /// Overwrite access counts with ones from `get_statistics`.
+  pub fn set_statistics(&mut self, statistics: &MemoryStatistics) {
+    for (port, reads) in self.read_ports.iter_mut().zip(&statistics.reads) {
+      port.reads = *reads;
+    }
+    for (port, writes) in self.write_ports.iter_mut().zip(&statistics.writes) {
+      port.writes = *writes;
+    }
+  }
+
+  /// Get contents as bits, least-significant bit of word 0 first.
+  pub fn get_bits(&self) -> BitVec {
+    BitVec::from(self.contents.clone())
+  }
+
+  /// Set contents from bits, least-significant bit of word 0 first.
+  /// Words past the end of `vals` are left unchanged.
+  pub fn set_bits(&mut self, vals: &BitVec) -> Result<(), MemoryError> {
+    if vals.bits.len() > self.contents.len() {
+      return Err(MemoryError::Size {
+        size: self.size,
+        requested: vals.bits.len().div_ceil(self.width.max(1)),
+      });
+    }
+    self.contents[..vals.bits.len()].copy_from_slice(&vals.bits);
+    Ok(())
+  }
+
+  /// Load words into memory, starting at word 0.
+  pub fn load_words<T: PrimInt>(&mut self, words: &[T]) -> Result<(), MemoryError> {
+    match BitVec::from_ints_sized(words, self.width) {
+      Ok(bits) => self.set_bits(&bits),
+      Err(err) => Err(MemoryError::Parse(err.to_string())),
+    }
+  }
+
+  /// Dump all words of memory.
+  pub fn dump_words<T: PrimInt + std::ops::BitXorAssign>(&self) -> Vec<T> {
+    self.get_bits().to_ints_sized(self.width)
+  }
+
+  /// Load memory from `$readmemh`-style text: whitespace separated hex words,
+  /// `//` comments and `@<hex address>` directives.
+  pub fn load_hex(&mut self, text: &str) -> Result<(), MemoryError> {
+    let mut addr = 0;
+    for line in text.lines() {
+      let line = line.split("//").next().unwrap_or_default();
+      for token in line.split_whitespace() {
+        if let Some(target) = token.strip_prefix('@') {
+          addr =
+            usize::from_str_radix(target, 16).map_err(|_| MemoryError::Parse(token.to_string()))?;
+          continue;
+        }
+
+        let Some(range) = self.word_range(addr + self.offset) else {
+          return Err(MemoryError::Size {
+            size: self.size,
+            requested: addr + 1,
+          });
+        };
+        let word = parse_hex_word(token, self.width)?;
+        self.contents[range].copy_from_slice(&word);
+        addr += 1;
+      }
+    }
+    Ok(())
+  }
+
+  fn address(&self, addr: &[SignalIndex], signals: &SignalList) -> Option<usize> {
+    let mut val: usize = 0;
+    for (i, idx) in addr.iter().enumerate() {
+      if signals[*idx].get_value() == Bit::One {
+        if i >= usize::BITS as usize {
+          return None;
+        }
+        val |= 1 << i;
+      }
+    }
+    Some(val)
+  }
+
+  fn word_range(&self, addr: usize) -> Option<std::ops::Range<usize>> {
+    match addr.checked_sub(self.offset) {
+      Some(word) if word < self.size => Some(word * self.width..(word + 1) * self.width),
+      _ => None,
+    }
+  }
+
+  /// Out-of-range reads return zero.
+  fn read_word(&self, addr: Option<usize>) -> Vec<Bit> {
+    match addr.and_then(|addr| self.word_range(addr)) {
+      Some(range) => self.contents[range].to_vec(),
+      None => vec![Bit::Zero; self.width],
+    }
+  }
+}
+
+impl TryFrom<&SynthCell> for Memory {
+  type Error = MemoryError;
+
+  fn try_from(value: &SynthCell) -> Result<Self, Self::Error> {
+    if !Self::is_supported(&value.cell_type) {
+      return Err(MemoryError::UnsupportedType(value.cell_type.clone()));
+    }
+
+    let param = |name: &str| match value.get_param(name) {
+      Some(param) => Ok(param),
+      None => Err(MemoryError::MissingParameter(name.to_string())),
+    };
+    let number = |name: &str| -> Result<usize, MemoryError> {
+      param(name)?
+        .to_usize()
+        .ok_or(MemoryError::MissingParameter(name.to_string()))
+    };
+    let bits = |name: &str| param(name).map(|param| param.to_bits()).unwrap_or_default();
+    let pin = |name: &str| value.get_pin(name).unwrap_or_default();
+    // Slice `i` of a packed per-port field
+    let chunk = |bits: &[Bit], i: usize, size: usize| -> Vec<Bit> {
+      (i * size..(i + 1) * size)
+        .map(|j| bits.get(j).copied().unwrap_or(Bit::Zero))
+        .collect()
+    };
+    let chunk_idx = |pins: &[SignalIndex], i: usize, size: usize| -> SignalIndexList {
+      (i * size..(i + 1) * size)
+        .map(|j| pins.get(j).copied().unwrap_or(0))
+        .collect()
+    };
+
+    let (size, offset, abits, width) = (
+      number("SIZE")?,
+      number("OFFSET").unwrap_or(0),
+      number("ABITS")?,
+      number("WIDTH")?,
+    );
+    let (num_read, num_write) = (number("RD_PORTS")?, number("WR_PORTS")?);
+
+    let (rd_clk, rd_en, rd_arst, rd_srst, rd_addr, rd_data) = (
+      pin("RD_CLK"),
+      pin("RD_EN"),
+      pin("RD_ARST"),
+      pin("RD_SRST"),
+      pin("RD_ADDR"),
+      pin("RD_DATA"),
+    );
+    let (rd_clk_enable, rd_clk_polarity, rd_transparency, rd_ce_over_srst) = (
+      bits("RD_CLK_ENABLE"),
+      bits("RD_CLK_POLARITY"),
+      bits("RD_TRANSPARENCY_MASK"),
+      bits("RD_CE_OVER_SRST"),
+    );
+    let (rd_arst_value, rd_srst_value, rd_init_value) = (
+      bits("RD_ARST_VALUE"),
+      bits("RD_SRST_VALUE"),
+      bits("RD_INIT_VALUE"),
+    );
+
+    let read_ports: Vec<MemoryReadPort> = (0..num_read)
+      .map(|i| {
+        let clocked = chunk(&rd_clk_enable, i, 1)[0] == Bit::One;
+        let init_value = chunk(&rd_init_value, i, width);
+        MemoryReadPort {
+          clock: clocked.then(|| {
+            (
+              chunk_idx(&rd_clk, i, 1)[0],
+              chunk(&rd_clk_polarity, i, 1)[0],
+            )
+          }),
+          enable: chunk_idx(&rd_en, i, 1)[0],
+          async_reset: chunk_idx(&rd_arst, i, 1)[0],
+          sync_reset: chunk_idx(&rd_srst, i, 1)[0],
+          addr: chunk_idx(&rd_addr, i, abits),
+          data: chunk_idx(&rd_data, i, width),
+          transparent: chunk(&rd_transparency, i, num_write)
+            .iter()
+            .map(|bit| *bit == Bit::One)
+            .collect(),
+          ce_over_srst: chunk(&rd_ce_over_srst, i, 1)[0] == Bit::One,
+          async_reset_value: chunk(&rd_arst_value, i, width),
+          sync_reset_value: chunk(&rd_srst_value, i, width),
+          state: init_value.clone(),
+          init_value,
+          last_clock: Bit::Zero,
+          last_addr: None,
+          reads: 0,
+        }
+      })
+      .collect();
+
+    let (wr_clk, wr_en, wr_addr, wr_data) =
+      (pin("WR_CLK"), pin("WR_EN"), pin("WR_ADDR"), pin("WR_DATA"));
+    let (wr_clk_enable, wr_clk_polarity) = (bits("WR_CLK_ENABLE"), bits("WR_CLK_POLARITY"));
+
+    let write_ports: Vec<MemoryWritePort> = (0..num_write)
+      .map(|i| {
+        let clocked = chunk(&wr_clk_enable, i, 1)[0] == Bit::One;
+        MemoryWritePort {
+          clock: clocked.then(|| {
+            (
+              chunk_idx(&wr_clk, i, 1)[0],
+              chunk(&wr_clk_polarity, i, 1)[0],
+            )
+          }),
+          enable: chunk_idx(&wr_en, i, width),
+          addr: chunk_idx(&wr_addr, i, abits),
+          data: chunk_idx(&wr_data, i, width),
+          last_clock: Bit::Zero,
+          last_write: None,
+          writes: 0,
+        }
+      })
+      .collect();
+
+    let memid = match value.get_param("MEMID") {
+      Some(crate::synth::netlist::SynthParam::String(memid)) => memid.clone(),
+      _ => String::new(),
+    };
+
+    Ok(Self {
+      name: value.cell_type.clone(),
+      memid,
+      size,
+      offset,
+      width,
+      contents: chunk(&bits("INIT"), 0, size * width),
+      read_ports,
+      write_ports,
+    })
+  }
+}
+
+/// Returns true on an active edge and remembers the clock value.
+fn clock_edge(
+  clock: Option<(SignalIndex, Bit)>,
+  last_clock: &mut Bit,
+  signals: &SignalList,
+) -> bool {
+  match clock {
+    Some((idx, level)) => {
+      let value = signals[idx].get_value();
+      let edge = value == level && *last_clock != level;
+      *last_clock = value;
+      edge
+    }
+    None => false,
+  }
+}
+
+fn parse_hex_word(token: &str, width: usize) -> Result<Vec<Bit>, MemoryError> {
+  let mut bits = vec![];
+  for c in token.chars().rev().filter(|c| *c != '_') {
+    let Some(nibble) = c.to_digit(16) else {
+      return Err(MemoryError::Parse(token.to_string()));
+    };
+    bits.extend((0..4).map(|i| Bit::from((nibble >> i) & 1 == 1)));
+  }
+  bits.resize(width, Bit::Zero);
+  Ok(bits)
+}
@@ -0,0 +1,7 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+pub mod memory;
+pub mod tristate;
+pub mod word;
+pub mod xilinx;
@@ -0,0 +1,206 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use crate::bit::Bit;
+use crate::module::hardware_module::{Component, ComponentIndex};
+use crate::signal::{AccessSignal, SignalIndex, SignalIndexList, SignalList};
+use crate::synth::netlist::SynthCell;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TristateError {
+  #[error("unsupported cell type `{0}`")]
+  UnsupportedType(String),
+  #[error("missing pin `{0}`")]
+  MissingPin(String),
+}
+
+/// Tri-state buffer (`$tribuf`, `$_TBUF_`, `OBUFT`).
+/// Buffers don't write their outputs, the `TristateNet`s they drive resolve them.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct TristateBuffer {
+  /// Name of cell.
+  pub name: String,
+  /// Data input signal indices.
+  pub inputs: SignalIndexList,
+  /// Output enable signal index.
+  pub enable: SignalIndex,
+  /// Enable level that drives the outputs.
+  pub enable_level: Bit,
+  /// Output signal indices.
+  pub outputs: SignalIndexList,
+  /// Index of the `TristateNet` driven by each output.
+  pub nets: Vec<usize>,
+  /// Value driven on each output, `None` when high-impedance.
+  pub drive: Vec<Option<Bit>>,
+}
+
+impl TristateBuffer {
+  /// Check if a cell type can be built as a `TristateBuffer`.
+  pub fn is_supported(cell_type: &str) -> bool {
+    matches!(cell_type, "$tribuf" | "$_TBUF_" | "OBUFT")
+  }
+
+  pub fn eval(&mut self, signals: &SignalList) {
+    let enabled = signals[self.enable].get_value() == self.enable_level;
+    for (drive, idx) in self.drive.iter_mut().zip(&self.inputs) {
+      *drive = enabled.then(|| signals[*idx].get_value());
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.drive.fill(None);
+  }
+}
+
+impl TryFrom<&SynthCell> for TristateBuffer {
+  type Error = TristateError;
+
+  fn try_from(value: &SynthCell) -> Result<Self, Self::Error> {
+    // (data, enable, output) pins and enable level
+    let (pins, enable_level) = match value.cell_type.as_str() {
+      "$tribuf" => (["A", "EN", "Y"], Bit::One),
+      "$_TBUF_" => (["A", "E", "Y"], Bit::One),
+      // Xilinx `T` is an active-low output enable
+      "OBUFT" => (["I", "T", "O"], Bit::Zero),
+      _ => return Err(TristateError::UnsupportedType(value.cell_type.clone())),
+    };
+
+    let get_pin = |pin: &str| match value.get_pin(pin) {
+      Some(bits) => Ok(bits),
+      None => Err(TristateError::MissingPin(pin.to_string())),
+    };
+    let (inputs, enable, outputs) = (get_pin(pins[0])?, get_pin(pins[1])?, get_pin(pins[2])?);
+    let Some(enable) = enable.first().copied() else {
+      return Err(TristateError::MissingPin(pins[1].to_string()));
+    };
+
+    Ok(Self {
+      name: value.cell_type.clone(),
+      drive: vec![None; outputs.len()],
+      nets: vec![0; outputs.len()],
+      inputs,
+      enable,
+      enable_level,
+      outputs,
+    })
+  }
+}
+
+/// Source of a value on a `TristateNet`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub enum TristateDriver {
+  /// Output `bit` of a `TristateBuffer` component.
+  Buffer {
+    component: ComponentIndex,
+    bit: usize,
+  },
+  /// Inout port of a submodule, driven by its `TristateNet` at index `net`.
+  Module {
+    component: ComponentIndex,
+    net: usize,
+  },
+}
+
+/// Net with tri-state drivers, resolved to a value or high-impedance (Z).
+/// A high-impedance net keeps its last value in the signal list.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct TristateNet {
+  /// Resolved signal index.
+  pub signal: SignalIndex,
+  pub drivers: Vec<TristateDriver>,
+  /// Value driven from outside the module through an inout port, `None` when released.
+  pub external: Option<Bit>,
+  /// Value driven by drivers inside the module, `None` when high-impedance.
+  pub internal: Option<Bit>,
+  /// Resolved value, `None` when high-impedance.
+  pub value: Option<Bit>,
+  /// Number of resolutions where enabled drivers disagreed.
+  pub contention_count: usize,
+}
+
+/// Combine driven values, returns the resolved value and whether drivers disagreed.
+/// The first enabled driver wins a conflict.
+fn combine(values: impl Iterator<Item = Option<Bit>>) -> (Option<Bit>, bool) {
+  let mut resolved = None;
+  let mut contention = false;
+  for bit in values.flatten() {
+    match resolved {
+      None => resolved = Some(bit),
+      Some(resolved_bit) => contention |= resolved_bit != bit,
+    }
+  }
+  (resolved, contention)
+}
+
+impl TristateNet {
+  pub fn new(signal: SignalIndex) -> Self {
+    Self {
+      signal,
+      ..Default::default()
+    }
+  }
+
+  fn driver_value(driver: &TristateDriver, components: &[Component]) -> Option<Bit> {
+    match driver {
+      TristateDriver::Buffer { component, bit } => match &components[*component] {
+        Component::Tristate(buffer) => buffer.drive[*bit],
+        _ => None,
+      },
+      TristateDriver::Module { component, net } => match &components[*component] {
+        Component::Module(module) => module.tristate_nets[*net].internal,
+        _ => None,
+      },
+    }
+  }
+
+  /// Resolve net from its drivers and write the value to the signal list.
+  pub fn resolve(&mut self, components: &[Component], signals: &mut SignalList) {
+    let (internal, internal_contention) = combine(
+      self
+        .drivers
+        .iter()
+        .map(|driver| Self::driver_value(driver, components)),
+    );
+    let (value, external_contention) = combine([internal, self.external].into_iter());
+
+    if internal_contention || external_contention {
+      self.contention_count += 1;
+    }
+    self.internal = internal;
+    self.value = value;
+    if let Some(bit) = value {
+      signals[self.signal].set_value(bit);
+    }
+  }
+
+  /// Value seen by a submodule from every other driver, including the external one.
+  pub fn resolve_excluding(
+    &self,
+    components: &[Component],
+    excluded: ComponentIndex,
+  ) -> Option<Bit> {
+    let others = self.drivers.iter().filter(|driver| match driver {
+      TristateDriver::Buffer { component, .. } | TristateDriver::Module { component, .. } => {
+        *component != excluded
+      }
+    });
+    let values = others
+      .map(|driver| Self::driver_value(driver, components))
+      .chain([self.external]);
+    combine(values).0
+  }
+
+  pub fn reset(&mut self) {
+    self.external = None;
+    self.internal = None;
+    self.value = None;
+    self.contention_count = 0;
+  }
+}
+
+/// Find the `TristateNet` resolving a signal.
+pub fn find_tristate_net(nets: &[TristateNet], signal: SignalIndex) -> Option<usize> {
+  nets.iter().position(|net| net.signal == signal)
+}
@@ -0,0 +1,532 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use crate::bit::Bit;
+use crate::signal::{AccessSignal, SignalIndex, SignalIndexList, SignalList};
+use crate::synth::netlist::SynthCell;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use thiserror::Error;
+
+/// Maximum result width of arithmetic (`$add`, `$sub`, `$mul`, `$neg`) cells.
+pub const MAX_ARITH_WIDTH: usize = 128;
+
+/// Yosys coarse-grain (RTLIL) cell functions.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub enum WordFunction {
+  Add,
+  Sub,
+  Mul,
+  Neg,
+  Pos,
+  Shl,
+  Shr,
+  Sshl,
+  Sshr,
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  Not,
+  And,
+  Or,
+  Xor,
+  Xnor,
+  LogicNot,
+  LogicAnd,
+  LogicOr,
+  ReduceAnd,
+  ReduceOr,
+  ReduceXor,
+  ReduceXnor,
+  ReduceBool,
+  Mux,
+  Pmux,
+  Dff,
+  Dffe,
+  Adff,
+  Adffe,
+  Sdff,
+  Sdffe,
+  Sdffce,
+}
+
+impl WordFunction {
+  /// Get function of a Yosys cell type (ex, `$add`).
+  pub fn from_cell_type(cell_type: &str) -> Option<Self> {
+    let function = match cell_type {
+      "$add" => Self::Add,
+      "$sub" => Self::Sub,
+      "$mul" => Self::Mul,
+      "$neg" => Self::Neg,
+      "$pos" => Self::Pos,
+      "$shl" => Self::Shl,
+      "$shr" => Self::Shr,
+      "$sshl" => Self::Sshl,
+      "$sshr" => Self::Sshr,
+      "$eq" | "$eqx" => Self::Eq,
+      "$ne" | "$nex" => Self::Ne,
+      "$lt" => Self::Lt,
+      "$le" => Self::Le,
+      "$gt" => Self::Gt,
+      "$ge" => Self::Ge,
+      "$not" => Self::Not,
+      "$and" => Self::And,
+      "$or" => Self::Or,
+      "$xor" => Self::Xor,
+      "$xnor" => Self::Xnor,
+      "$logic_not" => Self::LogicNot,
+      "$logic_and" => Self::LogicAnd,
+      "$logic_or" => Self::LogicOr,
+      "$reduce_and" => Self::ReduceAnd,
+      "$reduce_or" => Self::ReduceOr,
+      "$reduce_xor" => Self::ReduceXor,
+      "$reduce_xnor" => Self::ReduceXnor,
+      "$reduce_bool" => Self::ReduceBool,
+      "$mux" => Self::Mux,
+      "$pmux" => Self::Pmux,
+      "$dff" => Self::Dff,
+      "$dffe" => Self::Dffe,
+      "$adff" => Self::Adff,
+      "$adffe" => Self::Adffe,
+      "$sdff" => Self::Sdff,
+      "$sdffe" => Self::Sdffe,
+      "$sdffce" => Self::Sdffce,
+      _ => return None,
+    };
+    Some(function)
+  }
+
+  /// Function holds state between evaluations.
+  pub fn is_sequential(&self) -> bool {
+    matches!(
+      self,
+      Self::Dff | Self::Dffe | Self::Adff | Self::Adffe | Self::Sdff | Self::Sdffe | Self::Sdffce
+    )
+  }
+}
+
+#[derive(Debug, Error)]
+pub enum WordCellError {
+  #[error("unsupported cell type `{0}`")]
+  UnsupportedType(String),
+  #[error("missing pin `{0}`")]
+  MissingPin(String),
+  #[error("pin `{pin}` is {actual} bits wide but parameter says {expected}")]
+  Width {
+    pin: String,
+    expected: usize,
+    actual: usize,
+  },
+  #[error("result is {0} bits wide, arithmetic cells support at most {MAX_ARITH_WIDTH}")]
+  TooWide(usize),
+}
+
+/// Word-level cell operating on multi-bit operands.
+/// Flip-flops use `a` as their data input and `y` as their output.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WordCell {
+  /// Name of cell (Yosys cell type).
+  pub name: String,
+  /// Cell's function.
+  pub function: WordFunction,
+  /// Interpret `a` as two's complement.
+  pub a_signed: bool,
+  /// Interpret `b` as two's complement.
+  pub b_signed: bool,
+  /// `A` (or `D`) signal indices.
+  pub a: SignalIndexList,
+  /// `B` signal indices.
+  pub b: SignalIndexList,
+  /// `S` signal indices.
+  pub s: SignalIndexList,
+  /// `Y` (or `Q`) signal indices.
+  pub y: SignalIndexList,
+  /// Clock signal index and active edge level.
+  pub clock: Option<(SignalIndex, Bit)>,
+  /// Enable signal index and active level.
+  pub enable: Option<(SignalIndex, Bit)>,
+  /// Reset (async or sync) signal index and active level.
+  pub reset: Option<(SignalIndex, Bit)>,
+  /// Value loaded on reset.
+  pub reset_value: Vec<Bit>,
+  /// Stored flip-flop value.
+  pub state: Vec<Bit>,
+  /// Last sampled clock value.
+  pub last_clock: Bit,
+}
+
+impl WordCell {
+  /// Check if a Yosys cell type can be built as a `WordCell`.
+  pub fn is_supported(cell_type: &str) -> bool {
+    WordFunction::from_cell_type(cell_type).is_some()
+  }
+
+  pub fn eval(&mut self, signals: &mut SignalList) {
+    let width = self.y.len();
+    let a = read_bits(&self.a, signals);
+    let b = read_bits(&self.b, signals);
+
+    let output: Vec<Bit> = match self.function {
+      WordFunction::Add => from_u128(
+        to_u128(&a, self.a_signed).wrapping_add(to_u128(&b, self.b_signed)),
+        width,
+      ),
+      WordFunction::Sub => from_u128(
+        to_u128(&a, self.a_signed).wrapping_sub(to_u128(&b, self.b_signed)),
+        width,
+      ),
+      WordFunction::Mul => from_u128(
+        to_u128(&a, self.a_signed).wrapping_mul(to_u128(&b, self.b_signed)),
+        width,
+      ),
+      WordFunction::Neg => from_u128(to_u128(&a, self.a_signed).wrapping_neg(), width),
+      WordFunction::Pos => extend(&a, width, self.a_signed),
+      WordFunction::Shl | WordFunction::Sshl => {
+        let a = extend(&a, width, self.a_signed);
+        let shift = shift_amount(&b);
+        (0..width)
+          .map(|i| if i >= shift { a[i - shift] } else { Bit::Zero })
+          .collect()
+      }
+      WordFunction::Shr | WordFunction::Sshr => {
+        let a = extend(&a, width.max(a.len()), self.a_signed);
+        let fill = match a.last() {
+          Some(sign) if self.function == WordFunction::Sshr && self.a_signed => *sign,
+          _ => Bit::Zero,
+        };
+        let shift = shift_amount(&b);
+        (0..width)
+          .map(|i| match i.checked_add(shift) {
+            Some(j) if j < a.len() => a[j],
+            _ => fill,
+          })
+          .collect()
+      }
+      WordFunction::Eq => self.compare_bit(&a, &b, |ord| ord == Ordering::Equal),
+      WordFunction::Ne => self.compare_bit(&a, &b, |ord| ord != Ordering::Equal),
+      WordFunction::Lt => self.compare_bit(&a, &b, |ord| ord == Ordering::Less),
+      WordFunction::Le => self.compare_bit(&a, &b, |ord| ord != Ordering::Greater),
+      WordFunction::Gt => self.compare_bit(&a, &b, |ord| ord == Ordering::Greater),
+      WordFunction::Ge => self.compare_bit(&a, &b, |ord| ord != Ordering::Less),
+      WordFunction::Not => extend(&a, width, self.a_signed)
+        .into_iter()
+        .map(|bit| !bit)
+        .collect(),
+      WordFunction::And => self.bitwise(&a, &b, |x, y| x & y),
+      WordFunction::Or => self.bitwise(&a, &b, |x, y| x | y),
+      WordFunction::Xor => self.bitwise(&a, &b, |x, y| x ^ y),
+      WordFunction::Xnor => self.bitwise(&a, &b, |x, y| !(x ^ y)),
+      WordFunction::LogicNot => extend(&[!reduce_or(&a)], width, false),
+      WordFunction::LogicAnd => extend(&[reduce_or(&a) & reduce_or(&b)], width, false),
+      WordFunction::LogicOr => extend(&[reduce_or(&a) | reduce_or(&b)], width, false),
+      WordFunction::ReduceAnd => extend(
+        &[Bit::from(a.iter().all(|bit| *bit == Bit::One))],
+        width,
+        false,
+      ),
+      WordFunction::ReduceOr | WordFunction::ReduceBool => extend(&[reduce_or(&a)], width, false),
+      WordFunction::ReduceXor => extend(&[reduce_xor(&a)], width, false),
+      WordFunction::ReduceXnor => extend(&[!reduce_xor(&a)], width, false),
+      WordFunction::Mux => {
+        let s = read_bits(&self.s, signals);
+        if s.first() == Some(&Bit::One) {
+          b
+        } else {
+          a
+        }
+      }
+      WordFunction::Pmux => {
+        // Lowest selected case wins, fall back to `a` when nothing is selected
+        let s = read_bits(&self.s, signals);
+        match s.iter().position(|bit| *bit == Bit::One) {
+          Some(case) => b[case * width..(case + 1) * width].to_vec(),
+          None => a,
+        }
+      }
+      WordFunction::Dff
+      | WordFunction::Dffe
+      | WordFunction::Adff
+      | WordFunction::Adffe
+      | WordFunction::Sdff
+      | WordFunction::Sdffe
+      | WordFunction::Sdffce => {
+        self.clock_state(a, signals);
+        self.state.clone()
+      }
+    };
+
+    for (idx, bit) in self.y.iter().zip(output) {
+      signals[*idx].set_value(bit);
+    }
+  }
+
+  /// Clock new data into a flip-flop without writing its output, see `commit`.
+  pub fn sample(&mut self, signals: &SignalList) {
+    let data = read_bits(&self.a, signals);
+    self.clock_state(data, signals);
+  }
+
+  /// Write the stored value of a flip-flop to its output, returns if it changed.
+  pub fn commit(&self, signals: &mut SignalList) -> bool {
+    let mut changed = false;
+    for (idx, bit) in self.y.iter().zip(&self.state) {
+      changed |= signals[*idx].get_value() != *bit;
+      signals[*idx].set_value(*bit);
+    }
+    changed
+  }
+
+  pub fn reset(&mut self) {
+    self.state = vec![Bit::Zero; self.y.len()];
+    self.last_clock = Bit::Zero;
+  }
+
+  /// Update stored value of a flip-flop.
+  fn clock_state(&mut self, data: Vec<Bit>, signals: &SignalList) {
+    let active = |control: Option<(SignalIndex, Bit)>| match control {
+      Some((idx, level)) => signals[idx].get_value() == level,
+      None => false,
+    };
+
+    let (enabled, reset) = (
+      self.enable.is_none() || active(self.enable),
+      active(self.reset),
+    );
+    let edge = match self.clock {
+      Some((idx, level)) => {
+        let clock = signals[idx].get_value();
+        let edge = clock == level && self.last_clock != level;
+        self.last_clock = clock;
+        edge
+      }
+      None => false,
+    };
+
+    match self.function {
+      WordFunction::Adff | WordFunction::Adffe if reset => self.state = self.reset_value.clone(),
+      WordFunction::Sdff | WordFunction::Sdffe if edge && reset => {
+        self.state = self.reset_value.clone()
+      }
+      WordFunction::Sdffce if edge && enabled && reset => self.state = self.reset_value.clone(),
+      _ if edge && enabled => self.state = data,
+      _ => (),
+    }
+  }
+
+  fn compare_bit(&self, a: &[Bit], b: &[Bit], predicate: fn(Ordering) -> bool) -> Vec<Bit> {
+    let ordering = compare(a, self.a_signed, b, self.b_signed);
+    extend(&[Bit::from(predicate(ordering))], self.y.len(), false)
+  }
+
+  fn bitwise(&self, a: &[Bit], b: &[Bit], op: fn(Bit, Bit) -> Bit) -> Vec<Bit> {
+    let width = self.y.len();
+    extend(a, width, self.a_signed)
+      .into_iter()
+      .zip(extend(b, width, self.b_signed))
+      .map(|(x, y)| op(x, y))
+      .collect()
+  }
+}
+
+impl TryFrom<&SynthCell> for WordCell {
+  type Error = WordCellError;
+
+  fn try_from(value: &SynthCell) -> Result<Self, Self::Error> {
+    let Some(function) = WordFunction::from_cell_type(&value.cell_type) else {
+      return Err(WordCellError::UnsupportedType(value.cell_type.clone()));
+    };
+
+    let flag = |name: &str| value.get_param(name).is_some_and(|param| param.to_bool());
+    let required_pin = |name: &str| match value.get_pin(name) {
+      Some(pin) => Ok(pin),
+      None => Err(WordCellError::MissingPin(name.to_string())),
+    };
+    let control =
+      |pin: &str, polarity: &str| -> Result<Option<(SignalIndex, Bit)>, WordCellError> {
+        let idx = required_pin(pin)?[0];
+        Ok(Some((idx, Bit::from(flag(polarity)))))
+      };
+
+    let (a, b, s, y) = if function.is_sequential() {
+      (required_pin("D")?, vec![], vec![], required_pin("Q")?)
+    } else {
+      (
+        value.get_pin("A").unwrap_or_default(),
+        value.get_pin("B").unwrap_or_default(),
+        value.get_pin("S").unwrap_or_default(),
+        required_pin("Y")?,
+      )
+    };
+
+    // Check connections agree with width parameters
+    for (pin, param, actual) in [
+      ("A", "A_WIDTH", a.len()),
+      ("B", "B_WIDTH", b.len()),
+      ("S", "S_WIDTH", s.len()),
+      ("Y", "Y_WIDTH", y.len()),
+    ] {
+      if let Some(expected) = value.get_param(param).and_then(|param| param.to_usize()) {
+        if expected != actual {
+          return Err(WordCellError::Width {
+            pin: pin.to_string(),
+            expected,
+            actual,
+          });
+        }
+      }
+    }
+
+    // Each bit of `S` selects a `Y`-wide slice of `B`
+    if function == WordFunction::Pmux {
+      for (pin, expected, actual) in [("A", y.len(), a.len()), ("B", s.len() * y.len(), b.len())] {
+        if expected != actual {
+          return Err(WordCellError::Width {
+            pin: pin.to_string(),
+            expected,
+            actual,
+          });
+        }
+      }
+    }
+
+    if matches!(
+      function,
+      WordFunction::Add | WordFunction::Sub | WordFunction::Mul | WordFunction::Neg
+    ) && y.len() > MAX_ARITH_WIDTH
+    {
+      return Err(WordCellError::TooWide(y.len()));
+    }
+
+    let (clock, enable, reset, reset_value) = match function {
+      WordFunction::Dff => (control("CLK", "CLK_POLARITY")?, None, None, vec![]),
+      WordFunction::Dffe => (
+        control("CLK", "CLK_POLARITY")?,
+        control("EN", "EN_POLARITY")?,
+        None,
+        vec![],
+      ),
+      WordFunction::Adff | WordFunction::Adffe => (
+        control("CLK", "CLK_POLARITY")?,
+        match function {
+          WordFunction::Adffe => control("EN", "EN_POLARITY")?,
+          _ => None,
+        },
+        control("ARST", "ARST_POLARITY")?,
+        value
+          .get_param("ARST_VALUE")
+          .map(|param| param.to_bits())
+          .unwrap_or_default(),
+      ),
+      WordFunction::Sdff | WordFunction::Sdffe | WordFunction::Sdffce => (
+        control("CLK", "CLK_POLARITY")?,
+        match function {
+          WordFunction::Sdff => None,
+          _ => control("EN", "EN_POLARITY")?,
+        },
+        control("SRST", "SRST_POLARITY")?,
+        value
+          .get_param("SRST_VALUE")
+          .map(|param| param.to_bits())
+          .unwrap_or_default(),
+      ),
+      _ => (None, None, None, vec![]),
+    };
+
+    Ok(Self {
+      name: value.cell_type.clone(),
+      function,
+      a_signed: flag("A_SIGNED"),
+      b_signed: flag("B_SIGNED"),
+      reset_value: extend(&reset_value, y.len(), false),
+      state: vec![Bit::Zero; y.len()],
+      last_clock: Bit::Zero,
+      a,
+      b,
+      s,
+      y,
+      clock,
+      enable,
+      reset,
+    })
+  }
+}
+
+fn read_bits(indices: &[SignalIndex], signals: &SignalList) -> Vec<Bit> {
+  indices
+    .iter()
+    .map(|idx| signals[*idx].get_value())
+    .collect()
+}
+
+/// Truncate or extend bits to `width`, replicating the sign bit if `signed`.
+fn extend(bits: &[Bit], width: usize, signed: bool) -> Vec<Bit> {
+  let fill = match bits.last() {
+    Some(sign) if signed => *sign,
+    _ => Bit::Zero,
+  };
+  (0..width)
+    .map(|i| bits.get(i).copied().unwrap_or(fill))
+    .collect()
+}
+
+/// Low 128 bits of operand after extension.
+fn to_u128(bits: &[Bit], signed: bool) -> u128 {
+  extend(bits, 128, signed)
+    .iter()
+    .enumerate()
+    .fold(0, |val, (i, bit)| val | (bit.to_int::<u128>() << i))
+}
+
+fn from_u128(val: u128, width: usize) -> Vec<Bit> {
+  (0..width)
+    .map(|i| Bit::from(i < 128 && (val >> i) & 1 == 1))
+    .collect()
+}
+
+/// Shift amounts are unsigned, saturate instead of overflowing.
+fn shift_amount(bits: &[Bit]) -> usize {
+  let mut amount: usize = 0;
+  for (i, bit) in bits.iter().enumerate() {
+    if *bit == Bit::One {
+      if i >= usize::BITS as usize {
+        return usize::MAX;
+      }
+      amount |= 1 << i;
+    }
+  }
+  amount
+}
+
+/// Compare operands as integers, each extended by its own signedness.
+fn compare(a: &[Bit], a_signed: bool, b: &[Bit], b_signed: bool) -> Ordering {
+  // One extra bit so mixed signedness compares as mathematical integers
+  let width = a.len().max(b.len()) + 1;
+  let (a, b) = (extend(a, width, a_signed), extend(b, width, b_signed));
+
+  // Sign bits compare inverted
+  match (a[width - 1], b[width - 1]) {
+    (Bit::One, Bit::Zero) => return Ordering::Less,
+    (Bit::Zero, Bit::One) => return Ordering::Greater,
+    _ => (),
+  }
+
+  for i in (0..width - 1).rev() {
+    match (a[i], b[i]) {
+      (Bit::One, Bit::Zero) => return Ordering::Greater,
+      (Bit::Zero, Bit::One) => return Ordering::Less,
+      _ => (),
+    }
+  }
+  Ordering::Equal
+}
+
+fn reduce_or(bits: &[Bit]) -> Bit {
+  Bit::from(bits.contains(&Bit::One))
+}
+
+fn reduce_xor(bits: &[Bit]) -> Bit {
+  bits.iter().fold(Bit::Zero, |acc, bit| acc ^ *bit)
+}
@@ -0,0 +1,359 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use crate::bit::Bit;
+use crate::signal::{AccessSignal, SignalIndex, SignalIndexList, SignalList};
+use crate::synth::netlist::{SynthCell, SynthParam};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Xilinx UNISIM primitive functions.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub enum XilinxFunction {
+  /// Look-up table with `n` inputs.
+  Lut(usize),
+  Carry4,
+  Carry8,
+  /// Flip-flop with clock enable and synchronous reset.
+  Fdre,
+  /// Flip-flop with clock enable and synchronous set.
+  Fdse,
+  /// Flip-flop with clock enable and asynchronous clear.
+  Fdce,
+  /// Flip-flop with clock enable and asynchronous preset.
+  Fdpe,
+  /// Wide function multiplexer (MUXF7, MUXF8, MUXF9).
+  Muxf,
+  Buf,
+  Inv,
+  Vcc,
+  Gnd,
+}
+
+impl XilinxFunction {
+  /// Get function of a UNISIM cell type (ex, `LUT6`).
+  pub fn from_cell_type(cell_type: &str) -> Option<Self> {
+    let function = match cell_type {
+      "LUT1" => Self::Lut(1),
+      "LUT2" => Self::Lut(2),
+      "LUT3" => Self::Lut(3),
+      "LUT4" => Self::Lut(4),
+      "LUT5" => Self::Lut(5),
+      "LUT6" => Self::Lut(6),
+      "CARRY4" => Self::Carry4,
+      "CARRY8" => Self::Carry8,
+      "FDRE" => Self::Fdre,
+      "FDSE" => Self::Fdse,
+      "FDCE" => Self::Fdce,
+      "FDPE" => Self::Fdpe,
+      "MUXF7" | "MUXF8" | "MUXF9" => Self::Muxf,
+      "BUF" | "BUFG" | "IBUF" | "OBUF" => Self::Buf,
+      "INV" => Self::Inv,
+      "VCC" => Self::Vcc,
+      "GND" => Self::Gnd,
+      _ => return None,
+    };
+    Some(function)
+  }
+
+  /// Input pin names (and default connection when unconnected) in evaluation order.
+  pub fn input_pins(&self) -> Vec<(String, Bit)> {
+    let pins: Vec<(String, Bit)> = match self {
+      Self::Lut(n) => (0..*n).map(|i| (format!("I{i}"), Bit::Zero)).collect(),
+      Self::Carry4 | Self::Carry8 => {
+        let (width, upper_carry) = match self {
+          Self::Carry4 => (4, "CYINIT"),
+          _ => (8, "CI_TOP"),
+        };
+        let mut pins = vec![
+          ("CI".to_string(), Bit::Zero),
+          (upper_carry.to_string(), Bit::Zero),
+        ];
+        pins.extend((0..width).map(|i| (format!("DI[{i}]"), Bit::Zero)));
+        pins.extend((0..width).map(|i| (format!("S[{i}]"), Bit::Zero)));
+        pins
+      }
+      Self::Fdre | Self::Fdse | Self::Fdce | Self::Fdpe => {
+        let control = match self {
+          Self::Fdre => "R",
+          Self::Fdse => "S",
+          Self::Fdce => "CLR",
+          _ => "PRE",
+        };
+        vec![
+          ("C".to_string(), Bit::Zero),
+          ("CE".to_string(), Bit::One),
+          ("D".to_string(), Bit::Zero),
+          (control.to_string(), Bit::Zero),
+        ]
+      }
+      Self::Muxf => vec![
+        ("I0".to_string(), Bit::Zero),
+        ("I1".to_string(), Bit::Zero),
+        ("S".to_string(), Bit::Zero),
+      ],
+      Self::Buf | Self::Inv => vec![("I".to_string(), Bit::Zero)],
+      Self::Vcc | Self::Gnd => vec![],
+    };
+    pins
+  }
+
+  /// Output pin names in evaluation order.
+  pub fn output_pins(&self) -> Vec<String> {
+    match self {
+      Self::Carry4 | Self::Carry8 => {
+        let width = if *self == Self::Carry4 { 4 } else { 8 };
+        (0..width)
+          .map(|i| format!("O[{i}]"))
+          .chain((0..width).map(|i| format!("CO[{i}]")))
+          .collect()
+      }
+      Self::Fdre | Self::Fdse | Self::Fdce | Self::Fdpe => vec!["Q".to_string()],
+      Self::Vcc => vec!["P".to_string()],
+      Self::Gnd => vec!["G".to_string()],
+      _ => vec!["O".to_string()],
+    }
+  }
+
+  pub fn is_sequential(&self) -> bool {
+    matches!(self, Self::Fdre | Self::Fdse | Self::Fdce | Self::Fdpe)
+  }
+}
+
+#[derive(Debug, Error)]
+pub enum XilinxError {
+  #[error("unsupported cell type `{0}`")]
+  UnsupportedType(String),
+  #[error("missing output pin `{0}`")]
+  MissingPin(String),
+}
+
+/// FPGA resource usage.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct FpgaResources {
+  pub luts: usize,
+  pub ffs: usize,
+  pub carries: usize,
+  pub muxes: usize,
+}
+
+impl FpgaResources {
+  /// Count resources in a cell breakdown. Cells that aren't Xilinx primitives are ignored.
+  pub fn from_breakdown(breakdown: &HashMap<String, usize>) -> Self {
+    let mut resources = Self::default();
+    for (cell_name, count) in breakdown {
+      match XilinxFunction::from_cell_type(cell_name) {
+        Some(XilinxFunction::Lut(_)) => resources.luts += count,
+        Some(XilinxFunction::Carry4 | XilinxFunction::Carry8) => resources.carries += count,
+        Some(XilinxFunction::Muxf) => resources.muxes += count,
+        Some(function) if function.is_sequential() => resources.ffs += count,
+        _ => (),
+      }
+    }
+    resources
+  }
+}
+
+/// Xilinx UNISIM primitive.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct XilinxCell {
+  /// Name of cell (UNISIM cell type).
+  pub name: String,
+  /// Cell's function.
+  pub function: XilinxFunction,
+  /// `DUAL_CY4` carry: upper half of a `CARRY8` starts from `CI_TOP`.
+  pub dual_carry: bool,
+  /// Input signal indices, see `XilinxFunction` for pin order.
+  pub inputs: SignalIndexList,
+  /// Input pins inverted inside the primitive (`IS_<pin>_INVERTED`).
+  pub inverted: Vec<bool>,
+  /// Output signal indices, `None` when left unconnected.
+  pub outputs: Vec<Option<SignalIndex>>,
+  /// LUT truth table or flip-flop initial value.
+  pub init: Vec<Bit>,
+  /// Stored flip-flop value.
+  pub state: Bit,
+  /// Last sampled clock value, before inversion.
+  pub last_clock: Bit,
+}
+
+impl XilinxCell {
+  /// Check if a cell type can be built as a `XilinxCell`.
+  pub fn is_supported(cell_type: &str) -> bool {
+    XilinxFunction::from_cell_type(cell_type).is_some()
+  }
+
+  pub fn eval(&mut self, signals: &mut SignalList) {
+    let inputs = self.read_inputs(signals);
+
+    let outputs: Vec<Bit> = match self.function {
+      XilinxFunction::Lut(_) => {
+        let select = inputs
+          .iter()
+          .enumerate()
+          .fold(0, |acc, (i, bit)| acc | (bit.to_int::<usize>() << i));
+        vec![self.init.get(select).copied().unwrap_or(Bit::Zero)]
+      }
+      XilinxFunction::Carry4 | XilinxFunction::Carry8 => {
+        let width = if self.function == XilinxFunction::Carry4 {
+          4
+        } else {
+          8
+        };
+        let (di, s) = (&inputs[2..2 + width], &inputs[2 + width..2 + 2 * width]);
+        let mut carry = match self.function {
+          // CYINIT starts a chain, CI continues one
+          XilinxFunction::Carry4 => inputs[0] | inputs[1],
+          _ => inputs[0],
+        };
+        let (mut sums, mut carries) = (vec![], vec![]);
+        for i in 0..width {
+          if self.dual_carry && i == 4 {
+            carry = inputs[1];
+          }
+          sums.push(s[i] ^ carry);
+          carry = if s[i] == Bit::One { carry } else { di[i] };
+          carries.push(carry);
+        }
+        sums.into_iter().chain(carries).collect()
+      }
+      XilinxFunction::Fdre | XilinxFunction::Fdse | XilinxFunction::Fdce | XilinxFunction::Fdpe => {
+        self.clock_state(&inputs, signals);
+        vec![self.state]
+      }
+      XilinxFunction::Muxf => vec![if inputs[2] == Bit::One {
+        inputs[1]
+      } else {
+        inputs[0]
+      }],
+      XilinxFunction::Buf => vec![inputs[0]],
+      XilinxFunction::Inv => vec![!inputs[0]],
+      XilinxFunction::Vcc => vec![Bit::One],
+      XilinxFunction::Gnd => vec![Bit::Zero],
+    };
+
+    for (idx, bit) in self.outputs.iter().zip(outputs) {
+      if let Some(idx) = idx {
+        signals[*idx].set_value(bit);
+      }
+    }
+  }
+
+  /// Clock new data into a flip-flop without writing its output, see `commit`.
+  pub fn sample(&mut self, signals: &SignalList) {
+    let inputs = self.read_inputs(signals);
+    self.clock_state(&inputs, signals);
+  }
+
+  /// Write the stored value of a flip-flop to its output, returns if it changed.
+  pub fn commit(&self, signals: &mut SignalList) -> bool {
+    let Some(Some(idx)) = self.outputs.first() else {
+      return false;
+    };
+    let changed = signals[*idx].get_value() != self.state;
+    signals[*idx].set_value(self.state);
+    changed
+  }
+
+  /// Input pin values after inversion.
+  fn read_inputs(&self, signals: &SignalList) -> Vec<Bit> {
+    self
+      .inputs
+      .iter()
+      .zip(&self.inverted)
+      .map(|(idx, inverted)| {
+        let bit = signals[*idx].get_value();
+        if *inverted {
+          !bit
+        } else {
+          bit
+        }
+      })
+      .collect()
+  }
+
+  /// Update stored value of a flip-flop.
+  fn clock_state(&mut self, inputs: &[Bit], signals: &SignalList) {
+    let (clock, enable, data, control) = (inputs[0], inputs[1], inputs[2], inputs[3]);
+    // Compare raw pin value so an inverted clock doesn't start on an edge
+    let raw_clock = signals[self.inputs[0]].get_value();
+    let edge = clock == Bit::One && raw_clock != self.last_clock;
+    self.last_clock = raw_clock;
+
+    self.state = match self.function {
+      XilinxFunction::Fdce if control == Bit::One => Bit::Zero,
+      XilinxFunction::Fdpe if control == Bit::One => Bit::One,
+      XilinxFunction::Fdre if edge && control == Bit::One => Bit::Zero,
+      XilinxFunction::Fdse if edge && control == Bit::One => Bit::One,
+      _ if edge && enable == Bit::One => data,
+      _ => self.state,
+    };
+  }
+
+  pub fn reset(&mut self) {
+    if self.function.is_sequential() {
+      self.state = self.init.first().copied().unwrap_or(Bit::Zero);
+      self.last_clock = Bit::Zero;
+    }
+  }
+}
+
+impl TryFrom<&SynthCell> for XilinxCell {
+  type Error = XilinxError;
+
+  fn try_from(value: &SynthCell) -> Result<Self, Self::Error> {
+    let Some(function) = XilinxFunction::from_cell_type(&value.cell_type) else {
+      return Err(XilinxError::UnsupportedType(value.cell_type.clone()));
+    };
+
+    // Multi-bit pins (DI, S, O, CO) are split into one entry per bit
+    let pin_bit = |pin: &str| -> Option<SignalIndex> {
+      if let Some(idx) = value.get_pin(pin).and_then(|bits| bits.first().copied()) {
+        return Some(idx);
+      }
+      let (bus, bit) = pin.strip_suffix(']')?.split_once('[')?;
+      value.get_pin(bus)?.get(bit.parse::<usize>().ok()?).copied()
+    };
+
+    let (mut inputs, mut inverted) = (vec![], vec![]);
+    for (pin, default) in function.input_pins() {
+      inputs.push(pin_bit(&pin).unwrap_or(default.to_int()));
+      let flag = format!("IS_{pin}_INVERTED");
+      inverted.push(value.get_param(&flag).is_some_and(|param| param.to_bool()));
+    }
+
+    let mut outputs = vec![];
+    for pin in function.output_pins() {
+      match pin_bit(&pin) {
+        Some(idx) => outputs.push(Some(idx)),
+        // Carry outputs are frequently left unconnected
+        None if matches!(function, XilinxFunction::Carry4 | XilinxFunction::Carry8) => {
+          outputs.push(None)
+        }
+        None => return Err(XilinxError::MissingPin(pin)),
+      }
+    }
+
+    let init = value
+      .get_param("INIT")
+      .map(|param| param.to_bits())
+      .unwrap_or_default();
+    let dual_carry = matches!(
+      value.get_param("CARRY_TYPE"),
+      Some(SynthParam::String(carry_type)) if carry_type == "DUAL_CY4"
+    );
+
+    Ok(Self {
+      name: value.cell_type.clone(),
+      function,
+      dual_carry,
+      inputs,
+      inverted,
+      outputs,
+      state: init.first().copied().unwrap_or(Bit::Zero),
+      init,
+      last_clock: Bit::Zero,
+    })
+  }
+}
@@ -2,9 +2,19 @@
 // SPDX-License-Identifier: MIT
 
 use crate::bit::Bit;
-use crate::cell::{Cell, CellLibrary};
-use crate::module::hardware_module::{Component, ComponentIndexMap, HardwareModule, PortMap};
+use crate::cell::{BehaviorCell, Cell, CellLibrary};
+use crate::module::event::EventQueue;
+use crate::module::hardware_module::{
+  CellPinMap, Component, ComponentIndexMap, HardwareModule, ModuleError, PortMap,
+};
+use crate::module::model::{ModelComponent, ModuleModel};
 use crate::module::port::{Port, PortDirection};
+use crate::primitive::memory::{Memory, MemoryError};
+use crate::primitive::tristate::{
+  find_tristate_net, TristateBuffer, TristateDriver, TristateError, TristateNet,
+};
+use crate::primitive::word::{WordCell, WordCellError};
+use crate::primitive::xilinx::{XilinxCell, XilinxError};
 use crate::signal::{AccessSignal, Signal, SignalIndexMap, SignalList};
 use std::collections::BTreeMap;
 use std::io;
@@ -18,6 +28,18 @@ pub enum SynthError {
   Netlist(String),
   #[error("{0}")]
   IoError(#[from] io::Error),
+  #[error("error building cell `{0}`: {1}")]
+  WordCell(String, WordCellError),
+  #[error("error building memory `{0}`: {1}")]
+  Memory(String, MemoryError),
+  #[error("error building primitive `{0}`: {1}")]
+  Xilinx(String, XilinxError),
+  #[error("error building tri-state buffer `{0}`: {1}")]
+  Tristate(String, TristateError),
+  #[error("cell `{0}` is missing pin `{1}`")]
+  MissingPin(String, String),
+  #[error("{0}")]
+  Module(#[from] ModuleError),
 }
 
 #[derive(Debug)]
@@ -33,15 +55,82 @@ pub struct SynthPort {
   pub signed: bool,
 }
 
+impl SynthBit {
+  /// Signal index of bit. Constants map to the reserved indices 0 and 1.
+  pub fn to_index(&self) -> usize {
+    match self {
+      SynthBit::Constant(bit) => match bit {
+        Bit::Zero => 0,
+        Bit::One => 1,
+      },
+      SynthBit::NetIndex(idx) => *idx,
+    }
+  }
+}
+
+/// Value of a cell parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SynthParam {
+  /// Constant bits, least-significant bit first
+  Bits(Vec<Bit>),
+  String(String),
+}
+
+impl SynthParam {
+  /// Interpret parameter as an unsigned integer.
+  pub fn to_usize(&self) -> Option<usize> {
+    match self {
+      SynthParam::Bits(bits) => Some(
+        bits
+          .iter()
+          .take(usize::BITS as usize)
+          .enumerate()
+          .fold(0, |val, (i, bit)| val | (bit.to_int::<usize>() << i)),
+      ),
+      SynthParam::String(string) => string.parse().ok(),
+    }
+  }
+
+  /// Interpret parameter as a flag (any bit set).
+  pub fn to_bool(&self) -> bool {
+    self.to_usize().is_some_and(|val| val != 0)
+  }
+
+  /// Interpret parameter as constant bits, least-significant bit first.
+  pub fn to_bits(&self) -> Vec<Bit> {
+    match self {
+      SynthParam::Bits(bits) => bits.clone(),
+      SynthParam::String(_) => vec![],
+    }
+  }
+}
+
 #[derive(Debug)]
 pub struct SynthCell {
   pub cell_type: String,
+  pub parameters: BTreeMap<String, SynthParam>,
+  pub port_directions: BTreeMap<String, PortDirection>,
   pub connections: BTreeMap<String, Vec<SynthBit>>,
 }
 
+impl SynthCell {
+  /// Get a parameter by name.
+  pub fn get_param(&self, name: &str) -> Option<&SynthParam> {
+    self.parameters.get(name)
+  }
+
+  /// Get signal indices connected to a pin.
+  pub fn get_pin(&self, name: &str) -> Option<Vec<usize>> {
+    self
+      .connections
+      .get(name)
+      .map(|bits| bits.iter().map(|bit| bit.to_index()).collect())
+  }
+}
+
 #[derive(Debug)]
 pub struct SynthModule {
-  // Preserve topological order of cells
+  // Keyed by instance name, evaluation order comes from `HardwareModule::levelize`
   pub ports: BTreeMap<String, SynthPort>,
   pub cells: BTreeMap<String, SynthCell>,
   pub nets: BTreeMap<String, Vec<SynthBit>>, // context of local module
@@ -84,17 +173,7 @@ impl From<SynthBit> for Signal {
 
 impl From<&SynthPort> for Port {
   fn from(value: &SynthPort) -> Self {
-    let signal_idx_list: Vec<usize> = value
-      .bits
-      .iter()
-      .map(|x| match x {
-        SynthBit::Constant(bit) => match bit {
-          Bit::Zero => 0,
-          Bit::One => 1,
-        },
-        SynthBit::NetIndex(idx) => *idx,
-      })
-      .collect();
+    let signal_idx_list: Vec<usize> = value.bits.iter().map(|x| x.to_index()).collect();
 
     let shape = [1, signal_idx_list.len()];
 
@@ -119,10 +198,32 @@ pub struct Netlist {
 }
 
 impl Netlist {
+  /// Generate a levelized module and its submodules.
   pub fn generate_module(
     &self,
     name: &str,
     cell_library: &CellLibrary,
+  ) -> Result<HardwareModule, SynthError> {
+    let mut module = self.build_module(name, cell_library)?;
+    module.levelize();
+    Ok(module)
+  }
+
+  /// Generate a module with its submodules flattened into it, see `HardwareModule::flatten`.
+  pub fn generate_flat_module(
+    &self,
+    name: &str,
+    cell_library: &CellLibrary,
+  ) -> Result<HardwareModule, SynthError> {
+    let mut module = self.build_module(name, cell_library)?;
+    module.flatten()?;
+    Ok(module)
+  }
+
+  fn build_module(
+    &self,
+    name: &str,
+    cell_library: &CellLibrary,
   ) -> Result<HardwareModule, SynthError> {
     let top_module: &SynthModule = match self.modules.get(name) {
       Some(x) => x,
@@ -164,6 +265,7 @@ impl Netlist {
 
     let mut components: Vec<Component> = vec![];
     let mut component_map = ComponentIndexMap::new();
+    let mut cell_pins = CellPinMap::new();
 
     for (instance_name, synth_cell) in &top_module.cells {
       let new_component = match cell_library.cells.get(&synth_cell.cell_type) {
@@ -171,31 +273,71 @@ impl Netlist {
           let mut cell = Cell::from(cell_info);
           // flatten this for now, should only be 1 bit
           for (i, bits) in synth_cell.connections.values().enumerate() {
-            let idx = match &bits[0] {
-              SynthBit::Constant(bit) => match bit {
-                Bit::Zero => 0,
-                Bit::One => 1,
-              },
-              SynthBit::NetIndex(idx) => *idx,
-            };
-            cell.input_connections[i] = idx;
+            cell.input_connections[i] = bits[0].to_index();
           }
           // this sets last input as output but, fix later
           cell.output_connection = cell.input_connections[cell.num_inputs];
+          cell_pins
+            .entry(synth_cell.cell_type.clone())
+            .or_insert_with(|| synth_cell.connections.keys().cloned().collect());
           Component::Cell(cell)
         }
+        // User-defined cells
+        None if cell_library.behaviors.contains_key(&synth_cell.cell_type) => {
+          let info = &cell_library.behaviors[&synth_cell.cell_type];
+          let mut widths = vec![];
+          let mut pin_bits = |pins: &Vec<String>| -> Result<Vec<usize>, SynthError> {
+            let mut bits = vec![];
+            for pin in pins {
+              match synth_cell.get_pin(pin) {
+                Some(pin_bits) => {
+                  widths.push(pin_bits.len());
+                  bits.extend(pin_bits)
+                }
+                None => return Err(SynthError::MissingPin(instance_name.clone(), pin.clone())),
+              }
+            }
+            Ok(bits)
+          };
+          let inputs = pin_bits(&info.inputs)?;
+          let outputs = pin_bits(&info.outputs)?;
+          let mut behavior_cell = BehaviorCell::new(info, inputs, outputs);
+          behavior_cell.widths = widths;
+          Component::Behavior(behavior_cell)
+        }
+        // Yosys coarse-grain cells
+        None if WordCell::is_supported(&synth_cell.cell_type) => {
+          match WordCell::try_from(synth_cell) {
+            Ok(word_cell) => Component::Word(word_cell),
+            Err(err) => return Err(SynthError::WordCell(instance_name.clone(), err)),
+          }
+        }
+        None if Memory::is_supported(&synth_cell.cell_type) => match Memory::try_from(synth_cell) {
+          Ok(memory) => Component::Memory(memory),
+          Err(err) => return Err(SynthError::Memory(instance_name.clone(), err)),
+        },
+        None if TristateBuffer::is_supported(&synth_cell.cell_type) => {
+          match TristateBuffer::try_from(synth_cell) {
+            Ok(buffer) => Component::Tristate(buffer),
+            Err(err) => return Err(SynthError::Tristate(instance_name.clone(), err)),
+          }
+        }
+        // Xilinx UNISIM primitives
+        None if XilinxCell::is_supported(&synth_cell.cell_type) => {
+          match XilinxCell::try_from(synth_cell) {
+            Ok(xilinx_cell) => Component::Xilinx(xilinx_cell),
+            Err(err) => return Err(SynthError::Xilinx(instance_name.clone(), err)),
+          }
+        }
         None => {
-          let mut submodule = self.generate_module(&synth_cell.cell_type, cell_library)?;
+          let mut submodule = match cell_library.models.get(&synth_cell.cell_type) {
+            Some(model) => self.generate_model(instance_name, synth_cell, model.as_ref())?,
+            None => self.build_module(&synth_cell.cell_type, cell_library)?,
+          };
           for (port_name, bits) in &synth_cell.connections {
             let port = submodule.ports.get(port_name).unwrap();
             for (i, bit) in bits.iter().enumerate() {
-              let idx = match bit {
-                SynthBit::Constant(bit) => match bit {
-                  Bit::Zero => 0,
-                  Bit::One => 1,
-                },
-                SynthBit::NetIndex(idx) => *idx,
-              };
+              let idx = bit.to_index();
 
               match port.direction {
                 PortDirection::Input => submodule
@@ -204,16 +346,21 @@ impl Netlist {
                 PortDirection::Output => submodule
                   .output_connections
                   .push((idx, port.signal_idx_list[i])),
+                PortDirection::InOut => submodule
+                  .inout_connections
+                  .push((idx, port.signal_idx_list[i])),
               }
             }
           }
-          Component::Module(submodule)
+          Component::Module(Box::new(submodule))
         }
       };
       component_map.insert(instance_name.clone(), components.len());
       components.push(new_component);
     }
 
+    let tristate_nets = Self::generate_tristate_nets(&ports, &mut components);
+
     Ok(HardwareModule {
       name: name.to_string(),
       ports,
@@ -223,6 +370,162 @@ impl Netlist {
       component_map,
       input_connections: vec![],
       output_connections: vec![],
+      inout_connections: vec![],
+      schedule: Default::default(),
+      combinational_loops: vec![],
+      tristate_nets,
+      events: EventQueue::default(),
+      sampled: vec![],
+      instances: Default::default(),
+      cell_pins,
+      unsettled: None,
+    })
+  }
+
+  /// Collect nets driven by tri-state buffers, submodule inout ports, or connected to inout ports.
+  pub(crate) fn generate_tristate_nets(
+    ports: &PortMap,
+    components: &mut [Component],
+  ) -> Vec<TristateNet> {
+    let mut nets: Vec<TristateNet> = vec![];
+    let net_idx = |nets: &mut Vec<TristateNet>, signal| match find_tristate_net(nets, signal) {
+      Some(net) => net,
+      None => {
+        nets.push(TristateNet::new(signal));
+        nets.len() - 1
+      }
+    };
+
+    for (component_idx, component) in components.iter_mut().enumerate() {
+      match component {
+        Component::Tristate(buffer) => {
+          for (bit, signal) in buffer.outputs.iter().enumerate() {
+            let net = net_idx(&mut nets, *signal);
+            nets[net].drivers.push(TristateDriver::Buffer {
+              component: component_idx,
+              bit,
+            });
+            buffer.nets[bit] = net;
+          }
+        }
+        Component::Module(module) => {
+          for (external_idx, internal_idx) in &module.inout_connections {
+            let Some(child_net) = find_tristate_net(&module.tristate_nets, *internal_idx) else {
+              continue;
+            };
+            let net = net_idx(&mut nets, *external_idx);
+            nets[net].drivers.push(TristateDriver::Module {
+              component: component_idx,
+              net: child_net,
+            });
+          }
+        }
+        _ => (),
+      }
+    }
+
+    for port in ports.values() {
+      if port.direction == PortDirection::InOut {
+        for signal in &port.signal_idx_list {
+          net_idx(&mut nets, *signal);
+        }
+      }
+    }
+    nets
+  }
+
+  /// Generate a module wrapping a behavioral model.
+  /// Ports come from the module definition when the netlist has one, otherwise from the instance.
+  fn generate_model(
+    &self,
+    instance_name: &str,
+    synth_cell: &SynthCell,
+    model: &(dyn ModuleModel + 'static),
+  ) -> Result<HardwareModule, SynthError> {
+    let definition = self.modules.get(&synth_cell.cell_type);
+
+    // Bits 0 and 1 are kept as constant 0 and 1
+    let mut signals: SignalList = vec![
+      Signal::new_constant(Bit::Zero),
+      Signal::new_constant(Bit::One),
+    ];
+    let mut signal_map = SignalIndexMap::new();
+    let mut ports = PortMap::new();
+    let (mut inputs, mut outputs) = (BTreeMap::new(), BTreeMap::new());
+
+    for (port_name, bits) in &synth_cell.connections {
+      let direction = match (
+        definition.and_then(|module| module.ports.get(port_name)),
+        synth_cell.port_directions.get(port_name),
+      ) {
+        (Some(port), _) => port.direction.clone(),
+        (None, Some(direction)) => direction.clone(),
+        (None, None) => {
+          return Err(SynthError::MissingPin(
+            instance_name.to_string(),
+            port_name.clone(),
+          ))
+        }
+      };
+
+      let mut signal_idx_list = vec![];
+      for i in 0..bits.len() {
+        let idx = signals.len();
+        let signal_name = if bits.len() > 1 {
+          format!("{port_name}[{i}]")
+        } else {
+          port_name.clone()
+        };
+        let mut signal = Signal::new_net(idx);
+        signal.set_name(signal_name.clone());
+        signals.push(signal);
+        signal_map.insert(signal_name, idx);
+        signal_idx_list.push(idx);
+      }
+
+      match direction {
+        // Models only sample inout ports
+        PortDirection::Input | PortDirection::InOut => {
+          inputs.insert(port_name.clone(), signal_idx_list.clone())
+        }
+        PortDirection::Output => outputs.insert(port_name.clone(), signal_idx_list.clone()),
+      };
+      ports.insert(
+        port_name.clone(),
+        Port {
+          shape: [1, signal_idx_list.len()],
+          signal_idx_list,
+          direction,
+          signed: false,
+        },
+      );
+    }
+
+    let component = Component::Model(ModelComponent {
+      name: synth_cell.cell_type.clone(),
+      inputs,
+      outputs,
+      model: dyn_clone::clone_box(model),
+    });
+
+    Ok(HardwareModule {
+      name: synth_cell.cell_type.clone(),
+      ports,
+      signals,
+      signal_map,
+      components: vec![component],
+      component_map: Default::default(),
+      input_connections: vec![],
+      output_connections: vec![],
+      inout_connections: vec![],
+      tristate_nets: vec![],
+      schedule: Default::default(),
+      combinational_loops: vec![],
+      events: EventQueue::default(),
+      sampled: vec![],
+      instances: Default::default(),
+      cell_pins: Default::default(),
+      unsettled: None,
     })
   }
 }
@@ -1,7 +1,9 @@
 // Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
 // SPDX-License-Identifier: MIT
 
-use super::netlist::{Netlist, SynthBit, SynthCell, SynthError, SynthModule, SynthPort};
+use super::netlist::{
+  Netlist, SynthBit, SynthCell, SynthError, SynthModule, SynthParam, SynthPort,
+};
 use crate::bit::Bit;
 use crate::module::port::PortDirection;
 use std::collections::BTreeMap;
@@ -9,7 +11,7 @@ use std::collections::BTreeMap;
 impl From<yosys_netlist_json::PortDirection> for PortDirection {
   fn from(value: yosys_netlist_json::PortDirection) -> Self {
     match value {
-      yosys_netlist_json::PortDirection::InOut => todo!("Inout not supported"),
+      yosys_netlist_json::PortDirection::InOut => Self::InOut,
       yosys_netlist_json::PortDirection::Input => Self::Input,
       yosys_netlist_json::PortDirection::Output => Self::Output,
     }
@@ -23,8 +25,10 @@ impl From<yosys_netlist_json::BitVal> for SynthBit {
       yosys_netlist_json::BitVal::S(constant) => Self::Constant(match constant {
         yosys_netlist_json::SpecialBit::_0 => Bit::Zero,
         yosys_netlist_json::SpecialBit::_1 => Bit::One,
-        yosys_netlist_json::SpecialBit::X => todo!("X not supported"),
-        yosys_netlist_json::SpecialBit::Z => todo!("Z not supported"),
+        // Only 2-state logic is simulated, undefined bits become 0
+        yosys_netlist_json::SpecialBit::X => Bit::Zero,
+        // Undriven, tri-state nets are resolved separately
+        yosys_netlist_json::SpecialBit::Z => Bit::Zero,
       }),
     }
   }
@@ -40,6 +44,27 @@ impl From<yosys_netlist_json::Port> for SynthPort {
   }
 }
 
+impl From<yosys_netlist_json::AttributeVal> for SynthParam {
+  fn from(value: yosys_netlist_json::AttributeVal) -> Self {
+    match value {
+      yosys_netlist_json::AttributeVal::N(val) => Self::Bits(
+        (0..usize::BITS)
+          .map(|i| Bit::from((val >> i) & 1 == 1))
+          .collect(),
+      ),
+      yosys_netlist_json::AttributeVal::S(val) => {
+        // Yosys writes constants as bit strings (MSB first) and pads strings
+        // that could be mistaken for constants with a trailing space.
+        if !val.is_empty() && val.chars().all(|c| matches!(c, '0' | '1' | 'x' | 'z')) {
+          Self::Bits(val.chars().rev().map(|c| Bit::from(c == '1')).collect())
+        } else {
+          Self::String(val.strip_suffix(' ').unwrap_or(&val).to_string())
+        }
+      }
+    }
+  }
+}
+
 impl From<yosys_netlist_json::Cell> for SynthCell {
   fn from(value: yosys_netlist_json::Cell) -> Self {
     let mut connections: BTreeMap<String, Vec<SynthBit>> = BTreeMap::new();
@@ -48,8 +73,22 @@ impl From<yosys_netlist_json::Cell> for SynthCell {
       connections.insert(key, bits);
     }
 
+    let parameters: BTreeMap<String, SynthParam> = value
+      .parameters
+      .into_iter()
+      .map(|(key, val)| (key, SynthParam::from(val)))
+      .collect();
+
+    let port_directions: BTreeMap<String, PortDirection> = value
+      .port_directions
+      .into_iter()
+      .map(|(key, direction)| (key, PortDirection::from(direction)))
+      .collect();
+
     Self {
       cell_type: value.cell_type,
+      parameters,
+      port_directions,
       connections,
     }
   }
@@ -0,0 +1,67 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+//! Helpers shared by the integration tests, each test only uses some of them.
+#![allow(dead_code)]
+
+use arbolta::bit::Bit;
+use arbolta::cell::{default_cell_library, Cell, Function};
+use arbolta::module::design::Design;
+use arbolta::module::hardware_module::{Component, HardwareModule};
+use arbolta::signal::{AccessSignal, Signal};
+use arbolta::synth::netlist::Netlist;
+
+pub static NESTED_ADDER_RAW: &str = include_str!("../test_netlists/4b_nested_adder_netlist.json");
+pub static MEMORY_RAW: &str = include_str!("../test_netlists/memory_netlist.json");
+
+/// Elaborate `top_module` with the default cell library, optionally flattened.
+pub fn design(raw: &str, top_module: &str, flatten: bool) -> Design {
+  let netlist = Netlist::from_yosys_raw(raw.as_bytes()).unwrap();
+  let cell_library = default_cell_library();
+  let module = if flatten {
+    netlist.generate_flat_module(top_module, &cell_library)
+  } else {
+    netlist.generate_module(top_module, &cell_library)
+  }
+  .unwrap();
+  Design::from_module(module, cell_library)
+}
+
+/// 4-bit adder made of full adder instances.
+pub fn nested_adder(flatten: bool) -> Design {
+  design(NESTED_ADDER_RAW, "adder", flatten)
+}
+
+/// Memory with a write port and a read port, clocked by `clk_i`.
+pub fn ram() -> Design {
+  let mut design = design(MEMORY_RAW, "ram", false);
+  design.set_clock("clk_i").unwrap();
+  design
+}
+
+/// Add a named net to a hand-built module, returns its index.
+pub fn add_net(module: &mut HardwareModule, name: &str) -> usize {
+  let idx = module.signals.len();
+  let mut signal = Signal::new_net(idx);
+  signal.set_name(name.to_string());
+  module.signals.push(signal);
+  module.signal_map.insert(name.to_string(), idx);
+  idx
+}
+
+pub fn add_cell(module: &mut HardwareModule, function: Function, inputs: &[usize], output: usize) {
+  let mut input_connections = [0; 8];
+  input_connections[..inputs.len()].copy_from_slice(inputs);
+  module.components.push(Component::Cell(Cell {
+    name: String::new(),
+    function,
+    state: [Bit::Zero; 2],
+    input_connections,
+    output_connection: output,
+    num_inputs: inputs.len(),
+  }));
+}
+
+pub fn get(design: &mut Design, name: &str) -> Bit {
+  design.module.search_signal(name).unwrap()
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::module::batch::{BatchRunner, Stimulus};
+use arbolta::signal::AccessSignal;
+use common::nested_adder;
+use rstest::rstest;
+
+/// Operands of each step of sequence `seq`
+fn operands(seq: u128) -> Vec<(u128, u128)> {
+  (0..5)
+    .map(|step| ((seq * 3 + step) % 16, (seq * step * 7) % 16))
+    .collect()
+}
+
+fn stimuli(num_sequences: u128) -> Vec<Stimulus> {
+  (0..num_sequences)
+    .map(|seq| Stimulus {
+      steps: operands(seq)
+        .into_iter()
+        .map(|(a, b)| vec![("op0_i".to_string(), a), ("op1_i".to_string(), b)])
+        .collect(),
+    })
+    .collect()
+}
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+#[case(0)]
+fn test_batch_nested_adder(#[case] threads: usize) {
+  let design = nested_adder(false);
+  let mut runner = BatchRunner::new(threads);
+  runner.outputs = vec!["sum_o".to_string(), "adder.fa1.sum_o".to_string()];
+  let result = runner.run(&design, &stimuli(10)).unwrap();
+
+  assert_eq!(result.outputs.len(), 10);
+  for (seq, outputs) in result.outputs.iter().enumerate() {
+    // Bit 1 of the sum comes from the second full adder
+    assert!(outputs.iter().all(|step| step[1] == (step[0] >> 1) & 1));
+    let sums: Vec<u128> = outputs.iter().map(|step| step[0]).collect();
+    let expected: Vec<u128> = operands(seq as u128)
+      .into_iter()
+      .map(|(a, b)| a + b)
+      .collect();
+    assert_eq!(sums, expected);
+  }
+
+  // Same statistics as running each sequence on a fresh design
+  let (mut toggles, mut fa2_toggles, mut op0_toggles) = (0, 0, 0);
+  for seq in 0..10 {
+    let mut design = nested_adder(false);
+    for (a, b) in operands(seq) {
+      design.module.set_port_int("op0_i", a as u8).unwrap();
+      design.module.set_port_int("op1_i", b as u8).unwrap();
+      design.eval();
+    }
+    toggles += design.get_module_total_toggle_count("adder").unwrap();
+    fa2_toggles += design.get_module_total_toggle_count("adder.fa2").unwrap();
+    op0_toggles += design.module.ports["op0_i"]
+      .signal_idx_list
+      .iter()
+      .map(|idx| design.module.signals[*idx].get_total_toggle_count())
+      .sum::<usize>();
+  }
+  assert_eq!(result.get_total_toggle_count(), toggles);
+  assert_eq!(
+    result
+      .design
+      .get_module_total_toggle_count("adder.fa2")
+      .unwrap(),
+    fa2_toggles
+  );
+  assert_eq!(result.get_port_toggle_counts()["op0_i"], op0_toggles);
+
+  let area = design.get_module_area("adder").unwrap();
+  assert!((result.get_toggles_per_area().unwrap() - toggles as f64 / area).abs() < 1e-9);
+  assert_eq!(result.get_hierarchy_report().instances[0].toggles, toggles);
+}
+
+#[test]
+fn test_batch_error() {
+  let mut stimuli = stimuli(4);
+  stimuli[2].steps[1].push(("missing_i".to_string(), 1));
+
+  let runner = BatchRunner::new(2);
+  assert!(runner.run(&nested_adder(false), &stimuli).is_err());
+}
@@ -0,0 +1,129 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use arbolta::bit::Bit;
+use arbolta::cell::{default_cell_library, BehaviorInfo, CellBehavior, CellLibrary};
+use arbolta::module::design::Design;
+use arbolta::synth::netlist::Netlist;
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+static BEHAVIOR_RAW: &str = include_str!("test_netlists/behavior_netlist.json");
+
+/// 4x4 multiplier that drops the two lowest product bits, stored as a lookup table.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ApproxMultiplier {
+  table: Vec<u8>,
+  lookups: usize,
+  last_select: Option<usize>,
+}
+
+#[typetag::serde]
+impl CellBehavior for ApproxMultiplier {
+  fn eval(&mut self, inputs: &[Bit], outputs: &mut [Bit]) {
+    let select = inputs
+      .iter()
+      .enumerate()
+      .fold(0, |acc, (i, bit)| acc | (bit.to_int::<usize>() << i));
+    if self.last_select != Some(select) {
+      self.lookups += 1;
+      self.last_select = Some(select);
+    }
+
+    let product = self.table[select];
+    for (i, bit) in outputs.iter_mut().enumerate() {
+      *bit = Bit::from((product >> i) & 1 == 1);
+    }
+  }
+
+  fn reset(&mut self) {
+    self.lookups = 0;
+    self.last_select = None;
+  }
+
+  fn get_statistics(&self) -> HashMap<String, usize> {
+    HashMap::from([("lookups".to_string(), self.lookups)])
+  }
+}
+
+fn approx_library() -> CellLibrary {
+  let table = (0..256_usize)
+    .map(|select| (((select & 0xf) * (select >> 4)) & !0b11) as u8)
+    .collect();
+
+  let mut cell_library = default_cell_library();
+  cell_library.register_behavior(BehaviorInfo {
+    name: "AMUL4".to_string(),
+    area: 24.0,
+    inputs: vec!["A".to_string(), "B".to_string()],
+    outputs: vec!["Y".to_string()],
+    behavior: Box::new(ApproxMultiplier {
+      table,
+      lookups: 0,
+      last_select: None,
+    }),
+  });
+  cell_library
+}
+
+fn approx_design() -> Design {
+  let cell_library = approx_library();
+  let netlist = Netlist::from_yosys_raw(BEHAVIOR_RAW.as_bytes()).unwrap();
+  let module = netlist.generate_module("amul", &cell_library).unwrap();
+  Design::from_module(module, cell_library)
+}
+
+#[rstest]
+#[case(3, 5, 12)]
+#[case(15, 15, 224)]
+#[case(1, 2, 0)]
+#[case(7, 9, 60)]
+fn test_behavior_eval(#[case] a: u8, #[case] b: u8, #[case] expected: u8) {
+  let mut design = approx_design();
+  design.module.set_port_int("a_i", a).unwrap();
+  design.module.set_port_int("b_i", b).unwrap();
+  design.eval();
+
+  assert_eq!(design.module.get_port_int::<u8>("y_o").unwrap(), expected);
+}
+
+#[test]
+fn test_behavior_statistics_and_area() {
+  let mut design = approx_design();
+  for a in 0..4_u8 {
+    design.module.set_port_int("a_i", a).unwrap();
+    design.eval();
+    design.eval();
+  }
+
+  let stats = design.module.get_behavior_statistics("mul").unwrap();
+  assert_eq!(stats["lookups"], 4);
+  assert_eq!(design.get_module_area("amul").unwrap(), 24.0);
+
+  design.module.reset();
+  let stats = design.module.get_behavior_statistics("mul").unwrap();
+  assert_eq!(stats["lookups"], 0);
+}
+
+#[test]
+fn test_behavior_save_load() {
+  let mut design = approx_design();
+  design.module.set_port_int("a_i", 6_u8).unwrap();
+  design.module.set_port_int("b_i", 6_u8).unwrap();
+  design.eval();
+
+  let path = std::env::temp_dir().join("arbolta_test_behavior.design");
+  let path = path.to_str().unwrap();
+  design.save(path).unwrap();
+  let mut loaded = Design::load(path).unwrap();
+  std::fs::remove_file(path).unwrap();
+
+  assert_eq!(loaded.module.get_port_int::<u8>("y_o").unwrap(), 36);
+  let stats = loaded.module.get_behavior_statistics("mul").unwrap();
+  assert_eq!(stats["lookups"], 1);
+
+  loaded.module.set_port_int("b_i", 7_u8).unwrap();
+  loaded.eval();
+  assert_eq!(loaded.module.get_port_int::<u8>("y_o").unwrap(), 40);
+}
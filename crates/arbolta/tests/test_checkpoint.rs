@@ -0,0 +1,190 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::bit::{Bit, BitVec};
+use arbolta::cell::default_cell_library;
+use arbolta::module::checkpoint::{Checkpoint, ComponentState};
+use arbolta::module::design::{Design, DesignError};
+use arbolta::module::model::ModuleModel;
+use arbolta::synth::netlist::Netlist;
+use common::{design, ram, NESTED_ADDER_RAW};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+static MODEL_RAW: &str = include_str!("test_netlists/model_netlist.json");
+
+/// Black-box adder that can't be saved, like a Python model.
+#[derive(Debug, Clone)]
+struct UnsavedAddModel;
+
+#[typetag::serde]
+impl ModuleModel for UnsavedAddModel {
+  fn eval(&mut self, inputs: &BTreeMap<String, BitVec>, outputs: &mut BTreeMap<String, BitVec>) {
+    let value = |bits: &BitVec| -> usize {
+      bits
+        .bits
+        .iter()
+        .enumerate()
+        .map(|(i, bit)| bit.to_int::<usize>() << i)
+        .sum()
+    };
+    let sum = value(&inputs["A"]) + value(&inputs["B"]);
+    let bits: Vec<Bit> = (0..4).map(|i| Bit::from((sum >> i) & 1 == 1)).collect();
+    outputs.insert("Y".to_string(), BitVec::from(bits));
+  }
+}
+
+impl Serialize for UnsavedAddModel {
+  fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+    Err(ser::Error::custom("model can't be serialized"))
+  }
+}
+
+impl<'de> Deserialize<'de> for UnsavedAddModel {
+  fn deserialize<D: Deserializer<'de>>(_deserializer: D) -> Result<Self, D::Error> {
+    Err(de::Error::custom("model can't be deserialized"))
+  }
+}
+
+fn write(design: &mut Design, addr: u8, data: u8) {
+  design.module.set_port_int("we_i", 1_u8).unwrap();
+  design.module.set_port_int("waddr_i", addr).unwrap();
+  design.module.set_port_int("wdata_i", data).unwrap();
+  design.eval_clocked().unwrap();
+  design.module.set_port_int("we_i", 0_u8).unwrap();
+}
+
+#[test]
+fn test_checkpoint_restore() {
+  let mut design = ram();
+  for addr in 0..4 {
+    write(&mut design, addr, 0x10 | addr);
+  }
+  let checkpoint = design.checkpoint();
+  let toggles = design.module.get_total_toggle_count();
+  let contents: Vec<u8> = design.module.get_memory_words("buffer").unwrap();
+  assert_eq!(checkpoint.cycles, 4);
+
+  for addr in 0..16 {
+    write(&mut design, addr, 0xff);
+  }
+  assert_eq!(design.cycles, 20);
+
+  design.restore(&checkpoint).unwrap();
+  assert_eq!(design.cycles, 4);
+  assert_eq!(design.module.get_total_toggle_count(), toggles);
+  assert_eq!(
+    design.module.get_memory_words::<u8>("buffer").unwrap(),
+    contents
+  );
+  assert_eq!(design.checkpoint(), checkpoint);
+
+  // Continues as if nothing happened since the checkpoint
+  let mut reference = ram();
+  for addr in 0..4 {
+    write(&mut reference, addr, 0x10 | addr);
+  }
+  for design in [&mut design, &mut reference] {
+    write(design, 5, 0x55);
+    design.module.set_port_int("raddr_i", 5_u8).unwrap();
+    design.eval();
+  }
+  assert_eq!(design.module.get_port_int::<u8>("rdata_o").unwrap(), 0x55);
+  assert_eq!(design.checkpoint(), reference.checkpoint());
+}
+
+#[test]
+fn test_checkpoint_fork() {
+  let mut design = design(NESTED_ADDER_RAW, "adder", false);
+  design.module.set_port_int("op0_i", 5_u8).unwrap();
+  design.module.set_port_int("op1_i", 6_u8).unwrap();
+  design.eval();
+  let warm = design.checkpoint();
+
+  // Each what-if run starts from the same warm state
+  let sums: Vec<u8> = (0..4_u8)
+    .map(|b| {
+      let mut fork = design.clone();
+      fork.restore(&warm).unwrap();
+      fork.module.set_port_int("op1_i", b).unwrap();
+      fork.eval();
+      fork.module.get_port_int("sum_o").unwrap()
+    })
+    .collect();
+  assert_eq!(sums, vec![5, 6, 7, 8]);
+  assert_eq!(design.checkpoint(), warm);
+}
+
+#[test]
+fn test_checkpoint_save_load() {
+  let mut design = ram();
+  write(&mut design, 3, 0x33);
+  let checkpoint = design.checkpoint();
+
+  let path = std::env::temp_dir().join("arbolta_test_checkpoint.checkpoint");
+  let path = path.to_str().unwrap();
+  checkpoint.save(path).unwrap();
+  let loaded = Checkpoint::load(path).unwrap();
+  assert_eq!(loaded, checkpoint);
+
+  let mut fresh = ram();
+  fresh.restore(&loaded).unwrap();
+  assert_eq!(fresh.checkpoint(), checkpoint);
+}
+
+#[test]
+fn test_checkpoint_mismatch() {
+  let checkpoint = ram().checkpoint();
+  let mut design = design(NESTED_ADDER_RAW, "adder", false);
+  let before = design.checkpoint();
+
+  assert!(matches!(
+    design.restore(&checkpoint),
+    Err(DesignError::CheckpointMismatch(_))
+  ));
+  assert_eq!(design.checkpoint(), before);
+}
+
+#[test]
+fn test_checkpoint_save_model() {
+  let mut cell_library = default_cell_library();
+  cell_library.register_model("blackbox_add", Box::new(UnsavedAddModel));
+  let netlist = Netlist::from_yosys_raw(MODEL_RAW.as_bytes()).unwrap();
+  let module = netlist.generate_module("top", &cell_library).unwrap();
+  let mut design = Design::from_module(module, cell_library);
+  design.module.set_port_int("a_i", 3_u8).unwrap();
+  design.module.set_port_int("b_i", 4_u8).unwrap();
+  design.eval();
+  let checkpoint = design.checkpoint();
+
+  // Models aren't saved, everything else is
+  let path = std::env::temp_dir().join("arbolta_test_checkpoint_model.checkpoint");
+  let path = path.to_str().unwrap();
+  checkpoint.save(path).unwrap();
+  let loaded = Checkpoint::load(path).unwrap();
+  assert_eq!(loaded, checkpoint);
+
+  design.module.set_port_int("a_i", 1_u8).unwrap();
+  design.eval();
+  design.restore(&loaded).unwrap();
+  assert_eq!(design.module.get_port_int::<u8>("sum_o").unwrap(), 7);
+  assert_eq!(design.checkpoint(), checkpoint);
+}
+
+#[test]
+fn test_checkpoint_kind_mismatch() {
+  let mut design = ram();
+  write(&mut design, 3, 0x33);
+  let before = design.checkpoint();
+
+  // Same number of components, but not a memory
+  let mut checkpoint = before.clone();
+  checkpoint.components[0] = ComponentState::Tristate(vec![]);
+  assert!(matches!(
+    design.restore(&checkpoint),
+    Err(DesignError::CheckpointMismatch(_))
+  ));
+  assert_eq!(design.checkpoint(), before);
+}
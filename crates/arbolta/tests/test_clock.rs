@@ -0,0 +1,144 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::bit::Bit;
+use arbolta::cell::{default_cell_library, Function};
+use arbolta::module::design::{Design, DesignError};
+use arbolta::module::hardware_module::HardwareModule;
+use arbolta::signal::AccessSignal;
+use common::{add_cell, add_net, get};
+
+/// `d` shifted through `a0` -> `a1` on `fast_clk` and `b0` -> `b1` on `slow_clk`
+fn two_domains() -> Design {
+  let mut module = HardwareModule {
+    name: "domains".to_string(),
+    ..Default::default()
+  };
+  let fast = add_net(&mut module, "fast_clk");
+  let slow = add_net(&mut module, "slow_clk");
+  let d = add_net(&mut module, "d");
+  for (clock, prefix) in [(fast, "a"), (slow, "b")] {
+    let q0 = add_net(&mut module, &format!("{prefix}0"));
+    let q1 = add_net(&mut module, &format!("{prefix}1"));
+    add_cell(&mut module, Function::DffPosEdge, &[clock, d], q0);
+    add_cell(&mut module, Function::DffPosEdge, &[clock, q0], q1);
+  }
+  module.levelize();
+
+  let mut design = Design::from_module(module, default_cell_library());
+  design.add_clock("fast_clk", 10, 0).unwrap();
+  design
+    .add_derived_clock("slow_clk", "fast_clk", 1, 2)
+    .unwrap();
+  design
+}
+
+#[test]
+fn test_clock_edges() {
+  let mut design = two_domains();
+  let times: Vec<u64> = (0..6).map(|_| design.step().unwrap()).collect();
+  // Both clocks rise at time 0
+  assert_eq!(times, vec![0, 5, 10, 15, 20, 25]);
+  assert_eq!(get(&mut design, "fast_clk"), Bit::Zero);
+  assert_eq!(get(&mut design, "slow_clk"), Bit::One);
+  assert_eq!(design.clocks[0].cycles, 3);
+  assert_eq!(design.clocks[1].cycles, 2);
+
+  let mut design = Design::from_module(two_domains().module, default_cell_library());
+  design.add_clock("fast_clk", 4, 3).unwrap();
+  assert_eq!(design.step().unwrap(), 3);
+  assert_eq!(get(&mut design, "fast_clk"), Bit::One);
+  assert_eq!(design.step().unwrap(), 5);
+  assert_eq!(design.step().unwrap(), 7);
+}
+
+#[test]
+fn test_clock_domains() {
+  let mut design = two_domains();
+  let d = design.module.get_signal_idx("d").unwrap();
+  design.module.set_signal(d, Bit::One).unwrap();
+
+  design.advance_to(15).unwrap();
+  assert_eq!(design.time, 15);
+  assert_eq!(get(&mut design, "a1"), Bit::One);
+  assert_eq!(get(&mut design, "b0"), Bit::One);
+  assert_eq!(get(&mut design, "b1"), Bit::Zero);
+
+  design.advance_to(42).unwrap();
+  assert_eq!(design.time, 42);
+  assert_eq!(get(&mut design, "b1"), Bit::One);
+  assert_eq!(design.clocks[0].cycles, 5);
+  assert_eq!(design.clocks[1].cycles, 3);
+
+  // Every toggle after driving `d` is charged to a domain, even when edges coincide
+  let toggles: usize = design.clocks.iter().map(|clock| clock.toggles).sum();
+  assert_eq!(toggles, design.module.get_total_toggle_count() - 1);
+  let domain_toggles = |design: &Design, names: &[&str]| -> usize {
+    names
+      .iter()
+      .map(|name| {
+        let signal = &design.module.signals[design.module.get_signal_idx(name).unwrap()];
+        signal.get_toggle_count_rising() + signal.get_toggle_count_falling()
+      })
+      .sum()
+  };
+  assert_eq!(
+    design.clocks[0].toggles,
+    domain_toggles(&design, &["fast_clk", "a0", "a1"])
+  );
+  assert_eq!(
+    design.clocks[1].toggles,
+    domain_toggles(&design, &["slow_clk", "b0", "b1"])
+  );
+
+  let mut shifted = Design::from_module(two_domains().module, default_cell_library());
+  shifted.add_clock("fast_clk", 10, 0).unwrap();
+  shifted.add_clock("slow_clk", 20, 3).unwrap();
+  shifted.advance_to(23).unwrap();
+  // `slow_clk` rises at 3 and 23 and falls at 13, with `d` low nothing else toggles
+  assert_eq!(shifted.clocks[1].cycles, 2);
+  assert_eq!(shifted.clocks[1].toggles, 3);
+
+  // Time and clock statistics are part of checkpoints
+  let checkpoint = design.checkpoint();
+  design.advance_to(100).unwrap();
+  design.restore(&checkpoint).unwrap();
+  assert_eq!(design.time, 42);
+  assert_eq!(design.clocks[0].cycles, 5);
+  assert_eq!(design.step().unwrap(), 45);
+
+  // Clock domains are found again for the compiled module
+  let mut compiled = two_domains();
+  compiled.module.set_signal(d, Bit::One).unwrap();
+  compiled.step().unwrap();
+  compiled.compile().unwrap();
+  compiled.advance_to(42).unwrap();
+  let mut reference = two_domains();
+  reference.module.set_signal(d, Bit::One).unwrap();
+  reference.advance_to(42).unwrap();
+  assert_eq!(compiled.clocks, reference.clocks);
+}
+
+#[test]
+fn test_clock_errors() {
+  let mut design = two_domains();
+  assert!(matches!(
+    design.add_clock("d", 1, 0),
+    Err(DesignError::InvalidClock(..))
+  ));
+  assert!(matches!(
+    design.add_clock("fast_clk", 8, 0),
+    Err(DesignError::InvalidClock(..))
+  ));
+  assert!(matches!(
+    design.add_derived_clock("d", "fast_clk", 3, 1),
+    Err(DesignError::InvalidClock(..))
+  ));
+  assert!(design.add_derived_clock("d", "missing_clk", 1, 1).is_err());
+  assert!(design.add_clock("missing", 8, 0).is_err());
+
+  let mut design = Design::from_module(two_domains().module, default_cell_library());
+  assert!(design.step().is_err());
+}
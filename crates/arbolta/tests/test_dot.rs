@@ -0,0 +1,102 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::module::design::DesignError;
+use arbolta::module::dot::{DotActivity, DotConfig};
+use arbolta::module::hardware_module::ModuleError;
+use common::{design, MEMORY_RAW, NESTED_ADDER_RAW};
+use rstest::rstest;
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn test_dot_hierarchy(#[case] flatten: bool) {
+  let mut design = design(NESTED_ADDER_RAW, "adder", flatten);
+  design.module.set_port_int("op0_i", 0b0101_u8).unwrap();
+  design.eval();
+
+  let dot = design.to_dot(&DotConfig::default()).unwrap();
+  assert!(dot.starts_with("digraph \"adder\" {\n"));
+  assert!(
+    dot.contains("n0 [label=\"op0_i\\n2\", shape=octagon, fillcolor=\"0.000 0.600 1.000\"];\n")
+  );
+  // Idle submodules are blue
+  assert!(dot
+    .contains("[label=\"fa1\\nfull_adder\\n0\", shape=box3d, fillcolor=\"0.667 0.600 1.000\"];\n"));
+  assert!(!dot.contains("subgraph"));
+  assert_eq!(dot.matches("shape=box3d").count(), 4);
+
+  let config = DotConfig {
+    cluster: true,
+    ..Default::default()
+  };
+  let dot = design.to_dot(&config).unwrap();
+  assert_eq!(dot.matches("subgraph cluster_").count(), 4);
+  assert!(dot.contains("subgraph cluster_3 {\nlabel=\"fa2\\nfull_adder\";\n"));
+  assert!(!dot.contains("shape=box3d"));
+  assert_eq!(dot.matches("shape=box,").count(), 48);
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn test_dot_scope(#[case] flatten: bool) {
+  let design = design(NESTED_ADDER_RAW, "adder", flatten);
+  let config = DotConfig {
+    scope: "adder.fa2".to_string(),
+    ..Default::default()
+  };
+  let dot = design.to_dot(&config).unwrap();
+  assert!(dot.starts_with("digraph \"adder.fa2\" {\n"));
+  assert!(dot.contains("[label=\"carry_i\\n0\", shape=octagon"));
+  assert!(dot.contains("[label=\"$165\\nNAND\\n0\", shape=box"));
+  assert_eq!(dot.matches("shape=box,").count(), 12);
+
+  let config = DotConfig {
+    scope: "adder.fa4".to_string(),
+    ..Default::default()
+  };
+  assert!(matches!(
+    design.to_dot(&config),
+    Err(DesignError::ModuleError(ModuleError::MissingPath(path))) if path == "adder.fa4"
+  ));
+}
+
+#[test]
+fn test_dot_probability() {
+  let mut design = design(MEMORY_RAW, "ram", false);
+  design.set_clock("clk_i").unwrap();
+  let config = DotConfig {
+    activity: DotActivity::Probability,
+    ..Default::default()
+  };
+  assert!(matches!(
+    design.to_dot(&config),
+    Err(DesignError::MissingSaif)
+  ));
+
+  design.start_saif(10);
+  // Nothing recorded yet
+  let dot = design.to_dot(&config).unwrap();
+  assert!(!dot.contains("p="));
+  assert!(dot.contains("fillcolor=gray"));
+
+  for addr in 0..4_u8 {
+    design.module.set_port_int("we_i", 1_u8).unwrap();
+    design.module.set_port_int("waddr_i", addr).unwrap();
+    design.eval_clocked().unwrap();
+    design.module.set_port_int("we_i", 0_u8).unwrap();
+  }
+  let dot = design.to_dot(&config).unwrap();
+  assert!(
+    dot.contains("[label=\"we_i\\np=1.00\", shape=octagon, fillcolor=\"0.000 0.600 1.000\"];\n")
+  );
+  assert!(dot.contains("n4 -> n7 [label=\"waddr_i\\np=0.25\", color=\"0.500 0.600 1.000\"];\n"));
+
+  let path = std::env::temp_dir().join("arbolta_test_dot.dot");
+  let path = path.to_str().unwrap();
+  design.save_dot(path, &config).unwrap();
+  assert_eq!(std::fs::read_to_string(path).unwrap(), dot);
+}
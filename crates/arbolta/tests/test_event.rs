@@ -0,0 +1,70 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use arbolta::cell::default_cell_library;
+use arbolta::module::design::Design;
+use arbolta::module::event::Scheduler;
+use arbolta::synth::netlist::Netlist;
+
+static NESTED_ADDER_RAW: &str = include_str!("test_netlists/4b_nested_adder_netlist.json");
+static WORD_ALU_RAW: &str = include_str!("test_netlists/word_alu_netlist.json");
+
+fn design(raw: &str, top_module: &str, scheduler: Scheduler) -> Design {
+  let netlist = Netlist::from_yosys_raw(raw.as_bytes()).unwrap();
+  let cell_library = default_cell_library();
+  let module = netlist.generate_module(top_module, &cell_library).unwrap();
+  let mut design = Design::from_module(module, cell_library);
+  design.set_scheduler(scheduler);
+  design
+}
+
+#[test]
+fn test_event_nested_adder() {
+  let mut levelized = design(NESTED_ADDER_RAW, "adder", Scheduler::Levelized);
+  let mut event_driven = design(NESTED_ADDER_RAW, "adder", Scheduler::EventDriven);
+
+  for a in 0..16_u8 {
+    for b in 0..16_u8 {
+      for design in [&mut levelized, &mut event_driven] {
+        design.module.set_port_int("op0_i", a).unwrap();
+        design.module.set_port_int("op1_i", b).unwrap();
+        design.eval();
+        assert_eq!(design.module.get_port_int::<u8>("sum_o").unwrap(), a + b);
+      }
+    }
+  }
+
+  assert_eq!(
+    event_driven.get_module_total_toggle_count("adder").unwrap(),
+    levelized.get_module_total_toggle_count("adder").unwrap()
+  );
+  assert_eq!(
+    event_driven.get_module_total_toggle_count("full_adder").unwrap(),
+    levelized.get_module_total_toggle_count("full_adder").unwrap()
+  );
+}
+
+#[test]
+fn test_event_clocked() {
+  let mut levelized = design(WORD_ALU_RAW, "alu", Scheduler::Levelized);
+  let mut event_driven = design(WORD_ALU_RAW, "alu", Scheduler::EventDriven);
+
+  for a in [0x00_u8, 0xa5, 0xa5, 0x0f, 0x0f, 0x0f, 0xff, 0x00] {
+    for design in [&mut levelized, &mut event_driven] {
+      design.set_clock("clk_i").unwrap();
+      design.module.set_port_int("a_i", a).unwrap();
+      design.module.set_port_int("b_i", a ^ 0x3c).unwrap();
+      design.eval_clocked().unwrap();
+      assert_eq!(design.module.get_port_int::<u8>("q_o").unwrap(), a);
+    }
+    assert_eq!(
+      event_driven.module.get_port_int::<u16>("prod_o").unwrap(),
+      levelized.module.get_port_int::<u16>("prod_o").unwrap()
+    );
+  }
+
+  assert_eq!(
+    event_driven.get_module_total_toggle_count("alu").unwrap(),
+    levelized.get_module_total_toggle_count("alu").unwrap()
+  );
+}
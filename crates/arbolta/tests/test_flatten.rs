@@ -0,0 +1,89 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::module::event::Scheduler;
+use arbolta::module::hardware_module::Component;
+use common::{design, NESTED_ADDER_RAW};
+use rstest::rstest;
+
+static WORD_ALU_RAW: &str = include_str!("test_netlists/word_alu_netlist.json");
+
+#[rstest]
+#[case(Scheduler::Levelized)]
+#[case(Scheduler::EventDriven)]
+fn test_flatten_nested_adder(#[case] scheduler: Scheduler) {
+  let mut hierarchical = design(NESTED_ADDER_RAW, "adder", false);
+  let mut flat = design(NESTED_ADDER_RAW, "adder", true);
+  flat.set_scheduler(scheduler);
+
+  assert!(!flat
+    .module
+    .components
+    .iter()
+    .any(|component| matches!(component, Component::Module(_))));
+  assert_eq!(flat.module.instances[0].module, "adder");
+  assert!(flat
+    .module
+    .instances
+    .iter()
+    .skip(1)
+    .all(|instance| instance.module == "full_adder" && instance.parent == Some(0)));
+
+  for a in 0..16_u8 {
+    for b in 0..16_u8 {
+      for design in [&mut hierarchical, &mut flat] {
+        design.module.set_port_int("op0_i", a).unwrap();
+        design.module.set_port_int("op1_i", b).unwrap();
+        design.eval();
+        assert_eq!(design.module.get_port_int::<u8>("sum_o").unwrap(), a + b);
+      }
+    }
+  }
+
+  for name in ["adder", "full_adder"] {
+    assert_eq!(
+      flat.get_module_breakdown(name).unwrap(),
+      hierarchical.get_module_breakdown(name).unwrap()
+    );
+    assert_eq!(
+      flat.get_module_area(name).unwrap(),
+      hierarchical.get_module_area(name).unwrap()
+    );
+    assert_eq!(
+      flat.get_module_total_toggle_count(name).unwrap(),
+      hierarchical.get_module_total_toggle_count(name).unwrap()
+    );
+    assert_eq!(
+      flat.module.get_module_bit_flips(name),
+      hierarchical.module.get_module_bit_flips(name)
+    );
+  }
+}
+
+#[test]
+fn test_flatten_clocked() {
+  let mut hierarchical = design(WORD_ALU_RAW, "alu", false);
+  let mut flat = design(WORD_ALU_RAW, "alu", true);
+
+  for a in [0x00_u8, 0xa5, 0x0f, 0xff, 0x00] {
+    for design in [&mut hierarchical, &mut flat] {
+      design.set_clock("clk_i").unwrap();
+      design.module.set_port_int("a_i", a).unwrap();
+      design.module.set_port_int("b_i", a ^ 0x3c).unwrap();
+      design.eval_clocked().unwrap();
+      assert_eq!(design.module.get_port_int::<u8>("q_o").unwrap(), a);
+    }
+  }
+  assert_eq!(
+    flat.get_module_total_toggle_count("alu").unwrap(),
+    hierarchical.get_module_total_toggle_count("alu").unwrap()
+  );
+}
+
+#[test]
+fn test_flatten_missing_module() {
+  let flat = design(NESTED_ADDER_RAW, "adder", true);
+  assert!(flat.get_module_breakdown("half_adder").is_err());
+}
@@ -0,0 +1,134 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::bit::Bit;
+use arbolta::cell::{default_cell_library, Cell, Function};
+use arbolta::module::design::Design;
+use arbolta::module::hardware_module::{Component, HardwareModule, ModuleError};
+use arbolta::module::lanes::{LaneDesign, LANES};
+use arbolta::signal::{AccessSignal, Signal};
+use arbolta::synth::netlist::Netlist;
+use common::nested_adder;
+
+static WORD_ALU_RAW: &str = include_str!("test_netlists/word_alu_netlist.json");
+
+/// Operands of stimulus `i`
+fn operands(i: usize) -> (u8, u8) {
+  ((i * 7 % 16) as u8, (i * 13 % 11) as u8)
+}
+
+#[test]
+fn test_lanes_nested_adder() {
+  let num_vectors = 3 * LANES + 5;
+  let (op0, op1): (Vec<u8>, Vec<u8>) = (0..num_vectors).map(operands).unzip();
+
+  let mut lanes = LaneDesign::new(&nested_adder(false)).unwrap();
+  let results = lanes
+    .eval_batch(&[("op0_i", &op0), ("op1_i", &op1)], &["sum_o"], false)
+    .unwrap();
+  let expected: Vec<u8> = (0..num_vectors).map(|i| op0[i] + op1[i]).collect();
+  assert_eq!(results, vec![expected]);
+
+  // Each lane is its own stream, elements `LANES` apart
+  let mut scalar_toggles = 0;
+  let mut scalar_fa1_toggles = 0;
+  for lane in 0..LANES {
+    let mut design = nested_adder(false);
+    for i in (lane..num_vectors).step_by(LANES) {
+      design.module.set_port_int("op0_i", op0[i]).unwrap();
+      design.module.set_port_int("op1_i", op1[i]).unwrap();
+      design.eval();
+    }
+    scalar_toggles += design.get_module_total_toggle_count("adder").unwrap();
+    scalar_fa1_toggles += design.get_module_total_toggle_count("adder.fa1").unwrap();
+  }
+
+  let design = lanes.flush_statistics();
+  assert_eq!(
+    design.get_module_total_toggle_count("adder").unwrap(),
+    scalar_toggles
+  );
+  assert_eq!(
+    design.get_module_total_toggle_count("adder.fa1").unwrap(),
+    scalar_fa1_toggles
+  );
+
+  // Flushing again adds nothing
+  let design = lanes.flush_statistics();
+  assert_eq!(
+    design.get_module_total_toggle_count("adder").unwrap(),
+    scalar_toggles
+  );
+
+  lanes.reset();
+  assert_eq!(
+    lanes.design.get_module_total_toggle_count("adder").unwrap(),
+    0
+  );
+}
+
+#[test]
+fn test_lanes_shift_register() {
+  let mut module = HardwareModule::default();
+  let mut add_net = |name: &str| {
+    let idx = module.signals.len();
+    let mut signal = Signal::new_net(idx);
+    signal.set_name(name.to_string());
+    module.signals.push(signal);
+    module.signal_map.insert(name.to_string(), idx);
+    idx
+  };
+  let clk = add_net("clk");
+  let d = add_net("d");
+  let q: Vec<usize> = (0..3).map(|i| add_net(&format!("q{i}"))).collect();
+  for (data, out) in [d, q[0], q[1]].into_iter().zip(&q) {
+    let mut input_connections = [0; 8];
+    input_connections[..2].copy_from_slice(&[clk, data]);
+    module.components.push(Component::Cell(Cell {
+      name: String::new(),
+      function: Function::DffPosEdge,
+      state: [Bit::Zero; 2],
+      input_connections,
+      output_connection: *out,
+      num_inputs: 2,
+    }));
+  }
+  module.levelize();
+  let mut design = Design::from_module(module, default_cell_library());
+  design.set_clock("clk").unwrap();
+
+  // Each lane shifts its own pattern
+  let pattern: u64 = 0xDEAD_BEEF_0123_4567;
+  let mut lanes = LaneDesign::new(&design).unwrap();
+  lanes.set_signal_lanes(d, pattern).unwrap();
+  lanes.eval_clocked().unwrap();
+  lanes.set_signal_lanes(d, !pattern).unwrap();
+  for (cycle, expected) in [[!pattern, pattern, 0], [!pattern, !pattern, pattern]]
+    .into_iter()
+    .enumerate()
+  {
+    lanes.eval_clocked().unwrap();
+    let stages: Vec<u64> = q
+      .iter()
+      .map(|idx| lanes.get_signal_lanes(*idx).unwrap())
+      .collect();
+    assert_eq!(stages, expected, "cycle {cycle}");
+  }
+}
+
+#[test]
+fn test_lanes_unsupported() {
+  let netlist = Netlist::from_yosys_raw(WORD_ALU_RAW.as_bytes()).unwrap();
+  let cell_library = default_cell_library();
+  let module = netlist.generate_module("alu", &cell_library).unwrap();
+  let design = Design::from_module(module, cell_library);
+
+  assert!(matches!(
+    LaneDesign::new(&design),
+    Err(arbolta::module::design::DesignError::ModuleError(
+      ModuleError::UnsupportedLanes(_)
+    ))
+  ));
+}
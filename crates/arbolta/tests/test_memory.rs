@@ -0,0 +1,141 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use arbolta::cell::default_cell_library;
+use arbolta::module::hardware_module::HardwareModule;
+use arbolta::synth::netlist::Netlist;
+use std::io::Write;
+
+static MEMORY_RAW: &str = include_str!("test_netlists/memory_netlist.json");
+
+fn ram() -> HardwareModule {
+  let netlist = Netlist::from_yosys_raw(MEMORY_RAW.as_bytes()).unwrap();
+  netlist
+    .generate_module("ram", &default_cell_library())
+    .unwrap()
+}
+
+fn clock(module: &mut HardwareModule) {
+  module.set_port_int("clk_i", 0_u8).unwrap();
+  module.eval();
+  module.set_port_int("clk_i", 1_u8).unwrap();
+  module.eval();
+  module.set_port_int("clk_i", 0_u8).unwrap();
+  module.eval();
+}
+
+#[test]
+fn test_memory_init() {
+  let mut ram = ram();
+  ram.set_port_int("raddr_i", 3_u8).unwrap();
+  ram.eval();
+  assert_eq!(ram.get_port_int::<u8>("rdata_o").unwrap(), 0x42);
+
+  let contents: Vec<u8> = ram.get_memory_words("buffer").unwrap();
+  assert_eq!(contents.len(), 16);
+  assert_eq!(contents[3], 0x42);
+}
+
+#[test]
+fn test_memory_backdoor_load() {
+  let mut ram = ram();
+  let words: Vec<u8> = (0..16).map(|i| i * 3).collect();
+  // Memory can be found by `MEMID` as well as instance name
+  ram.set_memory_words("\\buffer", &words).unwrap();
+
+  for addr in 0..16_u8 {
+    ram.set_port_int("raddr_i", addr).unwrap();
+    ram.eval();
+    assert_eq!(ram.get_port_int::<u8>("rdata_o").unwrap(), addr * 3);
+  }
+}
+
+#[test]
+fn test_memory_write_then_dump() {
+  let mut ram = ram();
+  ram.set_port_int("we_i", 1_u8).unwrap();
+  for addr in 0..16_u8 {
+    ram.set_port_int("waddr_i", addr).unwrap();
+    ram.set_port_int("wdata_i", 0xf0 | addr).unwrap();
+    clock(&mut ram);
+  }
+  ram.set_port_int("we_i", 0_u8).unwrap();
+
+  let contents: Vec<u8> = ram.get_memory_words("buffer").unwrap();
+  let expected: Vec<u8> = (0..16).map(|addr| 0xf0 | addr).collect();
+  assert_eq!(contents, expected);
+
+  let stats = ram.get_memory_statistics("buffer").unwrap();
+  assert_eq!(stats.writes, vec![16]);
+}
+
+#[test]
+fn test_memory_sync_read_sees_old_data() {
+  let mut ram = ram();
+  ram.set_port_int("raddr_i", 5_u8).unwrap();
+  ram.set_port_int("waddr_i", 5_u8).unwrap();
+  ram.set_port_int("wdata_i", 0x99_u8).unwrap();
+  ram.set_port_int("we_i", 1_u8).unwrap();
+  clock(&mut ram);
+
+  // Port is not transparent, registered read returns old contents
+  assert_eq!(ram.get_port_int::<u8>("rdata_q_o").unwrap(), 0);
+  assert_eq!(ram.get_port_int::<u8>("rdata_o").unwrap(), 0x99);
+
+  ram.set_port_int("we_i", 0_u8).unwrap();
+  clock(&mut ram);
+  assert_eq!(ram.get_port_int::<u8>("rdata_q_o").unwrap(), 0x99);
+
+  let stats = ram.get_memory_statistics("buffer").unwrap();
+  assert_eq!(stats.reads[1], 2);
+}
+
+#[test]
+fn test_memory_load_hex() {
+  let path = std::env::temp_dir().join("arbolta_test_memory_load_hex.hex");
+  let mut file = std::fs::File::create(&path).unwrap();
+  writeln!(file, "// weights\n01 02 03\n@a\nff_ 7e").unwrap();
+
+  let mut ram = ram();
+  ram
+    .load_memory_hex("buffer", path.to_str().unwrap())
+    .unwrap();
+  let contents: Vec<u8> = ram.get_memory_words("buffer").unwrap();
+  assert_eq!(&contents[0..4], &[1, 2, 3, 0x42]);
+  assert_eq!(&contents[10..12], &[0xff, 0x7e]);
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_memory_async_write_counts() {
+  // Same RAM with an asynchronous write port and only 8 words
+  let raw = MEMORY_RAW
+    .replace("\"WR_CLK_ENABLE\": \"1\"", "\"WR_CLK_ENABLE\": \"0\"")
+    .replace(
+      "\"SIZE\": \"00000000000000000000000000010000\"",
+      "\"SIZE\": \"00000000000000000000000000001000\"",
+    );
+  let netlist = Netlist::from_yosys_raw(raw.as_bytes()).unwrap();
+  let mut ram = netlist
+    .generate_module("ram", &default_cell_library())
+    .unwrap();
+
+  ram.set_port_int("waddr_i", 2_u8).unwrap();
+  ram.set_port_int("wdata_i", 0x11_u8).unwrap();
+  ram.set_port_int("we_i", 1_u8).unwrap();
+  // Held writes are only counted once
+  for _ in 0..3 {
+    ram.eval();
+  }
+  ram.set_port_int("wdata_i", 0x12_u8).unwrap();
+  ram.eval();
+  // Out of range writes are dropped
+  ram.set_port_int("waddr_i", 12_u8).unwrap();
+  ram.eval();
+  ram.eval();
+
+  let contents: Vec<u8> = ram.get_memory_words("buffer").unwrap();
+  assert_eq!(contents[2], 0x12);
+  let stats = ram.get_memory_statistics("buffer").unwrap();
+  assert_eq!(stats.writes, vec![2]);
+}
@@ -0,0 +1,155 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use arbolta::bit::{Bit, BitVec};
+use arbolta::cell::default_cell_library;
+use arbolta::module::hardware_module::{HardwareModule, ModuleError};
+use arbolta::module::model::{ModelError, ModuleModel};
+use arbolta::synth::netlist::{Netlist, SynthError};
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+static MODEL_RAW: &str = include_str!("test_netlists/model_netlist.json");
+
+fn to_u8(bits: &BitVec) -> u8 {
+  bits
+    .bits
+    .iter()
+    .enumerate()
+    .fold(0, |acc, (i, bit)| acc | (bit.to_int::<u8>() << i))
+}
+
+fn from_u8(val: u8, width: usize) -> BitVec {
+  BitVec::from(
+    (0..width)
+      .map(|i| Bit::from((val >> i) & 1 == 1))
+      .collect::<Vec<Bit>>(),
+  )
+}
+
+/// Black-box adder, `Y = A + B`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct AddModel;
+
+#[typetag::serde]
+impl ModuleModel for AddModel {
+  fn eval(&mut self, inputs: &BTreeMap<String, BitVec>, outputs: &mut BTreeMap<String, BitVec>) {
+    let sum = to_u8(&inputs["A"]).wrapping_add(to_u8(&inputs["B"]));
+    outputs.insert("Y".to_string(), from_u8(sum, 4));
+  }
+}
+
+/// Replaces gate-level `inv4` with an incrementer, `Y = A + 1`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct IncrementModel;
+
+#[typetag::serde]
+impl ModuleModel for IncrementModel {
+  fn eval(&mut self, inputs: &BTreeMap<String, BitVec>, outputs: &mut BTreeMap<String, BitVec>) {
+    outputs.insert("Y".to_string(), from_u8(to_u8(&inputs["A"]) + 1, 4));
+  }
+}
+
+/// Black-box adder failing on overflow.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+struct CheckedAddModel {
+  error: Option<String>,
+}
+
+#[typetag::serde]
+impl ModuleModel for CheckedAddModel {
+  fn eval(&mut self, inputs: &BTreeMap<String, BitVec>, outputs: &mut BTreeMap<String, BitVec>) {
+    let sum = to_u8(&inputs["A"]) + to_u8(&inputs["B"]);
+    if sum < 16 {
+      outputs.insert("Y".to_string(), from_u8(sum, 4));
+    } else {
+      self.error.get_or_insert(format!("{sum} overflows"));
+    }
+  }
+
+  fn take_error(&mut self) -> Option<ModelError> {
+    self.error.take().map(|err| err.into())
+  }
+}
+
+fn top_module(replace_inv: bool) -> HardwareModule {
+  let mut cell_library = default_cell_library();
+  cell_library.register_model("blackbox_add", Box::new(AddModel));
+  if replace_inv {
+    cell_library.register_model("inv4", Box::new(IncrementModel));
+  }
+
+  let netlist = Netlist::from_yosys_raw(MODEL_RAW.as_bytes()).unwrap();
+  netlist.generate_module("top", &cell_library).unwrap()
+}
+
+#[rstest]
+#[case(false, 3, 4, 7, 0b1100)]
+#[case(false, 9, 9, 2, 0b0110)]
+#[case(true, 3, 4, 7, 4)]
+#[case(true, 15, 1, 0, 0)]
+fn test_model_eval(
+  #[case] replace_inv: bool,
+  #[case] a: u8,
+  #[case] b: u8,
+  #[case] sum: u8,
+  #[case] inc: u8,
+) {
+  let mut top = top_module(replace_inv);
+  top.set_port_int("a_i", a).unwrap();
+  top.set_port_int("b_i", b).unwrap();
+  top.eval();
+
+  assert_eq!(top.get_port_int::<u8>("sum_o").unwrap(), sum);
+  assert_eq!(top.get_port_int::<u8>("inc_o").unwrap(), inc);
+}
+
+#[test]
+fn test_model_missing() {
+  let netlist = Netlist::from_yosys_raw(MODEL_RAW.as_bytes()).unwrap();
+  let result = netlist.generate_module("top", &default_cell_library());
+
+  assert!(matches!(result, Err(SynthError::MissingModule(name)) if name == "blackbox_add"));
+}
+
+#[test]
+fn test_model_toggles() {
+  let mut top = top_module(true);
+  top.eval();
+  top.set_port_int("a_i", 1_u8).unwrap();
+  top.set_port_int("b_i", 2_u8).unwrap();
+  top.eval();
+
+  // Only the model's output port toggles are counted, Y: 0000 -> 0011
+  assert_eq!(
+    top
+      .search_module_total_toggle_count("blackbox_add")
+      .unwrap(),
+    2
+  );
+  // Not part of the cell breakdown
+  assert_eq!(top.get_cell_breakdown().get("blackbox_add"), None);
+}
+
+#[test]
+fn test_model_error() {
+  let mut cell_library = default_cell_library();
+  cell_library.register_model("blackbox_add", Box::new(CheckedAddModel::default()));
+  let netlist = Netlist::from_yosys_raw(MODEL_RAW.as_bytes()).unwrap();
+  let mut top = netlist.generate_module("top", &cell_library).unwrap();
+  let mut other = top.clone();
+
+  top.set_port_int("a_i", 9_u8).unwrap();
+  top.set_port_int("b_i", 9_u8).unwrap();
+  top.eval();
+  other.eval();
+
+  // Errors stay with the module that raised them and are only reported once
+  assert!(other.take_eval_error().is_ok());
+  assert!(matches!(
+    top.take_eval_error(),
+    Err(ModuleError::Model(name, err)) if name == "blackbox_add" && err.to_string() == "18 overflows"
+  ));
+  assert!(top.take_eval_error().is_ok());
+}
@@ -3,9 +3,9 @@
 
 use arbolta::bit::Bit;
 use arbolta::cell::{Cell, Function};
-use arbolta::module::hardware_module::{Component, HardwareModule};
+use arbolta::module::hardware_module::{Component, HardwareModule, ModuleError};
 use arbolta::module::port::{Port, PortDirection};
-use arbolta::signal::Signal;
+use arbolta::signal::{AccessSignal, Signal};
 use once_cell::sync::Lazy;
 use rstest::rstest;
 
@@ -159,3 +159,115 @@ fn test_module_1_input_cell_clocked(
   let actual: u8 = cell_module.get_port_int("c").unwrap();
   assert_eq!(actual, expected);
 }
+
+#[rstest]
+#[case(0, 0)]
+#[case(1, 1)]
+fn test_module_levelize(#[case] a: u8, #[case] expected: u8) {
+  let mut module = cell_module_from_function(Function::Inverter, 1);
+  // Second inverter drives the first, so component order is reversed
+  module.signals.push(Signal::new_net(2));
+  module.ports.get_mut("b").unwrap().signal_idx_list = vec![2];
+  if let Component::Cell(cell) = &mut module.components[0] {
+    cell.output_connection = 1;
+  }
+  module.components.insert(
+    0,
+    Component::Cell(Cell {
+      name: String::new(),
+      function: Function::Inverter,
+      state: [Bit::Zero; 2],
+      input_connections: [1, 0, 0, 0, 0, 0, 0, 0],
+      output_connection: 2,
+      num_inputs: 1,
+    }),
+  );
+  module.levelize();
+  assert_eq!(module.schedule.get(&0), Some(&vec![1]));
+  assert_eq!(module.schedule.get(&1), Some(&vec![0]));
+
+  module.set_port_int("a", a).unwrap();
+  module.eval();
+
+  let actual: u8 = module.get_port_int("b").unwrap();
+  assert_eq!(actual, expected);
+}
+
+/// Ring of inverters, inverter `i` drives net `n{i+1}` from net `n{i}`.
+fn inverter_ring(num_inverters: usize) -> HardwareModule {
+  let mut module = HardwareModule::default();
+  for i in 0..num_inverters {
+    let mut signal = Signal::new_net(i);
+    signal.set_name(format!("n{i}"));
+    module.signals.push(signal);
+    module.component_map.insert(format!("inv{i}"), i);
+    module.components.push(Component::Cell(Cell {
+      name: String::new(),
+      function: Function::Inverter,
+      state: [Bit::Zero; 2],
+      input_connections: [i, 0, 0, 0, 0, 0, 0, 0],
+      output_connection: (i + 1) % num_inverters,
+      num_inputs: 1,
+    }));
+  }
+  module
+}
+
+#[test]
+fn test_module_combinational_loop() {
+  let mut module = inverter_ring(3);
+  module.levelize();
+  assert_eq!(module.combinational_loops.len(), 1);
+  assert_eq!(
+    module.combinational_loops[0].cells,
+    vec!["inv0", "inv1", "inv2"]
+  );
+  assert_eq!(module.combinational_loops[0].nets, vec!["n0", "n1", "n2"]);
+
+  // Odd rings never settle
+  match module.eval_until_stable(10) {
+    Err(ModuleError::Oscillation(iterations, nets)) => {
+      assert_eq!(iterations, 10);
+      assert!(!nets.is_empty());
+    }
+    result => panic!("expected oscillation, got {result:?}"),
+  }
+}
+
+#[test]
+fn test_module_stable_loop() {
+  let mut module = inverter_ring(2);
+  module.levelize();
+  assert_eq!(module.combinational_loops.len(), 1);
+  assert_eq!(module.eval_until_stable(10).unwrap(), 2);
+}
+
+#[test]
+fn test_module_levelize_after_loop() {
+  let mut module = inverter_ring(2);
+  for (idx, name) in [(2, "y"), (3, "z")] {
+    let mut signal = Signal::new_net(idx);
+    signal.set_name(name.to_string());
+    module.signals.push(signal);
+  }
+  // Buffers `n0 -> y -> z` downstream of the ring, in reverse component order
+  for (name, input, output) in [("buf_z", 2, 3), ("buf_y", 0, 2)] {
+    let idx = module.components.len();
+    module.component_map.insert(name.to_string(), idx);
+    module.components.push(Component::Cell(Cell {
+      name: String::new(),
+      function: Function::Buf,
+      state: [Bit::Zero; 2],
+      input_connections: [input, 0, 0, 0, 0, 0, 0, 0],
+      output_connection: output,
+      num_inputs: 1,
+    }));
+  }
+  module.levelize();
+
+  // Only the ring shares a level, logic reading it is levelized after
+  assert_eq!(module.combinational_loops.len(), 1);
+  assert_eq!(module.schedule.get(&0), Some(&vec![0, 1]));
+  assert_eq!(module.schedule.get(&1), Some(&vec![3]));
+  assert_eq!(module.schedule.get(&2), Some(&vec![2]));
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::module::path::PathTarget;
+use common::nested_adder;
+use rstest::rstest;
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn test_path_peek_poke(#[case] flatten: bool) {
+  let mut design = nested_adder(flatten);
+  design
+    .module
+    .set_path_int("adder.op0_i", 0b0110_u8)
+    .unwrap();
+  design.module.set_path_int("op1_i", 0b0011_u8).unwrap();
+  design.eval();
+
+  assert_eq!(design.module.get_path_int::<u8>("adder.sum_o").unwrap(), 9);
+  assert_eq!(design.module.get_path_int::<u8>("sum_o[3]").unwrap(), 1);
+  // 0 + 1, 1 + 1, 1 + 0 + carry, 0 + 0 + carry
+  for (instance, sum, carry) in [("fa0", 1, 0), ("fa1", 0, 1), ("fa2", 0, 1), ("fa3", 1, 0)] {
+    assert_eq!(
+      design
+        .module
+        .get_path_int::<u8>(&format!("adder.{instance}.sum_o"))
+        .unwrap(),
+      sum
+    );
+    assert_eq!(
+      design
+        .module
+        .get_path_int::<u8>(&format!("{instance}.carry_o"))
+        .unwrap(),
+      carry
+    );
+  }
+
+  // Nets hold poked values until they're driven again
+  design.module.set_path_int("adder.fa1_carry", 0_u8).unwrap();
+  assert_eq!(design.module.get_path_int::<u8>("fa1_carry").unwrap(), 0);
+  design.eval();
+  assert_eq!(design.module.get_path_int::<u8>("fa1_carry").unwrap(), 1);
+
+  assert!(design.module.get_path_int::<u8>("adder.fa4.sum_o").is_err());
+  assert!(design.module.get_path_int::<u8>("adder.fa0").is_err());
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn test_path_statistics(#[case] flatten: bool) {
+  let mut design = nested_adder(flatten);
+  for a in 0..16_u8 {
+    design.module.set_port_int("op0_i", a).unwrap();
+    design.module.set_port_int("op1_i", 15 - a).unwrap();
+    design.eval();
+  }
+
+  assert_eq!(
+    design.get_module_breakdown("adder.fa2").unwrap(),
+    design.get_module_breakdown("full_adder").unwrap()
+  );
+  let area = design.get_module_area("adder").unwrap();
+  assert!((area - design.get_module_area("adder.fa3").unwrap() * 4.0).abs() < 1e-9);
+
+  // Type names resolve to the first instance
+  assert_eq!(
+    design.get_module_total_toggle_count("full_adder").unwrap(),
+    design.get_module_total_toggle_count("fa0").unwrap()
+  );
+  assert_ne!(
+    design.get_module_total_toggle_count("adder.fa0").unwrap(),
+    design.get_module_total_toggle_count("adder.fa3").unwrap()
+  );
+  assert!(design.get_module_total_toggle_count("adder.fa4").is_err());
+}
+
+#[test]
+fn test_path_resolve() {
+  let design = nested_adder(false);
+  let (instances, target) = design.module.resolve_path("adder.fa2.op0_i").unwrap();
+  assert_eq!(instances, vec![design.module.component_map["fa2"]]);
+  assert_eq!(target, PathTarget::Port("op0_i".to_string()));
+
+  let (instances, target) = design.module.resolve_path("adder").unwrap();
+  assert!(instances.is_empty());
+  assert_eq!(target, PathTarget::Instance);
+}
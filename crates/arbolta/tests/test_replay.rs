@@ -0,0 +1,149 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::module::design::DesignError;
+use arbolta::module::replay::{ReplayConfig, ReplayError};
+use arbolta::module::vcd::VcdConfig;
+use common::ram;
+use std::collections::HashMap;
+use std::io::Cursor;
+
+/// Testbench driving `ram` as `u_dut`, with a separate write enable and one variable per
+/// address bit.
+static TESTBENCH_VCD: &str = "$timescale 1ns $end
+$scope module tb $end
+$var wire 1 ! clk $end
+$var wire 1 \" write_enable $end
+$scope module u_dut $end
+$var wire 1 ! clk_i $end
+$var wire 1 # waddr_i [0] $end
+$var wire 1 $ waddr_i [1] $end
+$var wire 1 % waddr_i [2] $end
+$var wire 1 & waddr_i [3] $end
+$var wire 8 ' wdata_i [7:0] $end
+$var wire 4 ( raddr_i [3:0] $end
+$upscope $end
+$upscope $end
+$enddefinitions $end
+#0
+$dumpvars
+0!
+1\"
+1#
+0$
+0%
+0&
+b10101010 '
+bx (
+$end
+#5
+1!
+#10
+0!
+0#
+1$
+b10111011 '
+#15
+1!
+#20
+0!
+0\"
+bx '
+#25
+1!
+#30
+0!
+#35
+1!
+";
+
+fn testbench_config() -> ReplayConfig {
+  ReplayConfig {
+    scope: "tb.u_dut".to_string(),
+    mapping: HashMap::from([("we_i".to_string(), "tb.write_enable".to_string())]),
+    ..Default::default()
+  }
+}
+
+#[test]
+fn test_replay_testbench() {
+  let mut design = ram();
+  let cycles = design
+    .replay_vcd(Cursor::new(TESTBENCH_VCD), &testbench_config())
+    .unwrap();
+  assert_eq!(cycles, 4);
+  assert_eq!(design.cycles, 4);
+  let words: Vec<u8> = design.module.get_memory_words("buffer").unwrap();
+  // Word 3 is initialized to 0x42
+  assert_eq!(&words[..5], &[0x00, 0xaa, 0xbb, 0x42, 0x00]);
+
+  // Stops early, and can be clocked by another variable
+  let mut design = ram();
+  let config = ReplayConfig {
+    clock: Some("tb.clk".to_string()),
+    cycles: Some(1),
+    ..testbench_config()
+  };
+  let cycles = design
+    .replay_vcd(Cursor::new(TESTBENCH_VCD), &config)
+    .unwrap();
+  assert_eq!(cycles, 1);
+  let words: Vec<u8> = design.module.get_memory_words("buffer").unwrap();
+  assert_eq!(&words[..3], &[0x00, 0xaa, 0x00]);
+}
+
+#[test]
+fn test_replay_round_trip() {
+  let mut design = ram();
+  design
+    .start_vcd(VcdConfig {
+      glitches: true,
+      ..Default::default()
+    })
+    .unwrap();
+  design.module.set_port_int("we_i", 1_u8).unwrap();
+  for addr in 0..8_u8 {
+    design.module.set_port_int("waddr_i", addr).unwrap();
+    design.module.set_port_int("wdata_i", addr * 3).unwrap();
+    design.module.set_port_int("raddr_i", addr / 2).unwrap();
+    design.eval_clocked().unwrap();
+  }
+  let path = std::env::temp_dir().join("arbolta_test_replay.vcd");
+  let path = path.to_str().unwrap();
+  design.finish_vcd().unwrap().save(path).unwrap();
+
+  let mut replayed = ram();
+  let config = ReplayConfig {
+    scope: "ram".to_string(),
+    ..Default::default()
+  };
+  assert_eq!(replayed.replay(path, &config).unwrap(), 8);
+  assert_eq!(
+    replayed.module.get_memory_words::<u8>("buffer").unwrap(),
+    design.module.get_memory_words::<u8>("buffer").unwrap()
+  );
+  assert_eq!(
+    replayed.module.get_port_int::<u8>("rdata_q_o").unwrap(),
+    design.module.get_port_int::<u8>("rdata_q_o").unwrap()
+  );
+}
+
+#[test]
+fn test_replay_errors() {
+  let config = ReplayConfig {
+    scope: "tb".to_string(),
+    ..Default::default()
+  };
+  assert!(matches!(
+    ram().replay_vcd(Cursor::new(TESTBENCH_VCD), &config),
+    Err(DesignError::ReplayError(ReplayError::MissingVar(name))) if name == "tb.clk_i"
+  ));
+
+  let truncated = &TESTBENCH_VCD[..TESTBENCH_VCD.find("[1]").unwrap()];
+  assert!(matches!(
+    ram().replay_vcd(Cursor::new(truncated), &testbench_config()),
+    Err(DesignError::ReplayError(ReplayError::Parse(8, _)))
+  ));
+}
@@ -0,0 +1,97 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::module::report::Aggregation;
+use common::nested_adder;
+use rstest::rstest;
+use std::collections::BTreeMap;
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn test_report_nested_adder(#[case] flatten: bool) {
+  let mut design = nested_adder(flatten);
+  for a in 0..16_u8 {
+    design.module.set_port_int("op0_i", a).unwrap();
+    design.module.set_port_int("op1_i", a ^ 0b1010).unwrap();
+    design.eval();
+  }
+
+  let report = design.get_hierarchy_report();
+  let paths: Vec<&str> = report
+    .instances
+    .iter()
+    .map(|instance| instance.path.as_str())
+    .collect();
+  assert_eq!(
+    paths,
+    vec!["adder", "adder.fa0", "adder.fa1", "adder.fa2", "adder.fa3"]
+  );
+
+  let top = &report.instances[0];
+  assert_eq!(top.module, "adder");
+  assert_eq!(top.depth, 0);
+  assert_eq!(top.area, design.get_module_area("adder").unwrap());
+  assert_eq!(top.area_percent, 100.0);
+  // NAND2 has area 4 in the default library
+  assert_eq!(top.nand2_equivalents, Some(top.area / 4.0));
+  assert_eq!(top.flops, 0);
+  assert_eq!(
+    top.toggles,
+    design.get_module_total_toggle_count("adder").unwrap()
+  );
+
+  for instance in &report.instances[1..] {
+    assert_eq!(instance.module, "full_adder");
+    assert_eq!(instance.parent, Some(0));
+    assert_eq!(instance.area_percent, 25.0);
+    assert_eq!(
+      instance.cells,
+      design
+        .get_module_breakdown(&instance.path)
+        .unwrap()
+        .into_iter()
+        .collect::<BTreeMap<_, _>>()
+    );
+    assert_eq!(
+      instance.toggles,
+      design
+        .get_module_total_toggle_count(&instance.path)
+        .unwrap()
+    );
+    assert_eq!(instance.toggles, instance.local_toggles);
+  }
+
+  let full_adder = report.get_module("full_adder").unwrap();
+  assert_eq!(full_adder.instances, 4);
+  assert_eq!(full_adder.area, top.area);
+  assert_eq!(
+    full_adder.toggles,
+    report.instances[1..]
+      .iter()
+      .map(|instance| instance.toggles)
+      .sum::<usize>()
+  );
+}
+
+#[test]
+fn test_report_export() {
+  let report = nested_adder(false).get_hierarchy_report();
+
+  let csv = report.to_csv(Aggregation::Instance);
+  let lines: Vec<&str> = csv.lines().collect();
+  assert_eq!(lines.len(), 6);
+  assert!(lines[0].starts_with("path,module,depth,area"));
+  assert!(lines[2].starts_with("adder.fa0,full_adder,1,"));
+
+  let csv = report.to_csv(Aggregation::Module);
+  let lines: Vec<&str> = csv.lines().collect();
+  assert_eq!(lines.len(), 3);
+  assert!(lines[2].starts_with("full_adder,4,"));
+
+  let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+  assert_eq!(json["instances"][1]["path"], "adder.fa0");
+  assert_eq!(json["modules"][1]["instances"], 4);
+}
@@ -0,0 +1,155 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::bit::Bit;
+use arbolta::cell::{default_cell_library, Function};
+use arbolta::module::design::{Design, DesignError, ResetConfig};
+use arbolta::module::hardware_module::{HardwareModule, ModuleError};
+use arbolta::module::lanes::LaneDesign;
+use common::{add_cell, add_net, get, ram};
+
+/// `q` is cleared by active-low `rst_ni`, `q2` by active-high `rst_i`, both synchronously
+fn two_resets() -> Design {
+  let mut module = HardwareModule {
+    name: "resets".to_string(),
+    ..Default::default()
+  };
+  let clk = add_net(&mut module, "clk_i");
+  let rst_n = add_net(&mut module, "rst_ni");
+  let rst = add_net(&mut module, "rst_i");
+  let d = add_net(&mut module, "d_i");
+  let (masked, q) = (add_net(&mut module, "masked"), add_net(&mut module, "q"));
+  add_cell(&mut module, Function::And, &[d, rst_n], masked);
+  add_cell(&mut module, Function::DffPosEdge, &[clk, masked], q);
+  let (not_rst, masked2) = (
+    add_net(&mut module, "not_rst"),
+    add_net(&mut module, "masked2"),
+  );
+  let q2 = add_net(&mut module, "q2");
+  add_cell(&mut module, Function::Inverter, &[rst], not_rst);
+  add_cell(&mut module, Function::And, &[d, not_rst], masked2);
+  add_cell(&mut module, Function::DffPosEdge, &[clk, masked2], q2);
+  module.levelize();
+
+  let mut design = Design::from_module(module, default_cell_library());
+  design.set_clock("clk_i").unwrap();
+  design.set_reset("rst_i").unwrap();
+  design.add_reset("rst_ni", true).unwrap();
+  design
+}
+
+/// Clock ones into both flops, out of reset.
+fn load_ones(design: &mut Design) {
+  for (name, value) in [
+    ("rst_ni", Bit::One),
+    ("rst_i", Bit::Zero),
+    ("d_i", Bit::One),
+  ] {
+    let idx = design.module.get_signal_idx(name).unwrap();
+    design.module.set_signal(idx, value).unwrap();
+  }
+  design.eval_clocked().unwrap();
+  assert_eq!(get(design, "q"), Bit::One);
+  assert_eq!(get(design, "q2"), Bit::One);
+}
+
+#[test]
+fn test_reset_polarity_and_cycles() {
+  let mut design = two_resets();
+  design.reset_config.cycles = 3;
+  load_ones(&mut design);
+
+  design.reset_clocked().unwrap();
+  assert_eq!(get(&mut design, "q"), Bit::Zero);
+  assert_eq!(get(&mut design, "q2"), Bit::Zero);
+  // Released after the sequence
+  assert_eq!(get(&mut design, "rst_ni"), Bit::One);
+  assert_eq!(get(&mut design, "rst_i"), Bit::Zero);
+  assert_eq!(design.cycles, 4);
+  assert_eq!(design.get_resets().len(), 2);
+}
+
+#[test]
+fn test_reset_unclocked() {
+  let mut design = two_resets();
+  design.reset_config.clocked = false;
+  load_ones(&mut design);
+
+  // Synchronous resets need a clock edge, so the flops keep their values
+  design.reset_clocked().unwrap();
+  assert_eq!(get(&mut design, "q"), Bit::One);
+  assert_eq!(get(&mut design, "q2"), Bit::One);
+  assert_eq!(design.cycles, 1);
+}
+
+#[test]
+fn test_reset_exclude_statistics() {
+  let mut design = two_resets();
+  design.reset_config = ResetConfig {
+    cycles: 2,
+    exclude_statistics: true,
+    ..design.reset_config.clone()
+  };
+  design.start_vcd(Default::default()).unwrap();
+  design.start_saif(1);
+  load_ones(&mut design);
+  let toggles = design.module.get_total_toggle_count();
+  let vcd = design.vcd.as_ref().unwrap().as_str().to_string();
+  let q = design.module.get_signal_idx("q").unwrap();
+  let probability = design.saif.as_ref().unwrap().probability(&[], q);
+
+  design.reset_clocked().unwrap();
+  assert_eq!(get(&mut design, "q"), Bit::Zero);
+  assert_eq!(design.module.get_total_toggle_count(), toggles);
+  assert_eq!(design.cycles, 1);
+  // Recording paused during the sequence
+  assert_eq!(design.vcd.as_ref().unwrap().as_str(), vcd);
+  assert_eq!(
+    design.saif.as_ref().unwrap().probability(&[], q),
+    probability
+  );
+
+  // Memory accesses made during the sequence aren't counted either
+  let mut design = ram();
+  design.set_reset("we_i").unwrap();
+  design.reset_config.exclude_statistics = true;
+  design.reset_clocked().unwrap();
+  let statistics = design.module.get_memory_statistics("buffer").unwrap();
+  assert_eq!(statistics.writes, vec![0]);
+  assert_eq!(
+    design.module.get_memory_words::<u8>("buffer").unwrap()[0],
+    0
+  );
+}
+
+#[test]
+fn test_reset_lanes() {
+  let mut design = two_resets();
+  design.reset_config.cycles = 2;
+  let mut lanes = LaneDesign::new(&design).unwrap();
+  let d = design.module.get_signal_idx("d_i").unwrap();
+  let q = design.module.get_signal_idx("q").unwrap();
+  let rst_n = design.module.get_signal_idx("rst_ni").unwrap();
+
+  lanes.set_signal_lanes(rst_n, !0).unwrap();
+  lanes.set_signal_lanes(d, 0b1010).unwrap();
+  lanes.eval_clocked().unwrap();
+  assert_eq!(lanes.get_signal_lanes(q).unwrap(), 0b1010);
+
+  lanes.reset_clocked().unwrap();
+  assert_eq!(lanes.get_signal_lanes(q).unwrap(), 0);
+  assert_eq!(lanes.get_signal_lanes(rst_n).unwrap(), !0);
+}
+
+#[test]
+fn test_reset_missing() {
+  let mut design = two_resets();
+  design.reset = None;
+  design.reset_config.resets.clear();
+  assert!(matches!(
+    design.reset_clocked(),
+    Err(DesignError::ModuleError(ModuleError::MissingSignal(_)))
+  ));
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use common::{design, MEMORY_RAW, NESTED_ADDER_RAW};
+
+/// Lines describing net `name`.
+fn net<'a>(saif: &'a str, name: &str) -> Vec<&'a str> {
+  let lines: Vec<&str> = saif.lines().map(str::trim).collect();
+  let start = lines
+    .iter()
+    .position(|line| *line == format!("({name}"))
+    .unwrap();
+  lines[start + 1..start + 3].to_vec()
+}
+
+#[test]
+fn test_saif_activity() {
+  let mut design = design(MEMORY_RAW, "ram", false);
+  design.set_clock("clk_i").unwrap();
+  design.module.set_port_int("wdata_i", 0xff_u8).unwrap();
+  design.eval_clocked().unwrap();
+
+  design.start_saif(10);
+  for addr in 0..4_u8 {
+    design.module.set_port_int("we_i", 1_u8).unwrap();
+    design.module.set_port_int("waddr_i", addr).unwrap();
+    design.eval_clocked().unwrap();
+    design.module.set_port_int("we_i", 0_u8).unwrap();
+  }
+  let saif = design.finish_saif().unwrap().to_string();
+  assert!(design.finish_saif().is_none());
+
+  assert!(saif.contains("(DESIGN \"ram\")"));
+  assert!(saif.contains("(DURATION 40)"));
+  assert!(saif.contains("(INSTANCE ram\n"));
+  // High at the end of every cycle, raised and lowered around each one
+  assert_eq!(
+    net(&saif, "we_i"),
+    vec!["(T0 0) (T1 40) (TX 0)", "(TC 8) (IG 0)"]
+  );
+  // Toggles before recording started aren't counted
+  assert_eq!(
+    net(&saif, "wdata_i\\[0\\]"),
+    vec!["(T0 0) (T1 40) (TX 0)", "(TC 0) (IG 0)"]
+  );
+  // 0, 1, 0, 1 and 3 toggles
+  assert_eq!(
+    net(&saif, "waddr_i\\[0\\]"),
+    vec!["(T0 20) (T1 20) (TX 0)", "(TC 3) (IG 0)"]
+  );
+  // High for the first half of every cycle
+  assert_eq!(
+    net(&saif, "clk_i"),
+    vec!["(T0 20) (T1 20) (TX 0)", "(TC 8) (IG 0)"]
+  );
+}
+
+#[test]
+fn test_saif_step() {
+  let mut design = design(MEMORY_RAW, "ram", false);
+  design.add_clock("clk_i", 10, 0).unwrap();
+  design.start_saif(1);
+  // Rises at 0, 10 and 20 and falls at 5 and 15, then held high until 23
+  design.advance_to(23).unwrap();
+  let saif = design.finish_saif().unwrap().to_string();
+  assert!(saif.contains("(DURATION 23)"));
+  assert_eq!(
+    net(&saif, "clk_i"),
+    vec!["(T0 10) (T1 13) (TX 0)", "(TC 5) (IG 0)"]
+  );
+}
+
+#[test]
+fn test_saif_hierarchy() {
+  let mut hierarchical = design(NESTED_ADDER_RAW, "adder", false);
+  hierarchical.start_saif(1);
+  let saif = hierarchical.finish_saif().unwrap().to_string();
+  assert!(saif.contains("\n  (INSTANCE fa0\n"));
+  assert!(saif.contains("(\\$153\\$carry_o\n"));
+  assert_eq!(saif.matches("(INSTANCE").count(), 5);
+
+  // Flattened modules keep their hierarchy
+  let mut flat = design(NESTED_ADDER_RAW, "adder", true);
+  flat.start_saif(1);
+  assert_eq!(flat.finish_saif().unwrap().to_string(), saif);
+}
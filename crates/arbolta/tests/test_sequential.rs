@@ -0,0 +1,148 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::bit::Bit;
+use arbolta::cell::{default_cell_library, Function};
+use arbolta::module::design::Design;
+use arbolta::module::hardware_module::{Component, HardwareModule, ModuleError, MAX_DELTA_CYCLES};
+use arbolta::module::lanes::LaneDesign;
+use common::{add_cell, add_net, get};
+
+fn design(mut module: HardwareModule) -> Design {
+  module.levelize();
+  let mut design = Design::from_module(module, default_cell_library());
+  design.set_clock("clk").unwrap();
+  design
+}
+
+/// `clk`, `d` -> `q0` -> `q1` -> `q2`
+fn shift_register() -> HardwareModule {
+  let mut module = HardwareModule::default();
+  let clk = add_net(&mut module, "clk");
+  let mut data = add_net(&mut module, "d");
+  for i in 0..3 {
+    let q = add_net(&mut module, &format!("q{i}"));
+    add_cell(&mut module, Function::DffPosEdge, &[clk, data], q);
+    data = q;
+  }
+  module
+}
+
+#[test]
+fn test_sequential_shift_register() {
+  let mut design = design(shift_register());
+  let d = design.module.get_signal_idx("d").unwrap();
+  design.module.set_signal(d, Bit::One).unwrap();
+
+  // Data moves one stage per edge
+  for cycle in 0..3 {
+    design.eval_clocked().unwrap();
+    design.module.set_signal(d, Bit::Zero).unwrap();
+    let stages: Vec<Bit> = (0..3).map(|i| get(&mut design, &format!("q{i}"))).collect();
+    let expected: Vec<Bit> = (0..3).map(|i| Bit::from(i == cycle)).collect();
+    assert_eq!(stages, expected);
+  }
+}
+
+#[test]
+fn test_sequential_nested_shift_register() {
+  // One flip-flop per submodule, chained at the top
+  let mut stage = HardwareModule::default();
+  let (clk, d, q) = (
+    add_net(&mut stage, "clk"),
+    add_net(&mut stage, "d"),
+    add_net(&mut stage, "q"),
+  );
+  add_cell(&mut stage, Function::DffPosEdge, &[clk, d], q);
+
+  let mut module = HardwareModule::default();
+  let top_clk = add_net(&mut module, "clk");
+  let mut data = add_net(&mut module, "d");
+  for i in 0..3 {
+    let top_q = add_net(&mut module, &format!("q{i}"));
+    let mut instance = stage.clone();
+    instance.name = format!("stage{i}");
+    instance.input_connections = vec![(top_clk, clk), (data, d)];
+    instance.output_connections = vec![(top_q, q)];
+    let idx = module.components.len();
+    module.component_map.insert(instance.name.clone(), idx);
+    module
+      .components
+      .push(Component::Module(Box::new(instance)));
+    data = top_q;
+  }
+
+  let mut design = design(module);
+  let d = design.module.get_signal_idx("d").unwrap();
+  design.module.set_signal(d, Bit::One).unwrap();
+  design.eval_clocked().unwrap();
+  design.module.set_signal(d, Bit::Zero).unwrap();
+
+  let stages: Vec<Bit> = (0..3).map(|i| get(&mut design, &format!("q{i}"))).collect();
+  assert_eq!(stages, vec![Bit::One, Bit::Zero, Bit::Zero]);
+}
+
+#[test]
+fn test_sequential_derived_clock() {
+  // `div` toggles on every `clk` edge, `q` samples `d` on rising edges of `div`
+  let mut module = HardwareModule::default();
+  let clk = add_net(&mut module, "clk");
+  let d = add_net(&mut module, "d");
+  let div = add_net(&mut module, "div");
+  let div_n = add_net(&mut module, "div_n");
+  let q = add_net(&mut module, "q");
+  add_cell(&mut module, Function::DffPosEdge, &[clk, div_n], div);
+  add_cell(&mut module, Function::Inverter, &[div], div_n);
+  add_cell(&mut module, Function::DffPosEdge, &[div, d], q);
+
+  let mut design = design(module);
+  design.module.set_signal(d, Bit::One).unwrap();
+  design.eval_clocked().unwrap();
+  assert_eq!(get(&mut design, "div"), Bit::One);
+  assert_eq!(get(&mut design, "q"), Bit::One);
+
+  // Falling edge of `div`, `q` holds
+  design.module.set_signal(d, Bit::Zero).unwrap();
+  design.eval_clocked().unwrap();
+  assert_eq!(get(&mut design, "div"), Bit::Zero);
+  assert_eq!(get(&mut design, "q"), Bit::One);
+
+  design.eval_clocked().unwrap();
+  assert_eq!(get(&mut design, "q"), Bit::Zero);
+}
+
+#[test]
+fn test_sequential_unsettled() {
+  // Johnson counter clocked by its own state, `qa` and `qb` keep stepping without settling
+  let mut module = HardwareModule::default();
+  let qa = add_net(&mut module, "qa");
+  let qb = add_net(&mut module, "qb");
+  let qa_n = add_net(&mut module, "qa_n");
+  let clk_a = add_net(&mut module, "clk_a");
+  let clk_b = add_net(&mut module, "clk_b");
+  add_cell(&mut module, Function::Inverter, &[qa], qa_n);
+  add_cell(&mut module, Function::Xnor, &[qa_n, qb], clk_a);
+  add_cell(&mut module, Function::Xnor, &[qa, qb], clk_b);
+  add_cell(&mut module, Function::DffPosEdge, &[clk_a, qb], qa);
+  add_cell(&mut module, Function::DffPosEdge, &[clk_b, qa_n], qb);
+  module.levelize();
+  let mut levelized = Design::from_module(module, default_cell_library());
+  let mut compiled = levelized.clone();
+  compiled.compile().unwrap();
+  let mut lanes = LaneDesign::new(&levelized).unwrap();
+
+  let unsettled = |result| match result {
+    Err(ModuleError::Oscillation(MAX_DELTA_CYCLES, nets)) => nets.contains(&"qb".to_string()),
+    _ => false,
+  };
+  for design in [&mut levelized, &mut compiled] {
+    design.eval();
+    assert!(unsettled(design.module.take_eval_error()));
+    // Only reported once
+    assert!(design.module.take_eval_error().is_ok());
+  }
+  lanes.eval();
+  assert!(unsettled(lanes.design.module.take_eval_error()));
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::bit::Bit;
+use arbolta::cell::{default_cell_library, Cell, Function};
+use arbolta::module::design::{Design, DesignError};
+use arbolta::module::event::Scheduler;
+use arbolta::module::hardware_module::{Component, HardwareModule, ModuleError};
+use arbolta::signal::{AccessSignal, Signal};
+use arbolta::synth::netlist::Netlist;
+use common::nested_adder;
+
+static WORD_ALU_RAW: &str = include_str!("test_netlists/word_alu_netlist.json");
+
+#[test]
+fn test_tape_nested_adder() {
+  let mut levelized = nested_adder(false);
+  let mut compiled = nested_adder(false);
+  compiled.compile().unwrap();
+  assert_eq!(compiled.scheduler, Scheduler::Compiled);
+
+  for a in 0..16_u8 {
+    for b in 0..16_u8 {
+      for design in [&mut levelized, &mut compiled] {
+        design.module.set_port_int("op0_i", a).unwrap();
+        design.module.set_port_int("op1_i", b).unwrap();
+        design.eval();
+        assert_eq!(design.module.get_port_int::<u8>("sum_o").unwrap(), a + b);
+      }
+    }
+  }
+
+  for name in ["adder", "full_adder", "adder.fa2"] {
+    assert_eq!(
+      compiled.get_module_total_toggle_count(name).unwrap(),
+      levelized.get_module_total_toggle_count(name).unwrap()
+    );
+  }
+  assert_eq!(
+    compiled.eval_until_stable(4).unwrap(),
+    levelized.eval_until_stable(4).unwrap()
+  );
+}
+
+#[test]
+fn test_tape_shift_register() {
+  let mut module = HardwareModule {
+    name: "shift".to_string(),
+    ..Default::default()
+  };
+  let mut add_net = |name: &str| {
+    let idx = module.signals.len();
+    let mut signal = Signal::new_net(idx);
+    signal.set_name(name.to_string());
+    module.signals.push(signal);
+    module.signal_map.insert(name.to_string(), idx);
+    idx
+  };
+  let clk = add_net("clk");
+  let d = add_net("d");
+  let q: Vec<usize> = (0..3).map(|i| add_net(&format!("q{i}"))).collect();
+  for (data, out) in [d, q[0], q[1]].into_iter().zip(&q) {
+    let mut input_connections = [0; 8];
+    input_connections[..2].copy_from_slice(&[clk, data]);
+    module.components.push(Component::Cell(Cell {
+      name: String::new(),
+      function: Function::DffPosEdge,
+      state: [Bit::Zero; 2],
+      input_connections,
+      output_connection: *out,
+      num_inputs: 2,
+    }));
+  }
+  module.levelize();
+  let mut levelized = Design::from_module(module, default_cell_library());
+  levelized.set_clock("clk").unwrap();
+  let mut compiled = levelized.clone();
+  compiled.compile().unwrap();
+
+  for data in [1, 0, 1, 1, 0, 0, 0] {
+    for design in [&mut levelized, &mut compiled] {
+      design.module.set_signal(d, Bit::from(data == 1)).unwrap();
+      design.eval_clocked().unwrap();
+    }
+    assert_eq!(
+      q.iter()
+        .map(|idx| compiled.module.signals[*idx].get_value())
+        .collect::<Vec<_>>(),
+      q.iter()
+        .map(|idx| levelized.module.signals[*idx].get_value())
+        .collect::<Vec<_>>()
+    );
+  }
+  assert_eq!(
+    compiled.get_module_total_toggle_count("shift").unwrap(),
+    levelized.get_module_total_toggle_count("shift").unwrap()
+  );
+
+  // Flip-flop state lives on the module, so resetting it resets the tape
+  compiled.module.reset();
+  compiled.module.set_signal(d, Bit::One).unwrap();
+  compiled.eval_clocked().unwrap();
+  assert_eq!(compiled.module.signals[q[0]].get_value(), Bit::One);
+  assert_eq!(compiled.module.signals[q[1]].get_value(), Bit::Zero);
+}
+
+#[test]
+fn test_tape_unsupported() {
+  let netlist = Netlist::from_yosys_raw(WORD_ALU_RAW.as_bytes()).unwrap();
+  let cell_library = default_cell_library();
+  let module = netlist.generate_module("alu", &cell_library).unwrap();
+  let mut design = Design::from_module(module, cell_library);
+
+  assert!(matches!(
+    design.compile(),
+    Err(DesignError::ModuleError(ModuleError::UnsupportedTape(_)))
+  ));
+  assert_eq!(design.scheduler, Scheduler::Levelized);
+}
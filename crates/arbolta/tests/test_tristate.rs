@@ -0,0 +1,97 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use arbolta::cell::default_cell_library;
+use arbolta::module::hardware_module::HardwareModule;
+use arbolta::module::port::PortDirection;
+use arbolta::synth::netlist::Netlist;
+use rstest::rstest;
+
+static TRISTATE_RAW: &str = include_str!("test_netlists/tristate_netlist.json");
+
+fn bus() -> HardwareModule {
+  let netlist = Netlist::from_yosys_raw(TRISTATE_RAW.as_bytes()).unwrap();
+  netlist
+    .generate_module("bus", &default_cell_library())
+    .unwrap()
+}
+
+// Readers can be ordered before the drivers they sample
+fn settle(module: &mut HardwareModule) {
+  module.eval();
+  module.eval();
+}
+
+#[test]
+fn test_tristate_high_z() {
+  let mut bus = bus();
+  assert_eq!(
+    bus.get_port_direction("pad_io").unwrap(),
+    PortDirection::InOut
+  );
+
+  settle(&mut bus);
+  assert_eq!(bus.get_port_high_z("pad_io").unwrap(), vec![true, true]);
+  assert_eq!(bus.get_port_high_z("bus_o").unwrap(), vec![false, false]);
+}
+
+#[rstest]
+// (en_a, a, en_b, b, oe, d) -> pad
+#[case(1, 0b10, 0, 0b00, 0, 0b00, 0b10)]
+#[case(0, 0b10, 1, 0b01, 0, 0b00, 0b01)]
+#[case(0, 0b00, 1, 0b10, 0, 0b00, 0b10)]
+#[case(0, 0b00, 0, 0b00, 1, 0b11, 0b11)]
+fn test_tristate_drivers(
+  #[case] en_a: u8,
+  #[case] a: u8,
+  #[case] en_b: u8,
+  #[case] b: u8,
+  #[case] oe: u8,
+  #[case] d: u8,
+  #[case] expected: u8,
+) {
+  let mut bus = bus();
+  bus.set_port_int("en_a_i", en_a).unwrap();
+  bus.set_port_int("a_i", a).unwrap();
+  bus.set_port_int("en_b_i", en_b).unwrap();
+  bus.set_port_int("b_i", b).unwrap();
+  bus.set_port_int("oe_i", oe).unwrap();
+  bus.set_port_int("d_i", d).unwrap();
+  settle(&mut bus);
+
+  assert_eq!(bus.get_port_int::<u8>("pad_io").unwrap(), expected);
+  assert_eq!(bus.get_port_int::<u8>("bus_o").unwrap(), expected);
+  // Submodule samples the shared bus
+  assert_eq!(bus.get_port_int::<u8>("core_o").unwrap(), expected);
+  assert_eq!(bus.get_port_high_z("pad_io").unwrap(), vec![false, false]);
+  assert_eq!(bus.get_contention_count(), 0);
+}
+
+#[test]
+fn test_tristate_inout_port() {
+  let mut bus = bus();
+
+  // Drive pad from outside, sampled inside the design
+  bus.set_port_int("pad_io", 0b01_u8).unwrap();
+  settle(&mut bus);
+  assert_eq!(bus.get_port_int::<u8>("bus_o").unwrap(), 0b01);
+  assert_eq!(bus.get_port_int::<u8>("core_o").unwrap(), 0b01);
+
+  // Design drives the same value, no contention
+  bus.set_port_int("en_a_i", 1_u8).unwrap();
+  bus.set_port_int("a_i", 0b01_u8).unwrap();
+  settle(&mut bus);
+  assert_eq!(bus.get_contention_count(), 0);
+
+  // Design drives a different value
+  bus.set_port_int("a_i", 0b11_u8).unwrap();
+  settle(&mut bus);
+  assert!(bus.get_contention_count() > 0);
+
+  // Released, design drives pad
+  bus.release_port("pad_io").unwrap();
+  settle(&mut bus);
+  assert_eq!(bus.get_port_int::<u8>("pad_io").unwrap(), 0b11);
+
+  assert!(bus.release_port("bus_o").is_err());
+}
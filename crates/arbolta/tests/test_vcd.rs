@@ -0,0 +1,156 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::module::design::{Design, DesignError};
+use arbolta::module::hardware_module::ModuleError;
+use arbolta::module::vcd::VcdConfig;
+use common::{nested_adder, ram};
+use rstest::rstest;
+
+/// Header lines without identifier codes, which depend on declaration order.
+fn header(design: &mut Design, config: VcdConfig) -> Vec<String> {
+  design.start_vcd(config).unwrap();
+  let vcd = design.finish_vcd().unwrap();
+  vcd
+    .as_str()
+    .lines()
+    .filter(|line| {
+      line.starts_with("$scope") || line.starts_with("$var") || line.starts_with("$up")
+    })
+    .map(|line| {
+      let mut words: Vec<&str> = line.split(' ').collect();
+      if words[0] == "$var" {
+        words.remove(3);
+      }
+      words.join(" ")
+    })
+    .collect()
+}
+
+#[test]
+fn test_vcd_scopes() {
+  let hierarchical = header(&mut nested_adder(false), VcdConfig::default());
+  assert_eq!(hierarchical[0], "$scope module adder $end");
+  assert!(hierarchical.contains(&"$var wire 5 sum_o [4:0] $end".to_string()));
+  assert!(hierarchical.contains(&"$var wire 1 fa0_carry $end".to_string()));
+  assert_eq!(
+    hierarchical
+      .iter()
+      .filter(|line| line.starts_with("$scope"))
+      .count(),
+    5
+  );
+
+  // Flattened modules are dumped with the same hierarchy
+  assert_eq!(
+    header(&mut nested_adder(true), VcdConfig::default()),
+    hierarchical
+  );
+
+  for flatten in [false, true] {
+    let config = VcdConfig {
+      scopes: vec!["adder.fa2".to_string()],
+      signals: vec!["carry_*".to_string()],
+      ..Default::default()
+    };
+    assert_eq!(
+      header(&mut nested_adder(flatten), config),
+      vec![
+        "$scope module adder $end",
+        "$scope module fa2 $end",
+        "$var wire 1 carry_i $end",
+        "$var wire 1 carry_o $end",
+        "$upscope $end",
+        "$upscope $end",
+      ]
+    );
+  }
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn test_vcd_values(#[case] glitches: bool) {
+  let mut design = ram();
+  let config = VcdConfig {
+    signals: vec!["wdata_i".to_string(), "clk_i".to_string()],
+    start: 1,
+    end: Some(3),
+    glitches,
+    ..Default::default()
+  };
+  design.start_vcd(config).unwrap();
+  for data in [0x11_u8, 0x22, 0x33, 0x44] {
+    design.module.set_port_int("wdata_i", data).unwrap();
+    design.eval_clocked().unwrap();
+  }
+  let vcd = design.finish_vcd().unwrap();
+  let dump = vcd.as_str().split("$enddefinitions $end\n").nth(1).unwrap();
+
+  // Aliased clock nets share an identifier
+  let codes: Vec<&str> = vcd
+    .as_str()
+    .lines()
+    .filter(|line| line.starts_with("$var"))
+    .map(|line| line.split(' ').nth(3).unwrap())
+    .collect();
+  let (clock, data) = (codes[0], codes[1]);
+  let expected = match glitches {
+    // Before the edge, after the rising edge and after the falling edge of cycles 1 and 2
+    true => format!(
+      "#3\n$dumpvars\n0{clock}\nb00100010 {data}\n$end\n#4\n1{clock}\n#5\n0{clock}\n\
+       #6\nb00110011 {data}\n#7\n1{clock}\n#8\n0{clock}\n"
+    ),
+    false => format!("#1\n$dumpvars\n0{clock}\nb00100010 {data}\n$end\n#2\nb00110011 {data}\n"),
+  };
+  assert_eq!(dump, expected);
+  assert!(design.finish_vcd().is_none());
+}
+
+#[test]
+fn test_vcd_step() {
+  let mut design = ram();
+  design.add_clock("clk_i", 10, 0).unwrap();
+  let config = VcdConfig {
+    signals: vec!["clk_i".to_string()],
+    start: 1,
+    end: Some(3),
+    ..Default::default()
+  };
+  design.start_vcd(config).unwrap();
+  design.advance_to(30).unwrap();
+  let vcd = design.finish_vcd().unwrap();
+  let dump = vcd.as_str().split("$enddefinitions $end\n").nth(1).unwrap();
+
+  // Stamped with simulated time, from the first rising edge at 0 up to the third at 20
+  let line = vcd.as_str().lines().find(|line| line.starts_with("$var"));
+  let clock = line.unwrap().split(' ').nth(3).unwrap();
+  assert_eq!(
+    dump,
+    format!("#0\n$dumpvars\n1{clock}\n$end\n#5\n0{clock}\n#10\n1{clock}\n#15\n0{clock}\n")
+  );
+}
+
+#[test]
+fn test_vcd_save() {
+  let mut design = ram();
+  design.start_vcd(VcdConfig::default()).unwrap();
+  design.eval_clocked().unwrap();
+  let vcd = design.finish_vcd().unwrap();
+  assert!(vcd.as_str().contains("$var wire 8 "));
+
+  let path = std::env::temp_dir().join("arbolta_test_vcd.vcd");
+  let path = path.to_str().unwrap();
+  vcd.save(path).unwrap();
+  assert_eq!(std::fs::read_to_string(path).unwrap(), vcd.as_str());
+
+  assert!(matches!(
+    design.start_vcd(VcdConfig {
+      scopes: vec!["u_missing".to_string()],
+      ..Default::default()
+    }),
+    Err(DesignError::ModuleError(ModuleError::MissingPath(_)))
+  ));
+}
@@ -0,0 +1,175 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+mod common;
+
+use arbolta::cell::{default_cell_library, Cell, Function};
+use arbolta::module::design::{Design, DesignError};
+use arbolta::module::hardware_module::{Component, HardwareModule, ModuleError};
+use arbolta::module::verilog::VerilogConfig;
+use arbolta::synth::netlist::Netlist;
+use common::{add_cell, add_net, design, MEMORY_RAW, NESTED_ADDER_RAW};
+use rstest::rstest;
+
+static XILINX_RAW: &str = include_str!("test_netlists/xilinx_netlist.json");
+
+/// Lines of the instance `name` up to the closing `);`.
+fn instance<'a>(verilog: &'a str, name: &str) -> Vec<&'a str> {
+  verilog
+    .lines()
+    .skip_while(|line| !line.contains(&format!(" {name} (")))
+    .take_while(|line| *line != "  );")
+    .collect()
+}
+
+#[test]
+fn test_verilog_hierarchy() {
+  let hierarchical = design(NESTED_ADDER_RAW, "adder", false);
+  let verilog = hierarchical.to_verilog(&VerilogConfig::default()).unwrap();
+  assert!(verilog.starts_with("module adder(op0_i, op1_i, sum_o);\n"));
+  assert!(verilog.contains("  output [4:0] sum_o;\n"));
+  // Submodule types are written once
+  assert_eq!(verilog.matches("\nmodule full_adder(").count(), 1);
+  assert_eq!(verilog.matches("endmodule").count(), 2);
+  assert_eq!(
+    instance(&verilog, "fa3"),
+    vec![
+      "  full_adder fa3 (",
+      "    .carry_i(fa2_carry),",
+      "    .carry_o(sum_o[4]),",
+      "    .op0_i(op0_i[3]),",
+      "    .op1_i(op1_i[3]),",
+      "    .sum_o(sum_o[3])",
+    ]
+  );
+  assert!(!verilog.contains("//"));
+
+  // Flattening keeps the outer names of nets crossing ports
+  let config = VerilogConfig {
+    flatten: true,
+    ..Default::default()
+  };
+  let flat = hierarchical.to_verilog(&config).unwrap();
+  assert_eq!(flat.matches("endmodule").count(), 1);
+  assert!(flat.contains("  assign \\fa0.carry_o  = fa0_carry;\n"));
+  assert!(flat.contains("  assign \\fa3.carry_o  = sum_o[4];\n"));
+  assert_eq!(
+    design(NESTED_ADDER_RAW, "adder", true)
+      .to_verilog(&VerilogConfig::default())
+      .unwrap(),
+    flat
+  );
+}
+
+#[test]
+fn test_verilog_cell_pins() {
+  // Pin names come from the netlist, not the cell library
+  let raw = NESTED_ADDER_RAW
+    .replace("\"A\":", "\"I0\":")
+    .replace("\"B\":", "\"I1\":")
+    .replace("\"Y\":", "\"O\":");
+  let mut cell_library = default_cell_library();
+  cell_library
+    .cells
+    .values_mut()
+    .for_each(|info| info.pins.clear());
+  let netlist = Netlist::from_yosys_raw(raw.as_bytes()).unwrap();
+  let module = netlist
+    .generate_flat_module("adder", &cell_library)
+    .unwrap();
+  let verilog = Design::from_module(module, cell_library.clone())
+    .to_verilog(&VerilogConfig::default())
+    .unwrap();
+  assert_eq!(
+    instance(&verilog, "\\fa0.$160 "),
+    vec![
+      "  NAND \\fa0.$160  (",
+      "    .I0(\\fa0.$153$new_n8 ),",
+      "    .I1(\\fa0.$153$new_n9 ),",
+      "    .O(\\fa0.$153$new_n12 )",
+    ]
+  );
+  assert!(!verilog.contains(".A("));
+
+  // Hand-built modules fall back to the cell library
+  let mut module = HardwareModule {
+    name: "inverter".to_string(),
+    ..Default::default()
+  };
+  let a = add_net(&mut module, "a");
+  let y = add_net(&mut module, "y");
+  let mut cell = Cell::from(&default_cell_library().cells["NOT"]);
+  cell.input_connections[0] = a;
+  cell.output_connection = y;
+  module.components.push(Component::Cell(cell));
+  let design = Design::from_module(module, default_cell_library());
+  assert!(design
+    .to_verilog(&VerilogConfig::default())
+    .unwrap()
+    .contains(".A(a),\n    .Y(y)"));
+  let design = Design::from_module(design.module, cell_library);
+  assert!(matches!(
+    design.to_verilog(&VerilogConfig::default()),
+    Err(DesignError::ModuleError(ModuleError::MissingPins(cell))) if cell == "NOT"
+  ));
+
+  // Unnamed cells aren't taken for the buffers `flatten` adds
+  let mut module = HardwareModule::default();
+  let (a, y) = (add_net(&mut module, "a"), add_net(&mut module, "y"));
+  add_cell(&mut module, Function::Buf, &[a], y);
+  let design = Design::from_module(module, default_cell_library());
+  assert!(matches!(
+    design.to_verilog(&VerilogConfig::default()),
+    Err(DesignError::ModuleError(ModuleError::MissingPins(cell))) if cell.is_empty()
+  ));
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn test_verilog_toggles(#[case] flatten: bool) {
+  let mut design = design(NESTED_ADDER_RAW, "adder", flatten);
+  design.module.set_port_int("op0_i", 0b0101_u8).unwrap();
+  design.eval();
+  let config = VerilogConfig {
+    toggles: true,
+    ..Default::default()
+  };
+  let verilog = design.to_verilog(&config).unwrap();
+  assert!(verilog.contains("  input [3:0] op0_i; // toggles: 0 1 0 1\n"));
+  assert!(verilog.contains("  output [4:0] sum_o; // toggles: 0 0 1 0 1\n"));
+  assert!(verilog.contains("  wire fa0_carry; // toggles: 0\n"));
+}
+
+#[test]
+fn test_verilog_primitives() {
+  let verilog = design(XILINX_RAW, "fpga_top", false)
+    .to_verilog(&VerilogConfig::default())
+    .unwrap();
+  assert_eq!(
+    instance(&verilog, "add_sum"),
+    vec![
+      "  CARRY4 add_sum (",
+      "    .CI(1'b0),",
+      "    .CYINIT(1'b0),",
+      "    .DI(a_i),",
+      "    .S(carry_s),",
+      "    .O(sum_o),",
+      "    .CO({co_o, carry_co[2:0]})",
+    ]
+  );
+  assert!(verilog.contains("  LUT2 #(\n    .INIT(4'h6)\n  ) add_s0 (\n"));
+  assert!(verilog.contains("    .IS_C_INVERTED(1'b1)\n  ) q_clr_reg (\n"));
+
+  let ram = design(MEMORY_RAW, "ram", false);
+  let verilog = ram.to_verilog(&VerilogConfig::default()).unwrap();
+  assert!(verilog.contains("  \\$mem_v2  #(\n    .MEMID(\"\\\\buffer\"),\n"));
+  assert!(verilog.contains("    .INIT(128'h00000000000000000000000042000000),\n"));
+  assert!(verilog.contains("    .RD_CLK({clk_i, 1'b0}),\n"));
+  assert!(verilog.contains("    .RD_DATA({rdata_q_o, rdata_o}),\n"));
+
+  let path = std::env::temp_dir().join("arbolta_test_verilog.v");
+  let path = path.to_str().unwrap();
+  ram.save_verilog(path, &VerilogConfig::default()).unwrap();
+  assert_eq!(std::fs::read_to_string(path).unwrap(), verilog);
+}
@@ -0,0 +1,142 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use arbolta::cell::default_cell_library;
+use arbolta::module::hardware_module::HardwareModule;
+use arbolta::primitive::word::WordCellError;
+use arbolta::signal::AccessSignal;
+use arbolta::synth::netlist::{Netlist, SynthError};
+use rstest::rstest;
+
+static WORD_ALU_RAW: &str = include_str!("test_netlists/word_alu_netlist.json");
+/// 2-bit `$pmux` with two cases.
+static PMUX_RAW: &str = r#"{"modules": {"pmux": {
+  "ports": {
+    "a_i": {"direction": "input", "bits": [2, 3]},
+    "b_i": {"direction": "input", "bits": [4, 5, 6, 7]},
+    "s_i": {"direction": "input", "bits": [8, 9]},
+    "y_o": {"direction": "output", "bits": [10, 11]}
+  },
+  "cells": {"mux": {
+    "type": "$pmux",
+    "parameters": {"WIDTH": "10", "S_WIDTH": "10"},
+    "connections": {"A": [2, 3], "B": [4, 5, 6, 7], "S": [8, 9], "Y": [10, 11]}
+  }},
+  "netnames": {
+    "a_i": {"bits": [2, 3]},
+    "b_i": {"bits": [4, 5, 6, 7]},
+    "s_i": {"bits": [8, 9]},
+    "y_o": {"bits": [10, 11]}
+  }
+}}}"#;
+
+fn word_alu() -> HardwareModule {
+  let netlist = Netlist::from_yosys_raw(WORD_ALU_RAW.as_bytes()).unwrap();
+  netlist
+    .generate_module("alu", &default_cell_library())
+    .unwrap()
+}
+
+#[test]
+fn test_word_arithmetic() {
+  let mut alu = word_alu();
+
+  for a in (-128..128_i16).step_by(7) {
+    alu.set_port_int("a_i", a as i8).unwrap();
+    for b in (-128..128_i16).step_by(5) {
+      alu.set_port_int("b_i", b as i8).unwrap();
+      alu.eval();
+
+      // Unsigned add into 9 bits
+      let expected_sum = (a as u8) as u16 + (b as u8) as u16;
+      assert_eq!(alu.get_port_int::<u16>("sum_o").unwrap(), expected_sum);
+      // Signed multiply into 16 bits
+      assert_eq!(alu.get_port_int::<i16>("prod_o").unwrap(), a * b);
+      assert_eq!(alu.get_port_int::<u8>("eq_o").unwrap(), (a == b) as u8);
+      assert_eq!(alu.get_port_int::<u8>("lt_o").unwrap(), (a < b) as u8);
+      assert_eq!(alu.get_port_int::<u8>("any_o").unwrap(), (a != 0) as u8);
+      assert_eq!(
+        alu.get_port_int::<i8>("shr_o").unwrap(),
+        (a as i8) >> (b & 0b111)
+      );
+    }
+  }
+}
+
+#[rstest]
+#[case(0, 0x12, 0x34, 0x12)]
+#[case(1, 0x12, 0x34, 0x34)]
+fn test_word_mux(#[case] sel: u8, #[case] a: u8, #[case] b: u8, #[case] expected: u8) {
+  let mut alu = word_alu();
+  alu.set_port_int("sel_i", sel).unwrap();
+  alu.set_port_int("a_i", a).unwrap();
+  alu.set_port_int("b_i", b).unwrap();
+  alu.eval();
+
+  assert_eq!(alu.get_port_int::<u8>("mux_o").unwrap(), expected);
+}
+
+#[rstest]
+#[case(0b00, 0b01)]
+#[case(0b01, 0b10)]
+#[case(0b10, 0b11)]
+// Lowest selected case wins
+#[case(0b11, 0b10)]
+fn test_word_pmux(#[case] sel: u8, #[case] expected: u8) {
+  let netlist = Netlist::from_yosys_raw(PMUX_RAW.as_bytes()).unwrap();
+  let mut pmux = netlist
+    .generate_module("pmux", &default_cell_library())
+    .unwrap();
+  pmux.set_port_int("a_i", 0b01_u8).unwrap();
+  pmux.set_port_int("b_i", 0b11_10_u8).unwrap();
+  pmux.set_port_int("s_i", sel).unwrap();
+  pmux.eval();
+
+  assert_eq!(pmux.get_port_int::<u8>("y_o").unwrap(), expected);
+}
+
+#[test]
+fn test_word_pmux_width() {
+  // One bit short of a case per select bit
+  let raw = PMUX_RAW.replace(r#""B": [4, 5, 6, 7], "S""#, r#""B": [4, 5, 6], "S""#);
+  let netlist = Netlist::from_yosys_raw(raw.as_bytes()).unwrap();
+  assert!(matches!(
+    netlist.generate_module("pmux", &default_cell_library()),
+    Err(SynthError::WordCell(_, WordCellError::Width { pin, expected: 4, actual: 3 })) if pin == "B"
+  ));
+}
+
+#[test]
+fn test_word_dff() {
+  let mut alu = word_alu();
+  alu.set_port_int("a_i", 0xa5_u8).unwrap();
+  alu.eval();
+  assert_eq!(alu.get_port_int::<u8>("q_o").unwrap(), 0);
+
+  alu.set_port_int("clk_i", 1_u8).unwrap();
+  alu.eval();
+  assert_eq!(alu.get_port_int::<u8>("q_o").unwrap(), 0xa5);
+
+  // Holds value until next rising edge
+  alu.set_port_int("a_i", 0x0f_u8).unwrap();
+  alu.set_port_int("clk_i", 0_u8).unwrap();
+  alu.eval();
+  assert_eq!(alu.get_port_int::<u8>("q_o").unwrap(), 0xa5);
+}
+
+#[test]
+fn test_word_toggles_per_bit() {
+  let mut alu = word_alu();
+  alu.eval();
+  alu.set_port_int("a_i", 0b0000_0011_u8).unwrap();
+  alu.eval();
+
+  // sum_o[0] and sum_o[1] rise once each, other bits stay low
+  let sum_toggles: Vec<usize> = (0..9)
+    .map(|i| {
+      let idx = alu.get_signal_idx(&format!("sum_o[{i}]")).unwrap();
+      alu.signals[idx].get_total_toggle_count()
+    })
+    .collect();
+  assert_eq!(sum_toggles, vec![1, 1, 0, 0, 0, 0, 0, 0, 0]);
+}
@@ -0,0 +1,118 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use arbolta::cell::default_cell_library;
+use arbolta::module::design::Design;
+use arbolta::module::hardware_module::HardwareModule;
+use arbolta::primitive::xilinx::FpgaResources;
+use arbolta::synth::netlist::Netlist;
+use rstest::rstest;
+
+static XILINX_RAW: &str = include_str!("test_netlists/xilinx_netlist.json");
+
+fn fpga_top() -> HardwareModule {
+  let netlist = Netlist::from_yosys_raw(XILINX_RAW.as_bytes()).unwrap();
+  netlist
+    .generate_module("fpga_top", &default_cell_library())
+    .unwrap()
+}
+
+#[test]
+fn test_xilinx_carry_adder() {
+  let mut top = fpga_top();
+
+  for a in 0..16_u8 {
+    top.set_port_int("a_i", a).unwrap();
+    for b in 0..16_u8 {
+      top.set_port_int("b_i", b).unwrap();
+      top.eval();
+
+      assert_eq!(top.get_port_int::<u8>("sum_o").unwrap(), (a + b) & 0xf);
+      assert_eq!(top.get_port_int::<u8>("co_o").unwrap(), (a + b) >> 4);
+    }
+  }
+}
+
+#[rstest]
+#[case(0b00, 0b0, 0)]
+#[case(0b01, 0b0, 0)]
+#[case(0b01, 0b1, 1)]
+#[case(0b10, 0b1, 1)]
+#[case(0b11, 0b0, 1)]
+fn test_xilinx_lut(#[case] a: u8, #[case] b: u8, #[case] expected: u8) {
+  let mut top = fpga_top();
+  top.set_port_int("a_i", a).unwrap();
+  top.set_port_int("b_i", b).unwrap();
+  top.eval();
+
+  // Majority of a[0], b[0], a[1]
+  assert_eq!(top.get_port_int::<u8>("maj_o").unwrap(), expected);
+}
+
+#[rstest]
+#[case(0b0000, 0b0010, 0)]
+#[case(0b0010, 0b0000, 1)]
+#[case(0b0011, 0b0000, 0)]
+#[case(0b0001, 0b0010, 1)]
+fn test_xilinx_muxf(#[case] a: u8, #[case] b: u8, #[case] expected: u8) {
+  let mut top = fpga_top();
+  top.set_port_int("a_i", a).unwrap();
+  top.set_port_int("b_i", b).unwrap();
+  top.eval();
+
+  assert_eq!(top.get_port_int::<u8>("mux_o").unwrap(), expected);
+}
+
+#[test]
+fn test_xilinx_flops() {
+  let mut top = fpga_top();
+  top.eval();
+  // FDCE starts from INIT
+  assert_eq!(top.get_port_int::<u8>("q_o").unwrap(), 0);
+  assert_eq!(top.get_port_int::<u8>("q_clr_o").unwrap(), 1);
+
+  // Clock enable low, FDRE holds
+  top.set_port_int("a_i", 1_u8).unwrap();
+  top.set_port_int("clk_i", 1_u8).unwrap();
+  top.eval();
+  assert_eq!(top.get_port_int::<u8>("q_o").unwrap(), 0);
+
+  top.set_port_int("ce_i", 1_u8).unwrap();
+  top.set_port_int("clk_i", 0_u8).unwrap();
+  top.eval();
+  top.set_port_int("clk_i", 1_u8).unwrap();
+  top.eval();
+  assert_eq!(top.get_port_int::<u8>("q_o").unwrap(), 1);
+
+  // FDCE clock is inverted, captures on falling edge
+  top.set_port_int("a_i", 0_u8).unwrap();
+  top.set_port_int("clk_i", 0_u8).unwrap();
+  top.eval();
+  assert_eq!(top.get_port_int::<u8>("q_clr_o").unwrap(), 0);
+
+  // Asynchronous clear and synchronous reset
+  top.set_port_int("a_i", 1_u8).unwrap();
+  top.set_port_int("rst_i", 1_u8).unwrap();
+  top.eval();
+  assert_eq!(top.get_port_int::<u8>("q_clr_o").unwrap(), 0);
+  assert_eq!(top.get_port_int::<u8>("q_o").unwrap(), 1);
+  top.set_port_int("clk_i", 1_u8).unwrap();
+  top.eval();
+  assert_eq!(top.get_port_int::<u8>("q_o").unwrap(), 0);
+}
+
+#[test]
+fn test_xilinx_resources() {
+  let design = Design::from_module(fpga_top(), default_cell_library());
+  let resources = design.get_module_resources("fpga_top").unwrap();
+
+  assert_eq!(
+    resources,
+    FpgaResources {
+      luts: 5,
+      ffs: 2,
+      carries: 1,
+      muxes: 1,
+    }
+  );
+}
@@ -2,4 +2,5 @@
 # SPDX-License-Identifier: MIT
 
 from .arbolta import Design  # For pickling
+from .arbolta import LaneDesign
 from .design import *
@@ -1,12 +1,15 @@
 # Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
 # SPDX-License-Identifier: MIT
 
+import json
+import warnings
 from dataclasses import dataclass
-from typing import Any, Dict, List, Optional, Tuple, TypedDict
+from typing import (Any, Callable, Dict, List, Optional, Tuple, TypedDict,
+                    Union)
 
 import numpy as np
 
-from .arbolta import Design
+from .arbolta import Design, LaneDesign
 
 __all__ = ["PortConfig", "DesignConfig", "HardwareDesign"]
 
@@ -26,11 +29,41 @@ class PortConfig:
         Port is a clock signal.
     reset : bool, optional
         Port is a reset signal.
+    active_low : bool, optional
+        Reset is asserted low.
+    reset_cycles : int, optional
+        Clock cycles resets are held for by `HardwareDesign.reset_clocked`.
+        Applies to every reset of the design, so reset ports must agree on it,
+        `reset_clocked` and `exclude_reset_statistics`.
+    reset_clocked : bool, optional
+        Clock the design while resets are held. Disable to only evaluate it,
+        for asynchronous resets.
+    exclude_reset_statistics : bool, optional
+        Leave the reset sequence out of toggle, cycle, clock and memory
+        access counts, and pause VCD and SAIF recording while it runs.
+    period : int, optional
+        Register the port as a free-running clock with this period, advanced
+        by `HardwareDesign.step`.
+    phase : int, optional
+        Time of the clock's first rising edge.
+    derived_from : str, optional
+        Register the port as a clock derived from the clock on this port,
+        which must be configured first.
+    ratio : tuple, optional
+        `(multiply, divide)` applied to the frequency of `derived_from`.
     """
     shape: Tuple[int, int] = (1, 1)
     dtype: np.dtype = np.uint32
     clock: bool = False
     reset: bool = False
+    active_low: bool = False
+    reset_cycles: int = 1
+    reset_clocked: bool = True
+    exclude_reset_statistics: bool = False
+    period: Optional[int] = None
+    phase: int = 0
+    derived_from: Optional[str] = None
+    ratio: Tuple[int, int] = (1, 1)
 
 
 class DesignConfig(TypedDict):
@@ -53,6 +86,8 @@ class HardwarePorts:
     def __init__(self, config: DesignConfig, design: Design):
 
         _ports: Dict[str, np.ndarray] = {}
+        reset_config: Optional[Tuple[str, Tuple[int, bool, bool]]] = None
+        has_reset = False
 
         port_name: str
         port_config: PortConfig
@@ -61,14 +96,38 @@ class HardwarePorts:
                 raise AttributeError(
                     f"Port `{port_name}` cannot be a reset and clock")
             if port_config.reset:
-                design.set_reset(port_name)
+                # The first active-high reset is the design's reset, any other
+                # is asserted together with it
+                if port_config.active_low or has_reset:
+                    design.add_reset(port_name, port_config.active_low)
+                else:
+                    design.set_reset(port_name)
+                    has_reset = True
+                port_reset_config = (port_config.reset_cycles,
+                                     port_config.reset_clocked,
+                                     port_config.exclude_reset_statistics)
+                if reset_config is None:
+                    reset_config = (port_name, port_reset_config)
+                elif reset_config[1] != port_reset_config:
+                    raise ValueError(
+                        f"Reset `{port_name}` is configured differently from "
+                        f"reset `{reset_config[0]}`")
             if port_config.clock:
                 design.set_clock(port_name)
+            if port_config.period is not None:
+                design.add_clock(port_name, port_config.period,
+                                 port_config.phase)
+            if port_config.derived_from is not None:
+                design.add_derived_clock(port_name, port_config.derived_from,
+                                         *port_config.ratio)
 
             design.set_port_shape(port_name, port_config.shape)
             _ports[port_name] = np.zeros(port_config.shape[1],
                                          dtype=port_config.dtype)
 
+        if reset_config is not None:
+            design.set_reset_config(*reset_config[1])
+
         super().__setattr__('_ports', _ports)
         super().__setattr__('_design', design)
 
@@ -104,8 +163,14 @@ class HardwarePorts:
 
 class HardwareDesign:
 
-    def __init__(self, top_module: str, netlist_path: str,
-                 config: DesignConfig):
+    def __init__(self,
+                 top_module: str,
+                 netlist_path: str,
+                 config: DesignConfig,
+                 models: Optional[Dict[str, Callable[[Dict[str, int]],
+                                                     Dict[str, int]]]] = None,
+                 scheduler: str = "levelized",
+                 flatten: bool = False):
         """
         Parameters
         ----------
@@ -115,11 +180,32 @@ class HardwareDesign:
             Path to Yosys netlist JSON.
         config : DesignConfig
             Configuration for design.
+        models : dict, optional
+            Behavioral models used in place of modules, keyed by module name.
+            Each model is called with a dict of input port values (unsigned
+            ints) and returns a dict of output port values. Designs with
+            models can't be pickled or saved.
+        scheduler : str, optional
+            `levelized` evaluates every cell on each pass. `event` only
+            evaluates cells whose inputs changed, which is faster when few
+            nets toggle and gives identical results and statistics.
+            `compiled` flattens the design and lowers it into an instruction
+            tape, which is faster for gate-level designs without memories,
+            models or word-level cells.
+        flatten : bool, optional
+            Flatten submodules into the top module at elaboration. Statistics
+            by module are kept, submodule nets are named by instance path.
         """
         self.top_module = top_module
-        self.design = Design(top_module, netlist_path)
+        self.design = Design(top_module, netlist_path, models, flatten)
+        self.design.set_scheduler(scheduler)
         self.ports = HardwarePorts(config, self.design)
 
+        for loop in self.combinational_loops():
+            warnings.warn(
+                f"Combinational loop through cells {loop['cells']} "
+                f"and nets {loop['nets']}")
+
     def reset(self):
         """
         Reset all design signals and registers to zero.
@@ -129,7 +215,8 @@ class HardwareDesign:
 
     def reset_clocked(self):
         """
-        Asserts reset signal and clocks design for 1 cycle.
+        Asserts reset signals and clocks design for the configured number of
+        cycles, 1 by default.
 
         Raises
         ------
@@ -137,6 +224,218 @@ class HardwareDesign:
         """
         self.design.reset_clocked()
 
+    def checkpoint(self, file: Optional[str] = None) -> Optional[bytes]:
+        """
+        Save the dynamic state of the design (net values, registers, memories,
+        toggle counts and cycle count), without its netlist. Behavioral models
+        aren't saved and keep their own state on `restore`.
+
+        Parameters
+        ----------
+        file : str, optional
+            Path to write the checkpoint to.
+
+        Returns
+        -------
+        checkpoint : bytes or None
+            Checkpoint to pass to `restore`, `None` when written to `file`.
+        """
+        if file is not None:
+            self.design.save_checkpoint(file)
+            return None
+        return self.design.checkpoint()
+
+    def restore(self, checkpoint: Union[bytes, str]) -> None:
+        """
+        Return the design to a checkpoint, including values written to input
+        ports.
+
+        Parameters
+        ----------
+        checkpoint : bytes or str
+            Checkpoint from `checkpoint`, or path of a checkpoint file. Must
+            come from this design or one built from the same netlist.
+
+        Raises
+        ------
+            ValueError: Checkpoint doesn't match the design.
+        """
+        if isinstance(checkpoint, str):
+            self.design.load_checkpoint(checkpoint)
+        else:
+            self.design.restore(checkpoint)
+
+        for port_name, port_array in self.ports._ports.items():
+            if self.design.is_port_input(port_name):
+                self.design.get_port_numpy(port_name, port_array)
+
+    def start_vcd(self,
+                  scopes: Optional[List[str]] = None,
+                  signals: Optional[List[str]] = None,
+                  start: int = 0,
+                  end: Optional[int] = None,
+                  glitches: bool = False):
+        """
+        Record a VCD waveform of clocked evaluations and clock steps until
+        `finish_vcd`.
+
+        Parameters
+        ----------
+        scopes : list of str, optional
+            Instance paths to dump with everything below them, all by default.
+        signals : list of str, optional
+            Net names to dump, a trailing `*` matches any suffix. All by
+            default.
+        start : int, optional
+            First clock cycle dumped, or rising edge of the first configured
+            clock with `step`.
+        end : int, optional
+            Clock cycle dumping stops at.
+        glitches : bool, optional
+            Record values after every evaluation of a cycle instead of once
+            at its end.
+
+        Raises
+        ------
+            ValueError: A scope doesn't exist.
+        """
+        self.design.start_vcd(scopes or [], signals or [], start, end,
+                              glitches)
+
+    def finish_vcd(self, file: str):
+        """
+        Stop recording and write the waveform to `file`.
+
+        Raises
+        ------
+            RuntimeError: No recording was started.
+        """
+        self.design.finish_vcd(file)
+
+    def start_saif(self, period: int = 1):
+        """
+        Record switching activity of clocked evaluations and clock steps
+        until `finish_saif`.
+
+        Parameters
+        ----------
+        period : int, optional
+            Clock period of clocked evaluations in nanoseconds, time advanced
+            by `step` is counted in nanoseconds.
+        """
+        self.design.start_saif(period)
+
+    def finish_saif(self, file: str):
+        """
+        Stop recording and write toggle counts and time at 0 and 1 of every
+        net to `file` in SAIF, for power analysis tools.
+
+        Raises
+        ------
+            RuntimeError: No recording was started.
+        """
+        self.design.finish_saif(file)
+
+    def save_verilog(self,
+                     file: str,
+                     flatten: bool = False,
+                     toggles: bool = False):
+        """
+        Write a structural Verilog netlist of the design to `file`, with one
+        module per submodule type.
+
+        Parameters
+        ----------
+        file : str
+            Path of the netlist.
+        flatten : bool, optional
+            Fold submodules into one module.
+        toggles : bool, optional
+            Comment each port and wire with the toggle count of its bits.
+
+        Raises
+        ------
+            ValueError: Design can't be flattened or file can't be written.
+        """
+        self.design.save_verilog(file, flatten, toggles)
+
+    def save_dot(self,
+                 file: str,
+                 scope: str = "",
+                 probability: bool = False,
+                 cluster: bool = False):
+        """
+        Write a Graphviz DOT graph of the cells and nets of an instance to
+        `file`, colored from blue to red by switching activity.
+
+        Parameters
+        ----------
+        file : str
+            Path of the graph.
+        scope : str, optional
+            Path of the instance drawn (ex, `top.u_alu`), the whole design
+            by default.
+        probability : bool, optional
+            Color by the fraction of cycles each net ended at 1, from the
+            SAIF recording started with `start_saif`, instead of by toggle
+            count.
+        cluster : bool, optional
+            Draw the cells of submodules inside a cluster per instance.
+
+        Raises
+        ------
+            ValueError: Instance doesn't exist, no SAIF recording was started
+                or file can't be written.
+        """
+        self.design.save_dot(file, scope, probability, cluster)
+
+    def replay(self,
+               file: str,
+               scope: str = "",
+               mapping: Optional[Dict[str, str]] = None,
+               clock: Optional[str] = None,
+               cycles: Optional[int] = None) -> int:
+        """
+        Drive input ports from a VCD or FST waveform (FST needs GTKWave's
+        `fst2vcd`), clocking the design at every rising edge of its clock.
+
+        Parameters
+        ----------
+        file : str
+            Path of the waveform.
+        scope : str, optional
+            Scope of the design in the waveform (ex, `tb.u_dut`). Ports are
+            driven by the variables of the same name in it.
+        mapping : dict, optional
+            Hierarchical name of the variable driving a port, by port name.
+        clock : str, optional
+            Hierarchical name of the clock variable, the design's clock in
+            `scope` by default.
+        cycles : int, optional
+            Stop after this many cycles.
+
+        Returns
+        -------
+        cycles : int
+            Number of cycles run.
+
+        Raises
+        ------
+            ValueError: Malformed waveform or missing variable.
+        """
+        cycles = self.design.replay(file, scope, mapping or {}, clock, cycles)
+
+        for port_name, port_array in self.ports._ports.items():
+            if self.design.is_port_input(port_name):
+                self.design.get_port_numpy(port_name, port_array)
+        return cycles
+
+    def cycles(self) -> int:
+        """
+        Get number of clock cycles run with `eval_clocked`.
+        """
+        return self.design.get_cycles()
+
     def eval(self):
         """
         Evaluates all cells in design.
@@ -161,6 +460,107 @@ class HardwareDesign:
 
         self.design.eval_clocked()
 
+    def step(self) -> int:
+        """
+        Advances time to the next edge of any clock configured with a
+        `period`, drives the clocks with an edge and evaluates the design.
+
+        Returns
+        -------
+        time : int
+            Time of the edge.
+
+        Raises
+        ------
+            AttributeError: No clock configured with a period.
+        """
+        # Clocks with a period are driven by the design, not their arrays
+        clocks = self.design.get_clock_statistics()
+        for port_name, port_array in self.ports._ports.items():
+            if self.design.is_port_input(port_name) and port_name not in clocks:
+                self.design.set_port_numpy(port_name, port_array)
+
+        return self.design.step()
+
+    def advance_to(self, time: int) -> None:
+        """
+        Steps through every clock edge up to and including `time`.
+
+        Parameters
+        ----------
+        time : int
+            Time to advance to.
+        """
+        clocks = self.design.get_clock_statistics()
+        for port_name, port_array in self.ports._ports.items():
+            if self.design.is_port_input(port_name) and port_name not in clocks:
+                self.design.set_port_numpy(port_name, port_array)
+
+        self.design.advance_to(time)
+
+    def time(self) -> int:
+        """
+        Get simulated time of the last clock edge or `advance_to`.
+        """
+        return self.design.get_time()
+
+    def clock_statistics(self) -> Dict[str, Dict[str, int]]:
+        """
+        Get statistics of each clock domain. Toggles during a step are charged
+        to the clocks with an edge whose fanout cone contains the net.
+
+        Returns
+        -------
+        statistics : dict
+            `cycles` (rising edges) and `toggles` of each clock.
+        """
+        return {
+            name: {
+                "cycles": cycles,
+                "toggles": toggles
+            }
+            for name, (cycles,
+                       toggles) in self.design.get_clock_statistics().items()
+        }
+
+    def eval_until_stable(self, max_iterations: int = 100) -> int:
+        """
+        Evaluates design until no signal changes.
+
+        Parameters
+        ----------
+        max_iterations : int, optional
+            Number of evaluations before giving up.
+
+        Returns
+        -------
+        iterations : int
+            Number of evaluations needed to settle.
+
+        Raises
+        ------
+            RuntimeError: Design did not settle, lists the oscillating nets.
+        """
+        for port_name, port_array in self.ports._ports.items():
+            if self.design.is_port_input(port_name):
+                self.design.set_port_numpy(port_name, port_array)
+
+        return self.design.eval_until_stable(max_iterations)
+
+    def combinational_loops(self) -> List[Dict[str, List[str]]]:
+        """
+        Get combinational loops found when the design was elaborated.
+
+        Returns
+        -------
+        loops : list
+            Each loop's `cells` and `nets`, named by hierarchical path.
+        """
+        return [{
+            "cells": cells,
+            "nets": nets
+        } for cells, nets in self.design.get_combinational_loops()]
+
     def cell_breakdown(self,
                        module_name: Optional[str] = None) -> Dict[str, int]:
         """
@@ -169,7 +569,8 @@ class HardwareDesign:
         Parameters
         ----------
         module_name : str, optional
-            Name of module. Defaults to top module.
+            Module name or hierarchical instance path (e.g.
+            `top.u_tree.u_add[3]`). Defaults to top module.
 
         Returns
         -------
@@ -193,7 +594,8 @@ class HardwareDesign:
         Parameters
         ----------
         module_name : str, optional
-            Name of module. Defaults to top module.
+            Module name or hierarchical instance path (e.g.
+            `top.u_tree.u_add[3]`). Defaults to top module.
 
         Returns
         -------
@@ -209,6 +611,90 @@ class HardwareDesign:
         else:
             return self.design.get_module_area(module_name)
 
+    def resources(self, module_name: Optional[str] = None) -> Dict[str, int]:
+        """
+        Get FPGA resource usage of module. Use instead of `area` for designs
+        synthesized to Xilinx primitives.
+
+        Parameters
+        ----------
+        module_name : str, optional
+            Module name or hierarchical instance path (e.g.
+            `top.u_tree.u_add[3]`). Defaults to top module.
+
+        Returns
+        -------
+        resources : dict
+            Count of `luts`, `ffs`, `carries` and `muxes` in given module.
+
+        Raises
+        ------
+            AttributeError: Specified module doesn't exist in design.
+        """
+        if module_name is None:
+            return self.design.get_module_resources(self.design.top_module)
+        else:
+            return self.design.get_module_resources(module_name)
+
+    def drive(self, port_name: str, value: Any):
+        """
+        Drive an inout port from outside the design. The port stays driven
+        until released.
+
+        Parameters
+        ----------
+        port_name : str
+            Name of inout port.
+        value : array_like
+            Value to drive, interpreted with the port's configuration.
+
+        Raises
+        ------
+            AttributeError: Port doesn't exist or isn't an inout port.
+        """
+        if not self.design.is_port_inout(port_name):
+            raise AttributeError(f"Port `{port_name}` is not an inout port")
+
+        port_array = self.ports._ports[port_name]
+        np.copyto(port_array, value)
+        self.design.set_port_numpy(port_name, port_array)
+
+    def release(self, port_name: str):
+        """
+        Stop driving an inout port, so it can be sampled as driven by the
+        design.
+
+        Parameters
+        ----------
+        port_name : str
+            Name of inout port.
+
+        Raises
+        ------
+            AttributeError: Port doesn't exist or isn't an inout port.
+        """
+        self.design.release_port(port_name)
+
+    def high_z(self, port_name: str) -> List[bool]:
+        """
+        Get which bits of a port are high-impedance (undriven).
+
+        Parameters
+        ----------
+        port_name : str
+            Name of port.
+
+        Returns
+        -------
+        high_z : list
+            One flag per port bit.
+
+        Raises
+        ------
+            AttributeError: Specified port doesn't exist.
+        """
+        return self.design.get_port_high_z(port_name)
+
     def total_toggle_count(self, module_name: Optional[str] = None) -> int:
         """
         Get total toggle count (rising + falling) of module.
@@ -216,7 +702,8 @@ class HardwareDesign:
         Parameters
         ----------
         module_name : str, optional
-            Name of module. Defaults to top module.
+            Module name or hierarchical instance path (e.g.
+            `top.u_tree.u_add[3]`). Defaults to top module.
 
         Returns
         -------
@@ -233,6 +720,255 @@ class HardwareDesign:
         else:
             return self.design.get_module_total_toggle_count(module_name)
 
+    def peek(self, path: str) -> int:
+        """
+        Get the value of a net, port or bus anywhere in the design.
+
+        Parameters
+        ----------
+        path : str
+            Hierarchical path through instance names, e.g.
+            `top.u_tree.gen_stage_0.u_add[3].sum_o[4]`.
+
+        Returns
+        -------
+        value : int
+            Unsigned value, least significant bit first.
+
+        Raises
+        ------
+            AttributeError: Specified path doesn't exist in design.
+        """
+        return self.design.get_path_int(path)
+
+    def poke(self, path: str, value: int) -> None:
+        """
+        Set the value of a net, port or bus anywhere in the design. Nets keep
+        the value until they are next driven.
+
+        Parameters
+        ----------
+        path : str
+            Hierarchical path through instance names.
+        value : int
+            Unsigned value, least significant bit first.
+
+        Raises
+        ------
+            AttributeError: Specified path doesn't exist in design.
+        """
+        self.design.set_path_int(path, value)
+
+    def lanes(self) -> LaneDesign:
+        """
+        Get a bit-parallel copy of the design, which simulates 64 independent
+        stimuli per pass. Only gate-level designs are supported.
+
+        Use `eval_batch(inputs, outputs, clocked)` with a list of unsigned
+        values per input port, element `i` runs in lane `i % 64`. Toggle
+        counts are summed over lanes.
+
+        Returns
+        -------
+        design : LaneDesign
+            Flattened copy of the design, with its current clock and reset.
+
+        Raises
+        ------
+            Exception: Design has memories, models or word-level cells.
+        """
+        return LaneDesign(self.design)
+
+    def hierarchy_report(self, by: str = "instance") -> List[Dict[str, Any]]:
+        """
+        Get area, cell and toggle statistics of every instance in the design.
+
+        Parameters
+        ----------
+        by : str, optional
+            `instance` for one entry per instance (parents before children),
+            or `module` for instances summed by module type.
+
+        Returns
+        -------
+        report : list
+            One dict per instance or module type. Instance entries have
+            `path`, `module`, `parent`, `depth`, `cells`, `area`,
+            `area_percent`, `nand2_equivalents`, `flops`, `toggles` and
+            `local_toggles`. Statistics include instances below, except
+            `local_toggles`.
+
+        Raises
+        ------
+            ValueError: Unknown aggregation.
+        """
+        report = json.loads(self.design.get_hierarchy_report_json())
+        if by == "instance":
+            return report["instances"]
+        elif by == "module":
+            return report["modules"]
+        raise ValueError(
+            f"Unknown aggregation `{by}`, expected `instance` or `module`")
+
+    def save_hierarchy_report(self, file: str, by: str = "instance") -> None:
+        """
+        Write the hierarchy report to a file, as CSV if the file name ends in
+        `.csv` and as JSON otherwise.
+
+        Parameters
+        ----------
+        file : str
+            Output path.
+        by : str, optional
+            CSV rows per `instance` or per `module` type. JSON always has
+            both.
+        """
+        if file.endswith(".csv"):
+            contents = self.design.get_hierarchy_report_csv(by)
+        else:
+            contents = self.design.get_hierarchy_report_json()
+        with open(file, "w") as f:
+            f.write(contents)
+
+    def run_batch(self,
+                  stimuli: List[List[Dict[str, int]]],
+                  outputs: List[str],
+                  threads: int = 0,
+                  clocked: bool = False,
+                  reset: bool = False) -> Dict[str, Any]:
+        """
+        Simulate independent stimulus sequences (e.g. one per image) on a
+        thread pool, each starting from a reset copy of the design. The GIL
+        is released while simulating, and results don't depend on the number
+        of threads. The design itself isn't changed.
+
+        Parameters
+        ----------
+        stimuli : list
+            One list of steps per sequence. Each step is a dict of unsigned
+            values by port name or hierarchical path, written before
+            evaluating.
+        outputs : list
+            Ports or hierarchical paths read after each step.
+        threads : int, optional
+            Worker threads, all available cores when 0.
+        clocked : bool, optional
+            Pulse the clock after each step instead of a single eval.
+        reset : bool, optional
+            Pulse reset at the start of each sequence.
+
+        Returns
+        -------
+        results : dict
+            `outputs` (values by sequence, step and output), `toggles`,
+            `toggles_per_area` and `port_toggles` summed over all sequences,
+            and `hierarchy_report` with summed toggles per instance.
+
+        Raises
+        ------
+            RuntimeError: A sequence failed, e.g. a port doesn't exist.
+        """
+        values, merged, port_toggles = self.design.run_batch(
+            stimuli, outputs, threads, clocked, reset)
+        toggles = merged.get_module_total_toggle_count(self.top_module)
+        area = merged.get_module_area(self.top_module)
+        return {
+            "outputs": values,
+            "toggles": toggles,
+            "toggles_per_area": toggles / area if area > 0 else 0.0,
+            "port_toggles": port_toggles,
+            "hierarchy_report":
+            json.loads(merged.get_hierarchy_report_json())["instances"],
+        }
+
+    def load_memory(self, memory_name: str, contents: Any) -> None:
+        """
+        Backdoor load memory contents, starting at word 0.
+
+        Parameters
+        ----------
+        memory_name : str
+            Memory instance path or its name in the source design.
+        contents : np.ndarray or str
+            Words to load, or path to a `$readmemh`-style hex file.
+
+        Raises
+        ------
+            AttributeError: Specified memory doesn't exist in design.
+        """
+        if isinstance(contents, str):
+            self.design.load_memory_hex(memory_name, contents)
+        else:
+            self.design.set_memory_numpy(memory_name,
+                                         np.ascontiguousarray(contents))
+
+    def dump_memory(self,
+                    memory_name: str,
+                    dtype: np.dtype = np.uint32) -> np.ndarray:
+        """
+        Backdoor read all memory words.
+
+        Parameters
+        ----------
+        memory_name : str
+            Memory instance path or its name in the source design.
+        dtype : np.dtype, optional
+            Interpret words as type.
+
+        Returns
+        -------
+        contents : np.ndarray
+            Memory words.
+
+        Raises
+        ------
+            AttributeError: Specified memory doesn't exist in design.
+        """
+        size, _ = self.design.get_memory_shape(memory_name)
+        contents = np.zeros(size, dtype=dtype)
+        self.design.get_memory_numpy(memory_name, contents)
+        return contents
+
+    def memory_statistics(self, memory_name: str) -> Dict[str, List[int]]:
+        """
+        Get number of reads and writes performed by each memory port.
+
+        Parameters
+        ----------
+        memory_name : str
+            Memory instance path or its name in the source design.
+
+        Returns
+        -------
+        statistics : dict
+            `reads` and `writes` counts, one entry per port.
+
+        Raises
+        ------
+            AttributeError: Specified memory doesn't exist in design.
+        """
+        return self.design.get_memory_statistics(memory_name)
+
+    def cell_statistics(self, cell_name: str) -> Dict[str, int]:
+        """
+        Get statistics reported by a user-defined cell.
+
+        Parameters
+        ----------
+        cell_name : str
+            Cell instance name.
+
+        Returns
+        -------
+        statistics : dict
+            Statistics defined by the cell's behavior.
+
+        Raises
+        ------
+            AttributeError: Specified cell doesn't exist in design.
+        """
+        return self.design.get_cell_statistics(cell_name)
+
     def module_names(self) -> List[str]:
         """
         Get names of modules in top-level design module.
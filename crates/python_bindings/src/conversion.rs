@@ -1,7 +1,7 @@
 // Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
 // SPDX-License-Identifier: MIT
 
-use arbol::bit::BitVec;
+use arbol::bit::{Bit, BitVec};
 use num_traits::PrimInt;
 use numpy::{PyReadonlyArray1, PyReadwriteArray1};
 use pyo3::exceptions::PyValueError;
@@ -48,3 +48,87 @@ pub fn int_numpy_to_bits<T: PrimInt + numpy::Element>(
     Err(err) => Err(PyValueError::new_err(format!("{err}"))),
   }
 }
+
+/// Store bits in a numpy array, dispatching on its dtype.
+pub fn bits_to_numpy(
+  bits: &BitVec,
+  elem_size: usize,
+  numpy_array: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+  let item_type = numpy_array.getattr("dtype")?.getattr("str")?.to_string();
+  match item_type.as_str() {
+    "|b1" => bits_to_bool_numpy(bits, numpy_array),
+    "|u1" | "<V1" => bits_to_int_numpy::<u8>(bits, elem_size, numpy_array),
+    "<u2" => bits_to_int_numpy::<u16>(bits, elem_size, numpy_array),
+    "<u4" => bits_to_int_numpy::<u32>(bits, elem_size, numpy_array),
+    "<u8" => bits_to_int_numpy::<u64>(bits, elem_size, numpy_array),
+    "|i1" => bits_to_int_numpy::<i8>(bits, elem_size, numpy_array),
+    "<i2" => bits_to_int_numpy::<i16>(bits, elem_size, numpy_array),
+    "<i4" => bits_to_int_numpy::<i32>(bits, elem_size, numpy_array),
+    "<i8" => bits_to_int_numpy::<i64>(bits, elem_size, numpy_array),
+    // Cast f16 to u16
+    "<f2" => bits_to_int_numpy::<u16>(
+      bits,
+      elem_size,
+      &numpy_array.call_method1("view", ("uint16",))?,
+    ),
+    // Cast f32 to u32
+    "<f4" => bits_to_int_numpy::<u32>(
+      bits,
+      elem_size,
+      &numpy_array.call_method1("view", ("uint32",))?,
+    ),
+    _ => Err(PyValueError::new_err(format!(
+      "Unsupported item type: {item_type}"
+    ))),
+  }
+}
+
+/// Convert a numpy array to bits, dispatching on its dtype.
+pub fn numpy_to_bits(numpy_array: &Bound<'_, PyAny>, elem_size: usize) -> PyResult<BitVec> {
+  let item_type = numpy_array.getattr("dtype")?.getattr("str")?.to_string();
+  match item_type.as_str() {
+    "|b1" => bool_numpy_to_bits(numpy_array),
+    "|u1" => int_numpy_to_bits::<u8>(numpy_array, elem_size),
+    "<u2" => int_numpy_to_bits::<u16>(numpy_array, elem_size),
+    "<u4" => int_numpy_to_bits::<u32>(numpy_array, elem_size),
+    "<u8" => int_numpy_to_bits::<u64>(numpy_array, elem_size),
+    "|i1" => int_numpy_to_bits::<i8>(numpy_array, elem_size),
+    "<i2" => int_numpy_to_bits::<i16>(numpy_array, elem_size),
+    "<i4" => int_numpy_to_bits::<i32>(numpy_array, elem_size),
+    "<i8" => int_numpy_to_bits::<i64>(numpy_array, elem_size),
+    // Cast to raw uint8
+    "<V1" => int_numpy_to_bits::<u8>(&numpy_array.call_method1("view", ("uint8",))?, elem_size),
+    // Cast f16 to u16
+    "<f2" => int_numpy_to_bits::<u16>(&numpy_array.call_method1("view", ("uint16",))?, elem_size),
+    // Cast f32 to u32
+    "<f4" => int_numpy_to_bits::<u32>(&numpy_array.call_method1("view", ("uint32",))?, elem_size),
+    _ => Err(PyValueError::new_err(format!(
+      "Unsupported item type: {item_type}"
+    ))),
+  }
+}
+
+/// Convert bits to an (unsigned) Python int of any width.
+pub fn bits_to_pyint<'py>(py: Python<'py>, bits: &BitVec) -> PyResult<Bound<'py, PyAny>> {
+  let mut bytes = vec![0_u8; bits.bits.len().div_ceil(8)];
+  for (i, bit) in bits.bits.iter().enumerate() {
+    bytes[i / 8] |= bit.to_int::<u8>() << (i % 8);
+  }
+  let int_type = py.get_type::<pyo3::types::PyInt>();
+  int_type.call_method1("from_bytes", (bytes, "little"))
+}
+
+/// Convert a Python int to `width` bits, truncating and two's complementing as needed.
+pub fn pyint_to_bits(value: &Bound<'_, PyAny>, width: usize) -> PyResult<BitVec> {
+  let mask = 1_i32.into_pyobject(value.py())?.lshift(width)?.sub(1)?;
+  let masked = value.bitand(mask)?;
+  let bytes: Vec<u8> = masked
+    .call_method1("to_bytes", (width.div_ceil(8), "little"))?
+    .extract()?;
+
+  let bits = (0..width)
+    .map(|i| Bit::from((bytes[i / 8] >> (i % 8)) & 1 == 1))
+    .collect::<Vec<Bit>>();
+  Ok(BitVec::from(bits))
+}
@@ -1,14 +1,25 @@
 // Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
 // SPDX-License-Identifier: MIT
 
-use crate::conversion::{
-  bits_to_bool_numpy, bits_to_int_numpy, bool_numpy_to_bits, int_numpy_to_bits,
-};
+use crate::conversion::{bits_to_numpy, numpy_to_bits};
+use crate::model::{model_error, take_eval_error, PyModel};
 use arbol::cell::default_cell_library;
-use arbol::module::{design::Design, port::PortDirection};
+use arbol::module::{
+  batch::{BatchRunner, Stimulus},
+  checkpoint::Checkpoint,
+  design::{Design, DesignError},
+  dot::{DotActivity, DotConfig},
+  event::Scheduler,
+  hardware_module::ModuleError,
+  port::PortDirection,
+  replay::ReplayConfig,
+  report::Aggregation,
+  vcd::VcdConfig,
+  verilog::VerilogConfig,
+};
 use arbol::synth::netlist::Netlist;
 use bincode;
-use pyo3::exceptions::{PyAttributeError, PyException, PyValueError};
+use pyo3::exceptions::{PyAttributeError, PyException, PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::PyBytes;
 use serde::{Deserialize, Serialize};
@@ -20,20 +31,40 @@ pub struct PyDesign {
   #[pyo3(get)]
   pub top_module: String,
   pub netlist_path: String,
-  design: Design,
+  pub(crate) design: Design,
 }
 
 #[pymethods]
 impl PyDesign {
   #[new]
-  fn __new__(top_module: &str, netlist_path: &str) -> PyResult<Self> {
-    let cell_library = default_cell_library();
+  #[pyo3(signature = (top_module, netlist_path, models=None, flatten=false))]
+  fn __new__(
+    top_module: &str,
+    netlist_path: &str,
+    models: Option<HashMap<String, Py<PyAny>>>,
+    flatten: bool,
+  ) -> PyResult<Self> {
+    let mut cell_library = default_cell_library();
+    for (module_name, callable) in models.unwrap_or_default() {
+      cell_library.register_model(
+        &module_name,
+        Box::new(PyModel {
+          callable,
+          error: None,
+        }),
+      );
+    }
+
     let netlist = match Netlist::from_yosys(netlist_path) {
       Ok(netlist) => netlist,
       Err(err) => return Err(PyException::new_err(format!("{err}"))),
     };
 
-    let module = match netlist.generate_module(top_module, &cell_library) {
+    let module = match if flatten {
+      netlist.generate_flat_module(top_module, &cell_library)
+    } else {
+      netlist.generate_module(top_module, &cell_library)
+    } {
       Ok(module) => module,
       Err(err) => return Err(PyException::new_err(format!("{err}"))),
     };
@@ -49,6 +80,10 @@ impl PyDesign {
 
   fn __setstate__(&mut self, state: &Bound<'_, PyBytes>) {
     *self = bincode::deserialize(state.as_bytes()).unwrap();
+    // Tapes aren't serialized, build it again
+    if self.design.scheduler == Scheduler::Compiled {
+      _ = self.design.compile();
+    }
   }
 
   fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
@@ -84,6 +119,136 @@ impl PyDesign {
     })
   }
 
+  fn checkpoint<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+    match bincode::serialize(&self.design.checkpoint()) {
+      Ok(bytes) => Ok(PyBytes::new(py, &bytes)),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn restore(&mut self, checkpoint: &Bound<'_, PyBytes>) -> PyResult<()> {
+    let checkpoint: Checkpoint = match bincode::deserialize(checkpoint.as_bytes()) {
+      Ok(checkpoint) => checkpoint,
+      Err(err) => return Err(PyValueError::new_err(format!("{err}"))),
+    };
+    match self.design.restore(&checkpoint) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn save_checkpoint(&self, path: &str) -> PyResult<()> {
+    match self.design.checkpoint().save(path) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn load_checkpoint(&mut self, path: &str) -> PyResult<()> {
+    match Checkpoint::load(path).and_then(|checkpoint| self.design.restore(&checkpoint)) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  #[pyo3(signature = (scopes=vec![], signals=vec![], start=0, end=None, glitches=false))]
+  fn start_vcd(
+    &mut self,
+    scopes: Vec<String>,
+    signals: Vec<String>,
+    start: usize,
+    end: Option<usize>,
+    glitches: bool,
+  ) -> PyResult<()> {
+    let config = VcdConfig {
+      scopes,
+      signals,
+      start,
+      end,
+      glitches,
+    };
+    match self.design.start_vcd(config) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  #[pyo3(signature = (period=1))]
+  fn start_saif(&mut self, period: u64) {
+    self.design.start_saif(period);
+  }
+
+  fn finish_saif(&mut self, path: &str) -> PyResult<()> {
+    let Some(saif) = self.design.finish_saif() else {
+      return Err(PyRuntimeError::new_err("No SAIF recording was started"));
+    };
+    match saif.save(path) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  #[pyo3(signature = (path, flatten=false, toggles=false))]
+  fn save_verilog(&self, path: &str, flatten: bool, toggles: bool) -> PyResult<()> {
+    let config = VerilogConfig { flatten, toggles };
+    match self.design.save_verilog(path, &config) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  #[pyo3(signature = (path, scope=String::new(), probability=false, cluster=false))]
+  fn save_dot(&self, path: &str, scope: String, probability: bool, cluster: bool) -> PyResult<()> {
+    let activity = match probability {
+      true => DotActivity::Probability,
+      false => DotActivity::Toggles,
+    };
+    let config = DotConfig {
+      scope,
+      activity,
+      cluster,
+    };
+    match self.design.save_dot(path, &config) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  #[pyo3(signature = (path, scope=String::new(), mapping=HashMap::new(), clock=None, cycles=None))]
+  fn replay(
+    &mut self,
+    path: &str,
+    scope: String,
+    mapping: HashMap<String, String>,
+    clock: Option<String>,
+    cycles: Option<usize>,
+  ) -> PyResult<usize> {
+    let config = ReplayConfig {
+      scope,
+      mapping,
+      clock,
+      cycles,
+    };
+    match self.design.replay(path, &config) {
+      Ok(cycles) => take_eval_error(&mut self.design.module).map(|()| cycles),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn finish_vcd(&mut self, path: &str) -> PyResult<()> {
+    let Some(vcd) = self.design.finish_vcd() else {
+      return Err(PyRuntimeError::new_err("No VCD recording was started"));
+    };
+    match vcd.save(path) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_cycles(&self) -> usize {
+    self.design.cycles
+  }
+
   fn get_port_shape(&self, name: &str) -> PyResult<[usize; 2]> {
     match self.design.module.get_port_shape(name) {
       Ok(shape) => Ok(shape),
@@ -112,13 +277,27 @@ impl PyDesign {
 
   fn get_module_names(&self) -> Vec<String> {
     let mut names: Vec<String> = vec![];
+    // Flattened modules keep their submodules in the instance tree
+    self
+      .design
+      .module
+      .instances
+      .iter()
+      .filter(|instance| instance.parent == Some(0))
+      .for_each(|instance| names.push(instance.module.clone()));
     self
       .design
       .module
       .components
       .iter()
       .for_each(|component| match component {
-        arbol::module::hardware_module::Component::Cell(_) => (),
+        arbol::module::hardware_module::Component::Cell(_)
+        | arbol::module::hardware_module::Component::Behavior(_)
+        | arbol::module::hardware_module::Component::Word(_)
+        | arbol::module::hardware_module::Component::Memory(_)
+        | arbol::module::hardware_module::Component::Xilinx(_)
+        | arbol::module::hardware_module::Component::Tristate(_)
+        | arbol::module::hardware_module::Component::Model(_) => (),
         arbol::module::hardware_module::Component::Module(module) => {
           names.push(module.name.clone())
         }
@@ -140,28 +319,139 @@ impl PyDesign {
     }
   }
 
+  #[pyo3(signature = (name, active_low=false))]
+  fn add_reset(&mut self, name: &str, active_low: bool) -> PyResult<()> {
+    match self.design.add_reset(name, active_low) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  #[pyo3(signature = (cycles=1, clocked=true, exclude_statistics=false))]
+  fn set_reset_config(&mut self, cycles: usize, clocked: bool, exclude_statistics: bool) {
+    let config = &mut self.design.reset_config;
+    config.cycles = cycles;
+    config.clocked = clocked;
+    config.exclude_statistics = exclude_statistics;
+  }
+
   fn reset(&mut self) {
     self.design.module.reset();
   }
 
+  fn set_scheduler(&mut self, scheduler: &str) -> PyResult<()> {
+    self.design.set_scheduler(match scheduler {
+      "levelized" => Scheduler::Levelized,
+      "event" => Scheduler::EventDriven,
+      "compiled" => {
+        return match self.design.compile() {
+          Ok(()) => Ok(()),
+          Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+        }
+      }
+      _ => {
+        return Err(PyValueError::new_err(format!(
+          "Unknown scheduler `{scheduler}`, expected `levelized`, `event` or `compiled`"
+        )))
+      }
+    });
+    Ok(())
+  }
+
+  #[pyo3(signature = (name, period, phase=0))]
+  fn add_clock(&mut self, name: &str, period: u64, phase: u64) -> PyResult<()> {
+    match self.design.add_clock(name, period, phase) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  #[pyo3(signature = (name, source, multiply=1, divide=1))]
+  fn add_derived_clock(
+    &mut self,
+    name: &str,
+    source: &str,
+    multiply: u64,
+    divide: u64,
+  ) -> PyResult<()> {
+    match self
+      .design
+      .add_derived_clock(name, source, multiply, divide)
+    {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn step(&mut self) -> PyResult<u64> {
+    match self.design.step() {
+      Ok(time) => take_eval_error(&mut self.design.module).map(|()| time),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn advance_to(&mut self, time: u64) -> PyResult<()> {
+    match self.design.advance_to(time) {
+      Ok(()) => take_eval_error(&mut self.design.module),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_time(&self) -> u64 {
+    self.design.time
+  }
+
+  /// Rising edges and toggles of each clock.
+  fn get_clock_statistics(&self) -> HashMap<String, (usize, usize)> {
+    self
+      .design
+      .clocks
+      .iter()
+      .map(|clock| (clock.name.clone(), (clock.cycles, clock.toggles)))
+      .collect()
+  }
+
   fn reset_clocked(&mut self) -> PyResult<()> {
     match self.design.reset_clocked() {
-      Ok(()) => Ok(()),
+      Ok(()) => take_eval_error(&mut self.design.module),
       Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
     }
   }
 
-  fn eval(&mut self) {
+  fn eval(&mut self) -> PyResult<()> {
     self.design.eval();
+    take_eval_error(&mut self.design.module)
   }
 
   fn eval_clocked(&mut self) -> PyResult<()> {
     match self.design.eval_clocked() {
-      Ok(()) => Ok(()),
+      Ok(()) => take_eval_error(&mut self.design.module),
       Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
     }
   }
 
+  fn eval_until_stable(&mut self, max_iterations: usize) -> PyResult<usize> {
+    match self.design.eval_until_stable(max_iterations) {
+      Ok(iterations) => take_eval_error(&mut self.design.module).map(|()| iterations),
+      Err(err) => Err(PyRuntimeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_combinational_loops(&self) -> Vec<(Vec<String>, Vec<String>)> {
+    self
+      .design
+      .module
+      .combinational_loops
+      .iter()
+      .map(|combinational_loop| {
+        (
+          combinational_loop.cells.clone(),
+          combinational_loop.nets.clone(),
+        )
+      })
+      .collect()
+  }
+
   fn get_module_breakdown(&self, name: &str) -> PyResult<HashMap<String, usize>> {
     match self.design.get_module_breakdown(name) {
       Ok(breakdown) => Ok(breakdown),
@@ -176,6 +466,84 @@ impl PyDesign {
     }
   }
 
+  fn get_module_resources(&self, name: &str) -> PyResult<HashMap<String, usize>> {
+    match self.design.get_module_resources(name) {
+      Ok(resources) => Ok(HashMap::from([
+        ("luts".to_string(), resources.luts),
+        ("ffs".to_string(), resources.ffs),
+        ("carries".to_string(), resources.carries),
+        ("muxes".to_string(), resources.muxes),
+      ])),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_hierarchy_report_json(&self) -> PyResult<String> {
+    match self.design.get_hierarchy_report().to_json() {
+      Ok(json) => Ok(json),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_hierarchy_report_csv(&self, aggregation: &str) -> PyResult<String> {
+    let aggregation = match aggregation {
+      "instance" => Aggregation::Instance,
+      "module" => Aggregation::Module,
+      _ => {
+        return Err(PyValueError::new_err(format!(
+          "Unknown aggregation `{aggregation}`, expected `instance` or `module`"
+        )))
+      }
+    };
+    Ok(self.design.get_hierarchy_report().to_csv(aggregation))
+  }
+
+  /// Simulate independent stimulus sequences on a thread pool, without holding the GIL.
+  /// Returns outputs by sequence then step, a copy of the design with toggles summed over all
+  /// sequences, and the summed toggles of each port.
+  #[pyo3(signature = (stimuli, outputs, threads=0, clocked=false, reset=false))]
+  fn run_batch(
+    &self,
+    py: Python<'_>,
+    stimuli: Vec<Vec<HashMap<String, u128>>>,
+    outputs: Vec<String>,
+    threads: usize,
+    clocked: bool,
+    reset: bool,
+  ) -> PyResult<(Vec<Vec<Vec<u128>>>, Self, HashMap<String, usize>)> {
+    let stimuli: Vec<Stimulus> = stimuli
+      .into_iter()
+      .map(|steps| Stimulus {
+        steps: steps
+          .into_iter()
+          .map(|step| step.into_iter().collect())
+          .collect(),
+      })
+      .collect();
+    let runner = BatchRunner {
+      threads,
+      clocked,
+      reset,
+      outputs,
+    };
+
+    let result = match py.allow_threads(|| runner.run(&self.design, &stimuli)) {
+      Ok(result) => result,
+      Err(DesignError::ModuleError(err)) => return Err(model_error(err)),
+      Err(err) => return Err(PyRuntimeError::new_err(format!("{err}"))),
+    };
+    let port_toggles = result.get_port_toggle_counts().into_iter().collect();
+    Ok((
+      result.outputs,
+      Self {
+        top_module: self.top_module.clone(),
+        netlist_path: self.netlist_path.clone(),
+        design: result.design,
+      },
+      port_toggles,
+    ))
+  }
+
   fn get_module_total_toggle_count(&self, name: &str) -> PyResult<usize> {
     match self.design.get_module_total_toggle_count(name) {
       Ok(count) => Ok(count),
@@ -183,6 +551,20 @@ impl PyDesign {
     }
   }
 
+  fn get_path_int(&self, path: &str) -> PyResult<u128> {
+    match self.design.module.get_path_int(path) {
+      Ok(val) => Ok(val),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn set_path_int(&mut self, path: &str, val: u128) -> PyResult<()> {
+    match self.design.module.set_path_int(path, val) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
   fn get_port_string(&self, name: &str) -> PyResult<String> {
     match self.design.module.get_port_string(name) {
       Ok(bit_string) => Ok(bit_string),
@@ -199,75 +581,100 @@ impl PyDesign {
     Ok(direction == PortDirection::Input)
   }
 
+  fn is_port_inout(&self, name: &str) -> PyResult<bool> {
+    match self.design.module.get_port_direction(name) {
+      Ok(direction) => Ok(direction == PortDirection::InOut),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn release_port(&mut self, name: &str) -> PyResult<()> {
+    match self.design.module.release_port(name) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_port_high_z(&self, name: &str) -> PyResult<Vec<bool>> {
+    match self.design.module.get_port_high_z(name) {
+      Ok(high_z) => Ok(high_z),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
   fn get_port_numpy(&self, name: &str, numpy_array: &Bound<'_, PyAny>) -> PyResult<()> {
-    let item_type = numpy_array.getattr("dtype")?.getattr("str")?.to_string();
     let shape = self.get_port_shape(name)?;
     let elem_size = shape[1];
     let bits = match self.design.module.get_port_bits(name) {
       Ok(bits) => bits,
       Err(err) => return Err(PyAttributeError::new_err(format!("{err}"))),
     };
-    match item_type.as_str() {
-      "|b1" => bits_to_bool_numpy(&bits, numpy_array),
-      "|u1" | "<V1" => bits_to_int_numpy::<u8>(&bits, elem_size, numpy_array),
-      "<u2" => bits_to_int_numpy::<u16>(&bits, elem_size, numpy_array),
-      "<u4" => bits_to_int_numpy::<u32>(&bits, elem_size, numpy_array),
-      "<u8" => bits_to_int_numpy::<u64>(&bits, elem_size, numpy_array),
-      "|i1" => bits_to_int_numpy::<i8>(&bits, elem_size, numpy_array),
-      "<i2" => bits_to_int_numpy::<i16>(&bits, elem_size, numpy_array),
-      "<i4" => bits_to_int_numpy::<i32>(&bits, elem_size, numpy_array),
-      "<i8" => bits_to_int_numpy::<i64>(&bits, elem_size, numpy_array),
-      // Cast f16 to u16
-      "<f2" => bits_to_int_numpy::<u16>(
-        &bits,
-        elem_size,
-        &numpy_array.call_method1("view", ("uint16",))?,
-      ),
-      // Cast f32 to u32
-      "<f4" => bits_to_int_numpy::<u32>(
-        &bits,
-        elem_size,
-        &numpy_array.call_method1("view", ("uint32",))?,
-      ),
-      _ => Err(PyValueError::new_err(format!(
-        "Unsupported item type: {item_type}"
-      ))),
-    }
+    bits_to_numpy(&bits, elem_size, numpy_array)
   }
 
   fn set_port_numpy(&mut self, name: &str, numpy_array: &Bound<'_, PyAny>) -> PyResult<()> {
-    let item_type = numpy_array.getattr("dtype")?.getattr("str")?.to_string();
     let shape = self.get_port_shape(name)?;
     let elem_size = shape[1];
+    let bits = numpy_to_bits(numpy_array, elem_size)?;
+    match self.design.module.set_port_bits(name, &bits) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
 
-    let bits = match item_type.as_str() {
-      "|b1" => bool_numpy_to_bits(numpy_array)?,
-      "|u1" => int_numpy_to_bits::<u8>(numpy_array, elem_size)?,
-      "<u2" => int_numpy_to_bits::<u16>(numpy_array, elem_size)?,
-      "<u4" => int_numpy_to_bits::<u32>(numpy_array, elem_size)?,
-      "<u8" => int_numpy_to_bits::<u64>(numpy_array, elem_size)?,
-      "|i1" => int_numpy_to_bits::<i8>(numpy_array, elem_size)?,
-      "<i2" => int_numpy_to_bits::<i16>(numpy_array, elem_size)?,
-      "<i4" => int_numpy_to_bits::<i32>(numpy_array, elem_size)?,
-      "<i8" => int_numpy_to_bits::<i64>(numpy_array, elem_size)?,
-      // Cast to raw uint8
-      "<V1" => int_numpy_to_bits::<u8>(&numpy_array.call_method1("view", ("uint8",))?, elem_size)?,
-      // Cast f16 to u16
-      "<f2" => {
-        int_numpy_to_bits::<u16>(&numpy_array.call_method1("view", ("uint16",))?, elem_size)?
-      }
-      // Cast f32 to u32
-      "<f4" => {
-        int_numpy_to_bits::<u32>(&numpy_array.call_method1("view", ("uint32",))?, elem_size)?
-      }
-      _ => {
-        return Err(PyValueError::new_err(format!(
-          "Unsupported item type: {item_type}"
-        )))
-      }
+  fn get_memory_shape(&self, name: &str) -> PyResult<[usize; 2]> {
+    match self.design.module.search_memory(name) {
+      Some(memory) => Ok([memory.size, memory.width]),
+      None => Err(PyAttributeError::new_err(format!(
+        "{}",
+        ModuleError::MissingMemory(name.to_string())
+      ))),
+    }
+  }
+
+  fn get_memory_numpy(&self, name: &str, numpy_array: &Bound<'_, PyAny>) -> PyResult<()> {
+    let (elem_size, bits) = match (
+      self.design.module.get_memory_width(name),
+      self.design.module.get_memory_bits(name),
+    ) {
+      (Ok(elem_size), Ok(bits)) => (elem_size, bits),
+      (Err(err), _) | (_, Err(err)) => return Err(PyAttributeError::new_err(format!("{err}"))),
     };
-    match self.design.module.set_port_bits(name, &bits) {
+    bits_to_numpy(&bits, elem_size, numpy_array)
+  }
+
+  fn set_memory_numpy(&mut self, name: &str, numpy_array: &Bound<'_, PyAny>) -> PyResult<()> {
+    let elem_size = match self.design.module.get_memory_width(name) {
+      Ok(elem_size) => elem_size,
+      Err(err) => return Err(PyAttributeError::new_err(format!("{err}"))),
+    };
+    let bits = numpy_to_bits(numpy_array, elem_size)?;
+    match self.design.module.set_memory_bits(name, &bits) {
       Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn load_memory_hex(&mut self, name: &str, path: &str) -> PyResult<()> {
+    match self.design.module.load_memory_hex(name, path) {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_memory_statistics(&self, name: &str) -> PyResult<HashMap<String, Vec<usize>>> {
+    match self.design.module.get_memory_statistics(name) {
+      Ok(stats) => Ok(HashMap::from([
+        ("reads".to_string(), stats.reads),
+        ("writes".to_string(), stats.writes),
+      ])),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_cell_statistics(&self, name: &str) -> PyResult<HashMap<String, usize>> {
+    match self.design.module.get_behavior_statistics(name) {
+      Ok(stats) => Ok(stats),
       Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
     }
   }
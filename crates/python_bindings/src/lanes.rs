@@ -0,0 +1,78 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use crate::design::PyDesign;
+use arbol::module::lanes::LaneDesign;
+use pyo3::exceptions::{PyAttributeError, PyException, PyValueError};
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+/// Bit-parallel copy of a design, simulates 64 stimuli per pass.
+#[pyclass(module = "arbolta", name = "LaneDesign")]
+pub struct PyLaneDesign {
+  design: LaneDesign,
+}
+
+#[pymethods]
+impl PyLaneDesign {
+  #[new]
+  fn __new__(design: &PyDesign) -> PyResult<Self> {
+    match LaneDesign::new(&design.design) {
+      Ok(design) => Ok(Self { design }),
+      Err(err) => Err(PyException::new_err(format!("{err}"))),
+    }
+  }
+
+  fn reset(&mut self) {
+    self.design.reset();
+  }
+
+  fn reset_clocked(&mut self) -> PyResult<()> {
+    match self.design.reset_clocked() {
+      Ok(()) => Ok(()),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  /// Element `i` of each input runs in lane `i % 64`, returns the outputs of each element.
+  #[pyo3(signature = (inputs, outputs, clocked=false))]
+  fn eval_batch(
+    &mut self,
+    inputs: HashMap<String, Vec<u64>>,
+    outputs: Vec<String>,
+    clocked: bool,
+  ) -> PyResult<HashMap<String, Vec<u64>>> {
+    let inputs: Vec<(&str, &[u64])> = inputs
+      .iter()
+      .map(|(name, vals)| (name.as_str(), vals.as_slice()))
+      .collect();
+    let output_names: Vec<&str> = outputs.iter().map(String::as_str).collect();
+    match self.design.eval_batch(&inputs, &output_names, clocked) {
+      Ok(results) => Ok(outputs.into_iter().zip(results).collect()),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_module_total_toggle_count(&mut self, name: &str) -> PyResult<usize> {
+    match self
+      .design
+      .flush_statistics()
+      .get_module_total_toggle_count(name)
+    {
+      Ok(count) => Ok(count),
+      Err(err) => Err(PyAttributeError::new_err(format!("{err}"))),
+    }
+  }
+
+  fn get_hierarchy_report_json(&mut self) -> PyResult<String> {
+    match self
+      .design
+      .flush_statistics()
+      .get_hierarchy_report()
+      .to_json()
+    {
+      Ok(json) => Ok(json),
+      Err(err) => Err(PyValueError::new_err(format!("{err}"))),
+    }
+  }
+}
@@ -5,12 +5,15 @@ extern crate arbolta as arbol;
 
 pub mod conversion;
 pub mod design;
+pub mod lanes;
+pub mod model;
 
 use pyo3::prelude::*;
 
 #[pymodule]
 fn arbolta(m: &Bound<'_, PyModule>) -> PyResult<()> {
   m.add_class::<design::PyDesign>()?;
+  m.add_class::<lanes::PyLaneDesign>()?;
 
   Ok(())
 }
@@ -0,0 +1,97 @@
+// Copyright (c) 2024 Advanced Micro Devices, Inc. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use crate::conversion::{bits_to_pyint, pyint_to_bits};
+use arbol::bit::BitVec;
+use arbol::module::hardware_module::{HardwareModule, ModuleError};
+use arbol::module::model::{ModelError, ModuleModel};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// Take the first error raised while evaluating `module`, see `model_error`.
+pub fn take_eval_error(module: &mut HardwareModule) -> PyResult<()> {
+  module.take_eval_error().map_err(model_error)
+}
+
+/// Convert a module error, exceptions raised by Python models are passed through as is.
+pub fn model_error(err: ModuleError) -> PyErr {
+  match err {
+    ModuleError::Model(_, err) => match err.downcast::<PyErr>() {
+      Ok(err) => *err,
+      Err(err) => PyRuntimeError::new_err(format!("{err}")),
+    },
+    err => PyRuntimeError::new_err(format!("{err}")),
+  }
+}
+
+/// Behavioral model implemented by a Python callable.
+/// The callable receives a dict of input port ints and returns a dict of output port ints.
+#[derive(Debug)]
+pub struct PyModel {
+  pub callable: Py<PyAny>,
+  /// First error raised by the callable since it was last taken.
+  pub error: Option<PyErr>,
+}
+
+impl Clone for PyModel {
+  fn clone(&self) -> Self {
+    Python::with_gil(|py| Self {
+      callable: self.callable.clone_ref(py),
+      error: None,
+    })
+  }
+}
+
+impl PyModel {
+  fn call(
+    &self,
+    py: Python<'_>,
+    inputs: &BTreeMap<String, BitVec>,
+    outputs: &mut BTreeMap<String, BitVec>,
+  ) -> PyResult<()> {
+    let py_inputs = PyDict::new(py);
+    for (name, bits) in inputs {
+      py_inputs.set_item(name, bits_to_pyint(py, bits)?)?;
+    }
+
+    let py_outputs = self.callable.bind(py).call1((py_inputs,))?;
+    for (name, bits) in outputs.iter_mut() {
+      // Ports missing from the returned dict hold their value
+      if let Ok(value) = py_outputs.get_item(name) {
+        *bits = pyint_to_bits(&value, bits.bits.len())?;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[typetag::serde]
+impl ModuleModel for PyModel {
+  fn eval(&mut self, inputs: &BTreeMap<String, BitVec>, outputs: &mut BTreeMap<String, BitVec>) {
+    Python::with_gil(|py| {
+      if let Err(err) = self.call(py, inputs, outputs) {
+        self.error.get_or_insert(err);
+      }
+    });
+  }
+
+  fn take_error(&mut self) -> Option<ModelError> {
+    self.error.take().map(|err| err.into())
+  }
+}
+
+/// Python callables can't be saved with a design.
+impl Serialize for PyModel {
+  fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+    Err(ser::Error::custom("Python models can't be serialized"))
+  }
+}
+
+impl<'de> Deserialize<'de> for PyModel {
+  fn deserialize<D: Deserializer<'de>>(_deserializer: D) -> Result<Self, D::Error> {
+    Err(de::Error::custom("Python models can't be deserialized"))
+  }
+}